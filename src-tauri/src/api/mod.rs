@@ -0,0 +1,4 @@
+mod routes;
+mod server;
+
+pub use server::*;
@@ -0,0 +1,220 @@
+use crate::error::{AppError, AppResult};
+use crate::reminder::{pause_end, pause_scheduler, refresh_scheduler, resume_scheduler, subscribe_triggers};
+use crate::storage::{self, CreateReminder, PauseState, Reminder, ReminderEvent, UpdateReminder};
+use axum::body::Bytes;
+use axum::extract::{FromRequest, Path, Query, Request};
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use chrono::Utc;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
+use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::{Stream, StreamExt};
+
+const OPENAPI: &str = include_str!("openapi.json");
+
+/// All routes, see `openapi.json` for the description
+pub fn router() -> Router {
+    let v1 = Router::new()
+        .route("/reminders", get(list_reminders).post(create_reminder))
+        .route(
+            "/reminders/{id}",
+            get(get_reminder).put(update_reminder).delete(delete_reminder),
+        )
+        .route("/history", get(get_history))
+        .route("/pause", get(get_pause_state).post(pause))
+        .route("/resume", post(resume))
+        .route("/events", get(events))
+        .route_layer(middleware::from_fn(require_token));
+
+    Router::new()
+        .nest("/v1", v1)
+        .route("/openapi.json", get(openapi))
+}
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            AppError::NotFound(_) => StatusCode::NOT_FOUND,
+            AppError::Validation { .. } => StatusCode::BAD_REQUEST,
+            AppError::Scheduler(_) => StatusCode::SERVICE_UNAVAILABLE,
+            AppError::Db(_) | AppError::Window(_) | AppError::Io(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        (status, Json(self)).into_response()
+    }
+}
+
+/// A JSON request body whose errors are reported like every other validation error
+struct Body<T>(T);
+
+impl<S: Send + Sync, T: DeserializeOwned> FromRequest<S> for Body<T> {
+    type Rejection = AppError;
+
+    async fn from_request(request: Request, state: &S) -> Result<Self, AppError> {
+        Json::<T>::from_request(request, state)
+            .await
+            .map(|Json(value)| Body(value))
+            .map_err(|e| AppError::validation("body", e.body_text()))
+    }
+}
+
+/// Runs storage calls off the async workers
+async fn blocking<T, F>(f: F) -> AppResult<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> AppResult<T> + Send + 'static,
+{
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| AppError::Io(e.to_string()))?
+}
+
+/// Accepts the token as `Authorization: Bearer <token>`, or as `?token=` for
+/// clients like `EventSource` that can't set headers
+async fn require_token(request: Request, next: Next) -> Response {
+    let header = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+    let query = request
+        .uri()
+        .query()
+        .and_then(|q| q.split('&').find_map(|pair| pair.strip_prefix("token=")));
+    let given = header.or(query).unwrap_or_default().trim().to_string();
+
+    let expected = match tokio::task::spawn_blocking(storage::get_api_token).await {
+        Ok(Ok(token)) => token,
+        Ok(Err(e)) => return e.into_response(),
+        Err(e) => return AppError::Io(e.to_string()).into_response(),
+    };
+
+    if given.is_empty() || !constant_time_eq(given.as_bytes(), expected.as_bytes()) {
+        let body = serde_json::json!({
+            "kind": "unauthorized",
+            "message": "Missing or invalid API token",
+            "field": null,
+        });
+        return (StatusCode::UNAUTHORIZED, Json(body)).into_response();
+    }
+    next.run(request).await
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+async fn openapi() -> impl IntoResponse {
+    ([(header::CONTENT_TYPE, "application/json")], OPENAPI)
+}
+
+async fn list_reminders() -> AppResult<Json<Vec<Reminder>>> {
+    blocking(storage::get_all_reminders).await.map(Json)
+}
+
+async fn get_reminder(Path(id): Path<i64>) -> AppResult<Json<Reminder>> {
+    blocking(move || {
+        storage::get_reminder_by_id(id)?.ok_or_else(|| AppError::NotFound(format!("Reminder {}", id)))
+    })
+    .await
+    .map(Json)
+}
+
+/// A token for the API shouldn't be enough to run programs, so commands can
+/// only be switched on in the app. Leaving an enabled one unchanged is fine.
+fn check_command(existing: Option<&Reminder>, reminder: &CreateReminder) -> AppResult<()> {
+    let Some(command) = reminder.command.as_ref().filter(|c| c.enabled) else {
+        return Ok(());
+    };
+    let unchanged = existing
+        .and_then(|r| r.command.as_ref())
+        .is_some_and(|c| c.enabled && c.program == command.program && c.args == command.args);
+    if unchanged {
+        Ok(())
+    } else {
+        Err(AppError::validation("command", "Commands can only be enabled in the app"))
+    }
+}
+
+async fn create_reminder(Body(reminder): Body<CreateReminder>) -> AppResult<(StatusCode, Json<Reminder>)> {
+    check_command(None, &reminder)?;
+    let created = blocking(move || storage::create_reminder(reminder)).await?;
+    refresh_scheduler();
+    Ok((StatusCode::CREATED, Json(created)))
+}
+
+/// Replaces the whole definition, the runtime state (last trigger, snoozes)
+/// is kept
+async fn update_reminder(Path(id): Path<i64>, Body(reminder): Body<CreateReminder>) -> AppResult<Json<Reminder>> {
+    let updated = blocking(move || {
+        let existing = storage::get_reminder_by_id(id)?;
+        check_command(existing.as_ref(), &reminder)?;
+        storage::update_reminder(UpdateReminder::new(id, reminder))
+    })
+    .await?;
+    refresh_scheduler();
+    Ok(Json(updated))
+}
+
+async fn delete_reminder(Path(id): Path<i64>) -> AppResult<StatusCode> {
+    blocking(move || storage::delete_reminder(id)).await?;
+    refresh_scheduler();
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Debug, Deserialize)]
+struct HistoryQuery {
+    reminder_id: Option<i64>,
+    from: Option<String>,
+    to: Option<String>,
+}
+
+async fn get_history(Query(query): Query<HistoryQuery>) -> AppResult<Json<Vec<ReminderEvent>>> {
+    blocking(move || storage::get_events(query.reminder_id, query.from.as_deref(), query.to.as_deref()))
+        .await
+        .map(Json)
+}
+
+async fn get_pause_state() -> AppResult<Json<PauseState>> {
+    blocking(storage::get_pause_state).await.map(Json)
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct PauseRequest {
+    /// Minutes until the pause ends by itself, `None` pauses indefinitely
+    minutes: Option<i64>,
+}
+
+async fn pause(body: Bytes) -> AppResult<StatusCode> {
+    // No body at all pauses indefinitely, like `{}`
+    let PauseRequest { minutes } = if body.is_empty() {
+        PauseRequest::default()
+    } else {
+        serde_json::from_slice(&body).map_err(|e| AppError::validation("body", e.to_string()))?
+    };
+    pause_scheduler(pause_end(minutes, Utc::now())?)?;
+    Ok(StatusCode::ACCEPTED)
+}
+
+async fn resume() -> AppResult<StatusCode> {
+    resume_scheduler()?;
+    Ok(StatusCode::ACCEPTED)
+}
+
+/// `reminder-triggered` events with the reminder as data
+async fn events() -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let stream = BroadcastStream::new(subscribe_triggers()).filter_map(|reminder| {
+        let reminder = reminder.ok()?;
+        Event::default()
+            .event("reminder-triggered")
+            .json_data(&reminder)
+            .ok()
+            .map(Ok)
+    });
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
@@ -0,0 +1,48 @@
+use super::routes::router;
+use crate::error::{AppError, AppResult};
+use crate::storage::Settings;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use std::net::Ipv4Addr;
+use tauri::async_runtime::JoinHandle;
+use tokio::net::TcpListener;
+
+static SERVER: Lazy<Mutex<Option<RunningServer>>> = Lazy::new(|| Mutex::new(None));
+
+struct RunningServer {
+    port: u16,
+    task: JoinHandle<()>,
+}
+
+/// Starts, restarts or stops the HTTP API to match the settings
+pub fn apply_api_settings(settings: &Settings) {
+    let mut server = SERVER.lock();
+    if settings.api_enabled && server.as_ref().is_some_and(|s| s.port == settings.api_port) {
+        return;
+    }
+
+    // Dropping the aborted task closes its listener
+    if let Some(running) = server.take() {
+        running.task.abort();
+    }
+
+    if settings.api_enabled {
+        let port = settings.api_port;
+        let task = tauri::async_runtime::spawn(async move {
+            if let Err(e) = serve(port).await {
+                eprintln!("HTTP API stopped: {}", e);
+            }
+        });
+        *server = Some(RunningServer { port, task });
+    }
+}
+
+/// Serves the API on 127.0.0.1 only, it is meant for tools on this machine
+pub async fn serve(port: u16) -> AppResult<()> {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))
+        .await
+        .map_err(|e| AppError::Io(format!("Failed to listen on port {}: {}", port, e)))?;
+    axum::serve(listener, router())
+        .await
+        .map_err(|e| AppError::Io(format!("HTTP API: {}", e)))
+}
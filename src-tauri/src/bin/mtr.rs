@@ -0,0 +1,754 @@
+//! `mtr` - manage reminders from the command line.
+//!
+//! Works on the same database as the app and asks a running instance to
+//! reload its reminders after every change. `pause`, `resume`, `trigger` and
+//! `status` talk to the running app over its control socket.
+
+use chrono::{DateTime, Local, Utc};
+use chrono_tz::Tz;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use multitask_reminder_lib::control;
+use multitask_reminder_lib::error::{AppError, AppResult};
+use multitask_reminder_lib::reminder::{self, parse_when, PlannedTrigger, SchedulerStatus};
+use multitask_reminder_lib::storage::{
+    self, CatchUpPolicy, CreateReminder, DeliveryStyle, EscalationAction, EscalationStep, Reminder, ReminderCommand,
+    Schedule, UpdateReminder,
+};
+use multitask_reminder_lib::transfer;
+use serde::Serialize;
+use serde_json::json;
+use std::path::Path;
+use std::process::ExitCode;
+
+#[derive(Parser)]
+#[command(name = "mtr", version, about = "Manage MultiTask Reminder from the command line")]
+struct Cli {
+    /// Print machine-readable JSON instead of text
+    #[arg(long, global = true)]
+    json: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// List all reminders
+    List,
+    /// Add a reminder
+    Add {
+        name: String,
+        #[command(flatten)]
+        options: ReminderOptions,
+    },
+    /// Change a reminder, leaving everything not given as it is
+    Edit {
+        id: i64,
+        /// New name
+        #[arg(long)]
+        name: Option<String>,
+        #[command(flatten)]
+        options: ReminderOptions,
+    },
+    /// Delete reminders
+    Rm {
+        #[arg(required = true)]
+        ids: Vec<i64>,
+    },
+    /// Enable reminders
+    Enable {
+        #[arg(required = true)]
+        ids: Vec<i64>,
+    },
+    /// Disable reminders
+    Disable {
+        #[arg(required = true)]
+        ids: Vec<i64>,
+    },
+    /// Snooze a reminder
+    Snooze {
+        id: i64,
+        /// Minutes until it fires again
+        #[arg(default_value_t = 5)]
+        minutes: i32,
+    },
+    /// Show triggers, dismissals and snoozes
+    History {
+        /// Only this reminder
+        #[arg(long)]
+        id: Option<i64>,
+        /// Start of the range (RFC 3339)
+        #[arg(long)]
+        from: Option<String>,
+        /// End of the range (RFC 3339)
+        #[arg(long)]
+        to: Option<String>,
+        /// Only the most recent events
+        #[arg(long)]
+        limit: Option<usize>,
+    },
+    /// Write reminders to a file, or to standard output with `-`
+    Export {
+        path: String,
+        #[arg(long, value_enum, default_value_t = ExportFormat::Json)]
+        format: ExportFormat,
+        /// Only these reminders (iCalendar only)
+        #[arg(long = "id")]
+        ids: Vec<i64>,
+    },
+    /// Pause all reminders in the running app
+    Pause {
+        /// Resume automatically after this many minutes
+        minutes: Option<i64>,
+    },
+    /// Resume paused reminders in the running app
+    Resume,
+    /// Show a reminder right away
+    Trigger { id: i64 },
+    /// Show what the running app will do next
+    Status,
+    /// Show when reminders fire next
+    Next {
+        /// Only this reminder
+        id: Option<i64>,
+        /// How many occurrences to show
+        #[arg(long, short = 'n', default_value_t = reminder::DEFAULT_UPCOMING_TRIGGERS)]
+        count: usize,
+        /// Stop at this time, e.g. "tomorrow 9am" or "in 3 hours"
+        #[arg(long)]
+        until: Option<String>,
+    },
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ExportFormat {
+    Json,
+    Ics,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Delivery {
+    Popup,
+    Notification,
+    NotificationThenPopup,
+    Silent,
+}
+
+/// Minutes until the popup follows the notification, unless given
+const DEFAULT_POPUP_AFTER_MINUTES: u32 = 10;
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum CatchUp {
+    Skip,
+    Once,
+    Each,
+}
+
+/// Missed occurrences fired with `--catch-up each`, unless given
+const DEFAULT_CATCH_UP_MAX: u32 = 5;
+
+#[derive(Args)]
+struct ReminderOptions {
+    /// Text shown in the popup
+    #[arg(long)]
+    message: Option<String>,
+    /// Remove the message
+    #[arg(long, conflicts_with = "message")]
+    no_message: bool,
+    /// Repeat every N minutes
+    #[arg(long, value_name = "MINUTES", group = "schedule")]
+    every: Option<i32>,
+    /// Fire at fixed times of day, e.g. 09:00,13:30
+    #[arg(long, value_name = "HH:MM,...", value_delimiter = ',', group = "schedule")]
+    at: Vec<String>,
+    /// Fire according to a cron expression
+    #[arg(long, group = "schedule")]
+    cron: Option<String>,
+    /// Fire according to an RFC 5545 recurrence rule
+    #[arg(long, group = "schedule")]
+    rrule: Option<String>,
+    /// Fire once, e.g. "in 20 minutes", "tomorrow 9am" or "2026-11-03 15:00"
+    #[arg(long, group = "schedule")]
+    when: Option<String>,
+    /// Only fire between these times, e.g. 09:00-17:00
+    #[arg(long, value_name = "HH:MM-HH:MM")]
+    window: Option<String>,
+    /// Remove the time window
+    #[arg(long, conflicts_with = "window")]
+    no_window: bool,
+    /// Only fire on these days, e.g. mon,tue,fri or 0,1,4
+    #[arg(long, value_delimiter = ',')]
+    days: Vec<String>,
+    /// Fire on every day
+    #[arg(long, conflicts_with = "days")]
+    all_days: bool,
+    /// Read times of day in this zone, e.g. Europe/Berlin
+    #[arg(long, value_name = "ZONE")]
+    timezone: Option<String>,
+    /// Use the default zone from the settings
+    #[arg(long, conflicts_with = "timezone")]
+    no_timezone: bool,
+    #[arg(long)]
+    sound: Option<String>,
+    /// Run this program when the reminder fires, with REMINDER_ID,
+    /// REMINDER_NAME and REMINDER_MESSAGE in its environment
+    #[arg(long, value_name = "PROGRAM")]
+    command: Option<String>,
+    /// Argument for the program, repeat for more
+    #[arg(long, value_name = "ARG", allow_hyphen_values = true, requires = "command")]
+    command_arg: Vec<String>,
+    /// Kill the program after this many seconds
+    #[arg(long, value_name = "SECONDS")]
+    command_timeout: Option<u32>,
+    /// Stop running a program
+    #[arg(long, conflicts_with_all = ["command", "command_timeout"])]
+    no_command: bool,
+    /// How the reminder shows up
+    #[arg(long, value_enum)]
+    delivery: Option<Delivery>,
+    /// Minutes until the popup follows an unanswered notification
+    #[arg(long, value_name = "MINUTES")]
+    popup_after: Option<u32>,
+    /// What happens while the reminder stays unanswered, replacing all steps,
+    /// e.g. 5:replay, 10:refocus, 15:sound=alert or 30:webhook
+    #[arg(long, value_name = "MINUTES:ACTION")]
+    escalate: Vec<String>,
+    /// Remove all escalation steps
+    #[arg(long, conflicts_with = "escalate")]
+    no_escalation: bool,
+    /// What happens to occurrences missed while the app was closed
+    #[arg(long, value_enum)]
+    catch_up: Option<CatchUp>,
+    /// Most missed occurrences fired with --catch-up each
+    #[arg(long, value_name = "COUNT")]
+    catch_up_max: Option<u32>,
+    /// Create the reminder disabled
+    #[arg(long)]
+    disabled: bool,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    match run(cli.command, cli.json) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            if cli.json {
+                eprintln!("{}", serde_json::to_string(&e).unwrap_or_else(|_| e.to_string()));
+            } else {
+                eprintln!("error: {}", e);
+            }
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(command: Command, json: bool) -> AppResult<()> {
+    match command {
+        Command::List => {
+            let reminders = storage::get_all_reminders()?;
+            if json {
+                return print_json(&reminders);
+            }
+            for reminder in reminders.iter().rev() {
+                print_reminder(reminder);
+            }
+        }
+        Command::Add { name, options } => {
+            let mut reminder = CreateReminder {
+                name,
+                message: None,
+                interval_minutes: 60,
+                schedule: Schedule::Interval,
+                due_at: None,
+                enabled: !options.disabled,
+                active_start_time: None,
+                active_end_time: None,
+                active_days: None,
+                sound: "chime".to_string(),
+                command: None,
+                delivery_style: Default::default(),
+                escalation: Vec::new(),
+                catch_up: Default::default(),
+                timezone: None,
+            };
+            options.apply(&mut reminder, default_timezone()?)?;
+            let created = storage::create_reminder(reminder)?;
+            changed();
+            output(json, &created, print_reminder)?;
+        }
+        Command::Edit { id, name, options } => {
+            let current = find(id)?;
+            let mut reminder = CreateReminder::from(&current);
+            if let Some(name) = name {
+                reminder.name = name;
+            }
+            options.apply(&mut reminder, default_timezone()?)?;
+            let updated = storage::update_reminder(UpdateReminder::new(id, reminder))?;
+            changed();
+            output(json, &updated, print_reminder)?;
+        }
+        Command::Rm { ids } => {
+            for id in &ids {
+                // Report unknown ids instead of silently deleting nothing
+                find(*id)?;
+                storage::delete_reminder(*id)?;
+            }
+            changed();
+            output(json, &ids, |ids| println!("Deleted {}", join_ids(ids)))?;
+        }
+        Command::Enable { ids } => set_enabled(&ids, true, json)?,
+        Command::Disable { ids } => set_enabled(&ids, false, json)?,
+        Command::Snooze { id, minutes } => {
+            let snoozed = storage::snooze_reminder(id, minutes)?;
+            changed();
+            output(json, &snoozed, |r| {
+                println!("Snoozed '{}' until {}", r.name, format_time(r.snoozed_until.as_deref()))
+            })?;
+        }
+        Command::History { id, from, to, limit } => {
+            let mut events = storage::get_events(id, from.as_deref(), to.as_deref())?;
+            if let Some(limit) = limit {
+                events.drain(..events.len().saturating_sub(limit));
+            }
+            if json {
+                return print_json(&events);
+            }
+            let names: std::collections::HashMap<i64, String> = storage::get_all_reminders()?
+                .into_iter()
+                .map(|r| (r.id, r.name))
+                .collect();
+            for event in events {
+                let name = names.get(&event.reminder_id).map(String::as_str).unwrap_or("(deleted)");
+                println!(
+                    "{}  {:<10} #{} {}",
+                    format_time(Some(&event.occurred_at)),
+                    event.kind.as_str(),
+                    event.reminder_id,
+                    name
+                );
+            }
+        }
+        Command::Export { path, format, ids } => export(&path, format, ids, json)?,
+        Command::Pause { minutes } => {
+            control::call("pause", json!({ "minutes": minutes }))?;
+            let until = minutes.map(|m| format!(" for {} minutes", m)).unwrap_or_default();
+            output(json, &minutes, |_| println!("Paused{}", until))?;
+        }
+        Command::Resume => {
+            control::call("resume", serde_json::Value::Null)?;
+            output(json, &(), |_| println!("Resumed"))?;
+        }
+        Command::Trigger { id } => {
+            control::call("trigger_now", json!({ "id": id }))?;
+            output(json, &id, |id| println!("Triggered #{}", id))?;
+        }
+        Command::Status => {
+            let status: SchedulerStatus = serde_json::from_value(control::call("status", serde_json::Value::Null)?)
+                .map_err(|e| AppError::Io(format!("Invalid status from the app: {}", e)))?;
+            output(json, &status, print_status)?;
+        }
+        Command::Next { id, count, until } => {
+            let until = until
+                .map(|until| parse_when(&until, &Utc::now().with_timezone(&default_timezone()?)).map_err(|reason| AppError::validation("until", reason)))
+                .transpose()?;
+            let upcoming = reminder::get_upcoming_triggers(id, count, until.map(|u| u.with_timezone(&Utc)))?;
+            output(json, &upcoming, |upcoming| print_upcoming(upcoming))?;
+        }
+    }
+    Ok(())
+}
+
+impl ReminderOptions {
+    /// `tz` is the zone times like "tomorrow 9am" are read in
+    fn apply(self, reminder: &mut CreateReminder, tz: Tz) -> AppResult<()> {
+        if let Some(message) = self.message {
+            reminder.message = Some(message);
+        }
+        if self.no_message {
+            reminder.message = None;
+        }
+
+        // Choosing any schedule turns a one-shot reminder into a repeating one
+        let schedule = if let Some(minutes) = self.every {
+            reminder.interval_minutes = minutes;
+            Some(Schedule::Interval)
+        } else if !self.at.is_empty() {
+            Some(Schedule::DailyTimes { times: self.at })
+        } else if let Some(expression) = self.cron {
+            Some(Schedule::Cron { expression })
+        } else {
+            self.rrule.map(|rule| Schedule::Rrule { rule })
+        };
+        if let Some(schedule) = schedule {
+            reminder.schedule = schedule;
+            reminder.due_at = None;
+        }
+        if let Some(when) = self.when {
+            let due = parse_when(&when, &Utc::now().with_timezone(&tz))
+                .map_err(|reason| AppError::validation("when", reason))?;
+            if due <= Local::now() {
+                return Err(AppError::validation("when", format!("'{}' is in the past", when)));
+            }
+            reminder.schedule = Schedule::Interval;
+            reminder.due_at = Some(due.with_timezone(&Utc).to_rfc3339());
+        }
+
+        if let Some(window) = self.window {
+            let (start, end) = window.split_once('-').ok_or_else(|| {
+                AppError::validation("window", format!("Invalid window '{}', expected HH:MM-HH:MM", window))
+            })?;
+            reminder.active_start_time = Some(start.trim().to_string());
+            reminder.active_end_time = Some(end.trim().to_string());
+        }
+        if self.no_window {
+            reminder.active_start_time = None;
+            reminder.active_end_time = None;
+        }
+
+        if !self.days.is_empty() {
+            reminder.active_days = Some(self.days.iter().map(|d| parse_day(d)).collect::<AppResult<_>>()?);
+        }
+        if self.all_days {
+            reminder.active_days = None;
+        }
+        if let Some(timezone) = self.timezone {
+            reminder.timezone = Some(timezone);
+        }
+        if self.no_timezone {
+            reminder.timezone = None;
+        }
+
+        if let Some(sound) = self.sound {
+            reminder.sound = sound;
+        }
+        if let Some(program) = self.command {
+            reminder.command = Some(ReminderCommand {
+                program,
+                args: self.command_arg,
+                timeout_seconds: reminder.command.as_ref().map_or(storage::DEFAULT_COMMAND_TIMEOUT_SECONDS, |c| c.timeout_seconds),
+                enabled: true,
+            });
+        }
+        if let Some(seconds) = self.command_timeout {
+            let command = reminder
+                .command
+                .as_mut()
+                .ok_or_else(|| AppError::validation("command", "The reminder has no command"))?;
+            command.timeout_seconds = seconds;
+        }
+        if self.no_command {
+            reminder.command = None;
+        }
+
+        let popup_after = match reminder.delivery_style {
+            DeliveryStyle::NotificationThenPopup { minutes } => minutes,
+            _ => DEFAULT_POPUP_AFTER_MINUTES,
+        };
+        if let Some(delivery) = self.delivery {
+            reminder.delivery_style = match delivery {
+                Delivery::Popup => DeliveryStyle::Popup,
+                Delivery::Notification => DeliveryStyle::Notification,
+                Delivery::NotificationThenPopup => DeliveryStyle::NotificationThenPopup { minutes: popup_after },
+                Delivery::Silent => DeliveryStyle::Silent,
+            };
+        }
+        if let Some(minutes) = self.popup_after {
+            let DeliveryStyle::NotificationThenPopup { minutes: current } = &mut reminder.delivery_style else {
+                return Err(AppError::validation(
+                    "delivery_style",
+                    "--popup-after needs --delivery notification-then-popup",
+                ));
+            };
+            *current = minutes;
+        }
+        if !self.escalate.is_empty() {
+            reminder.escalation = self.escalate.iter().map(|s| parse_escalation(s)).collect::<AppResult<_>>()?;
+        }
+        if self.no_escalation {
+            reminder.escalation.clear();
+        }
+
+        let catch_up_max = match reminder.catch_up {
+            CatchUpPolicy::FireEach { max } => max,
+            _ => DEFAULT_CATCH_UP_MAX,
+        };
+        if let Some(catch_up) = self.catch_up {
+            reminder.catch_up = match catch_up {
+                CatchUp::Skip => CatchUpPolicy::Skip,
+                CatchUp::Once => CatchUpPolicy::FireOnce,
+                CatchUp::Each => CatchUpPolicy::FireEach { max: catch_up_max },
+            };
+        }
+        if let Some(count) = self.catch_up_max {
+            let CatchUpPolicy::FireEach { max } = &mut reminder.catch_up else {
+                return Err(AppError::validation("catch_up", "--catch-up-max needs --catch-up each"));
+            };
+            *max = count;
+        }
+        if self.disabled {
+            reminder.enabled = false;
+        }
+        Ok(())
+    }
+}
+
+/// Monday = 0, accepting day names, their first three letters and numbers
+fn parse_day(value: &str) -> AppResult<u8> {
+    const NAMES: [&str; 7] = ["monday", "tuesday", "wednesday", "thursday", "friday", "saturday", "sunday"];
+    let lower = value.trim().to_lowercase();
+    NAMES
+        .iter()
+        .position(|name| lower == *name || lower == name[..3])
+        .map(|i| i as u8)
+        .or_else(|| lower.parse::<u8>().ok())
+        .ok_or_else(|| AppError::validation("active_days", format!("Unknown day '{}'", value)))
+}
+
+/// `MINUTES:ACTION` with the actions replay, refocus, sound=NAME and webhook
+fn parse_escalation(value: &str) -> AppResult<EscalationStep> {
+    let invalid = || AppError::validation("escalation", format!("Invalid escalation step '{}'", value));
+    let (minutes, action) = value.split_once(':').ok_or_else(invalid)?;
+    let after_minutes = minutes.trim().parse().map_err(|_| invalid())?;
+    let action = match action.trim() {
+        "replay" => EscalationAction::ReplaySound,
+        "refocus" => EscalationAction::Refocus,
+        "webhook" => EscalationAction::Webhook,
+        other => match other.strip_prefix("sound=") {
+            Some(sound) => EscalationAction::ChangeSound { sound: sound.to_string() },
+            None => return Err(invalid()),
+        },
+    };
+    Ok(EscalationStep { after_minutes, action })
+}
+
+/// The zone from the settings the app reads times of day in
+fn default_timezone() -> AppResult<Tz> {
+    Ok(reminder::default_timezone(&storage::get_settings()?).unwrap_or_else(reminder::system_timezone))
+}
+
+fn find(id: i64) -> AppResult<Reminder> {
+    storage::get_reminder_by_id(id)?.ok_or_else(|| AppError::NotFound(format!("Reminder {}", id)))
+}
+
+fn set_enabled(ids: &[i64], enabled: bool, json: bool) -> AppResult<()> {
+    let mut reminders = Vec::new();
+    for id in ids {
+        reminders.push(storage::toggle_reminder(*id, enabled)?);
+    }
+    changed();
+    output(json, &reminders, |reminders| reminders.iter().for_each(print_reminder))
+}
+
+fn export(path: &str, format: ExportFormat, ids: Vec<i64>, json: bool) -> AppResult<()> {
+    let ids = (!ids.is_empty()).then_some(ids);
+    if path == "-" {
+        match format {
+            ExportFormat::Json => print_json(&transfer::export_document()?)?,
+            ExportFormat::Ics => {
+                let reminders: Vec<Reminder> = storage::get_all_reminders()?
+                    .into_iter()
+                    .rev()
+                    .filter(|r| ids.as_ref().map_or(r.enabled, |ids| ids.contains(&r.id)))
+                    .collect();
+                let (calendar, report) = transfer::build_calendar(&reminders, Utc::now(), default_timezone()?);
+                print!("{}", calendar.to_ics());
+                print_skipped(&report.skipped);
+            }
+        }
+        return Ok(());
+    }
+
+    match format {
+        ExportFormat::Json => {
+            let count = transfer::export_reminders(Path::new(path))?;
+            output(json, &count, |count| println!("Exported {} reminders to {}", count, path))
+        }
+        ExportFormat::Ics => {
+            let report = transfer::export_ics(Path::new(path), ids.as_deref())?;
+            output(json, &report, |report| {
+                println!("Exported {} reminders to {}", report.exported, path);
+                print_skipped(&report.skipped);
+            })
+        }
+    }
+}
+
+/// Lets a running app pick up the change
+fn changed() {
+    control::notify_refresh();
+}
+
+fn output<T: Serialize>(json: bool, value: &T, text: impl FnOnce(&T)) -> AppResult<()> {
+    if json {
+        print_json(value)
+    } else {
+        text(value);
+        Ok(())
+    }
+}
+
+fn print_json<T: Serialize>(value: &T) -> AppResult<()> {
+    let json = serde_json::to_string_pretty(value).map_err(|e| AppError::Io(e.to_string()))?;
+    println!("{}", json);
+    Ok(())
+}
+
+fn print_reminder(reminder: &Reminder) {
+    println!(
+        "#{:<4} {}  {}  ({})",
+        reminder.id,
+        if reminder.enabled { "on " } else { "off" },
+        reminder.name,
+        describe_schedule(reminder)
+    );
+}
+
+fn print_status(status: &SchedulerStatus) {
+    if !status.running {
+        println!("The scheduler is not running");
+        return;
+    }
+    if status.pause.paused {
+        match status.pause.until.as_deref() {
+            Some(until) => println!("Paused until {}", format_time(Some(until))),
+            None => println!("Paused indefinitely"),
+        }
+    }
+    println!("{} enabled reminders", status.enabled_reminders);
+    if status.pending_reminders > 0 {
+        println!("{} waiting to be dismissed or snoozed", status.pending_reminders);
+    }
+    for trigger in &status.next_triggers {
+        println!("{}  #{} {}", format_time(Some(&trigger.at)), trigger.reminder_id, trigger.name);
+    }
+}
+
+fn print_upcoming(upcoming: &[PlannedTrigger]) {
+    if upcoming.is_empty() {
+        println!("Nothing scheduled");
+    }
+    for trigger in upcoming {
+        println!("{}  #{} {}", format_time(Some(&trigger.at)), trigger.reminder_id, trigger.name);
+    }
+}
+
+fn print_skipped(skipped: &[transfer::SkippedReminder]) {
+    for s in skipped {
+        eprintln!("skipped '{}': {}", s.name, s.reason);
+    }
+}
+
+fn describe_schedule(reminder: &Reminder) -> String {
+    let mut description = match (&reminder.due_at, &reminder.schedule) {
+        (Some(due), _) => format!("once at {}", format_time(Some(due))),
+        (None, Schedule::Interval) => format!("every {} min", reminder.interval_minutes),
+        (None, Schedule::DailyTimes { times }) => format!("daily at {}", times.join(", ")),
+        (None, Schedule::Cron { expression }) => format!("cron {}", expression),
+        (None, Schedule::Rrule { rule }) => rule.replace('\n', " "),
+    };
+    if let (Some(start), Some(end)) = (&reminder.active_start_time, &reminder.active_end_time) {
+        description.push_str(&format!(", {}-{}", start, end));
+    }
+    if let Some(days) = &reminder.active_days {
+        const NAMES: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+        let days: Vec<&str> = days.iter().filter_map(|d| NAMES.get(*d as usize).copied()).collect();
+        description.push_str(&format!(", {}", days.join(" ")));
+    }
+    if let Some(timezone) = &reminder.timezone {
+        description.push_str(&format!(", {}", timezone));
+    }
+    description
+}
+
+/// An RFC 3339 timestamp in local time
+fn format_time(value: Option<&str>) -> String {
+    value
+        .and_then(|v| DateTime::parse_from_rfc3339(v).ok())
+        .map(|t| t.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_else(|| "-".to_string())
+}
+
+fn join_ids(ids: &[i64]) -> String {
+    ids.iter().map(|id| format!("#{}", id)).collect::<Vec<_>>().join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The reminder after applying the options of `mtr edit 1 ARGS...`
+    fn edited(args: &[&str]) -> AppResult<CreateReminder> {
+        let cli = Cli::try_parse_from(["mtr", "edit", "1"].iter().chain(args)).unwrap();
+        let Command::Edit { options, .. } = cli.command else {
+            unreachable!();
+        };
+        let mut reminder = CreateReminder {
+            name: "Stretch".to_string(),
+            message: None,
+            interval_minutes: 60,
+            schedule: Schedule::Interval,
+            due_at: None,
+            enabled: true,
+            active_start_time: None,
+            active_end_time: None,
+            active_days: None,
+            sound: "chime".to_string(),
+            command: None,
+            delivery_style: Default::default(),
+            escalation: Vec::new(),
+            catch_up: Default::default(),
+            timezone: None,
+        };
+        options.apply(&mut reminder, chrono_tz::UTC)?;
+        Ok(reminder)
+    }
+
+    #[test]
+    fn parses_days() {
+        assert_eq!(parse_day("mon").unwrap(), 0);
+        assert_eq!(parse_day(" Sunday ").unwrap(), 6);
+        assert_eq!(parse_day("WED").unwrap(), 2);
+        assert_eq!(parse_day("4").unwrap(), 4);
+        for value in ["monkey", "sunshine", "tues", "m", ""] {
+            assert!(parse_day(value).is_err(), "{}", value);
+        }
+    }
+
+    #[test]
+    fn parses_escalation_steps() {
+        assert_eq!(
+            parse_escalation("5:replay").unwrap(),
+            EscalationStep { after_minutes: 5, action: EscalationAction::ReplaySound }
+        );
+        assert_eq!(parse_escalation(" 10 : refocus").unwrap().action, EscalationAction::Refocus);
+        assert_eq!(parse_escalation("30:webhook").unwrap().action, EscalationAction::Webhook);
+        assert_eq!(
+            parse_escalation("15:sound=alert").unwrap().action,
+            EscalationAction::ChangeSound { sound: "alert".to_string() }
+        );
+        for value in ["replay", "x:replay", "-5:replay", "5:explode", "5:"] {
+            assert!(parse_escalation(value).is_err(), "{}", value);
+        }
+    }
+
+    #[test]
+    fn popup_after_needs_notification_then_popup() {
+        let error = edited(&["--popup-after", "5"]).unwrap_err();
+        assert_eq!(error.field(), Some("delivery_style"));
+
+        let reminder = edited(&["--delivery", "notification-then-popup", "--popup-after", "5"]).unwrap();
+        assert_eq!(reminder.delivery_style, DeliveryStyle::NotificationThenPopup { minutes: 5 });
+    }
+
+    #[test]
+    fn catch_up_max_needs_catch_up_each() {
+        let error = edited(&["--catch-up-max", "3"]).unwrap_err();
+        assert_eq!(error.field(), Some("catch_up"));
+        let error = edited(&["--catch-up", "once", "--catch-up-max", "3"]).unwrap_err();
+        assert_eq!(error.field(), Some("catch_up"));
+
+        let reminder = edited(&["--catch-up", "each", "--catch-up-max", "3"]).unwrap();
+        assert_eq!(reminder.catch_up, CatchUpPolicy::FireEach { max: 3 });
+    }
+}
@@ -0,0 +1,5 @@
+mod rpc;
+mod socket;
+
+pub use rpc::*;
+pub use socket::*;
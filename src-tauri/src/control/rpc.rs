@@ -0,0 +1,279 @@
+use crate::error::{AppError, AppResult};
+use crate::reminder::refresh_scheduler;
+use crate::stats::StatsRange;
+use crate::storage::{CreateReminder, CreateWebhook, Settings, UpdateReminder, UpdateWebhook};
+use crate::transfer::ImportMode;
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+/// Errors raised by the app itself, with the `AppError` as data
+const APP_ERROR: i64 = -32000;
+
+/// A JSON-RPC 2.0 request, one per line on the control socket
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Request {
+    pub jsonrpc: String,
+    #[serde(default)]
+    pub id: Value,
+    pub method: String,
+    #[serde(default)]
+    pub params: Value,
+}
+
+impl Request {
+    pub fn new(id: u64, method: &str, params: Value) -> Self {
+        Self {
+            jsonrpc: "2.0".to_string(),
+            id: Value::from(id),
+            method: method.to_string(),
+            params,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Response {
+    pub jsonrpc: String,
+    pub id: Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<RpcError>,
+}
+
+impl Response {
+    fn success(id: Value, result: Value) -> Self {
+        Self {
+            jsonrpc: "2.0".to_string(),
+            id,
+            result: Some(result),
+            error: None,
+        }
+    }
+
+    fn failure(id: Value, code: i64, message: String, data: Option<Value>) -> Self {
+        Self {
+            jsonrpc: "2.0".to_string(),
+            id,
+            result: None,
+            error: Some(RpcError { code, message, data }),
+        }
+    }
+
+    /// The result, or the error the other side reported
+    pub fn into_result(self) -> AppResult<Value> {
+        match self.error {
+            Some(error) => Err(AppError::Io(format!("The app reported an error: {}", error.message))),
+            None => Ok(self.result.unwrap_or(Value::Null)),
+        }
+    }
+}
+
+/// Handles one request line and returns the response to send back
+pub fn handle_line(line: &str) -> Response {
+    let value: Value = match serde_json::from_str(line) {
+        Ok(value) => value,
+        Err(e) => return Response::failure(Value::Null, PARSE_ERROR, e.to_string(), None),
+    };
+    let id = value.get("id").cloned().unwrap_or(Value::Null);
+    let request: Request = match serde_json::from_value(value) {
+        Ok(request) => request,
+        Err(e) => return Response::failure(id, INVALID_REQUEST, e.to_string(), None),
+    };
+    if request.jsonrpc != "2.0" {
+        return Response::failure(id, INVALID_REQUEST, "Only JSON-RPC 2.0 is supported".to_string(), None);
+    }
+
+    match dispatch(&request.method, request.params) {
+        Some(Ok(result)) => Response::success(id, result),
+        Some(Err(e)) => {
+            let data = serde_json::to_value(&e).ok();
+            Response::failure(id, APP_ERROR, e.to_string(), data)
+        }
+        None => Response::failure(
+            id,
+            METHOD_NOT_FOUND,
+            format!("Unknown method '{}'", request.method),
+            None,
+        ),
+    }
+}
+
+/// Reads named params into a tuple, e.g. `params!(params, id: i64, enabled: bool)`.
+/// Missing `Option` params are `None`.
+macro_rules! params {
+    ($params:expr, $($name:ident: $ty:ty),+ $(,)?) => {{
+        #[derive(Deserialize)]
+        struct Params {
+            $($name: $ty),+
+        }
+        parse_params::<Params>($params).map(|p| ($(p.$name),+,))
+    }};
+}
+
+fn parse_params<T: DeserializeOwned>(params: Value) -> AppResult<T> {
+    // Methods without required params may be called without any
+    let params = if params.is_null() { Value::Object(Default::default()) } else { params };
+    serde_json::from_value(params).map_err(|e| AppError::validation("params", format!("Invalid params: {}", e)))
+}
+
+fn to_value<T: Serialize>(result: AppResult<T>) -> AppResult<Value> {
+    let value = result?;
+    serde_json::to_value(value).map_err(|e| AppError::Io(format!("Failed to serialize the result: {}", e)))
+}
+
+/// Runs a method, `None` if there is no such method. Apart from the control
+/// methods these are the Tauri commands, taking the same arguments by name.
+fn dispatch(method: &str, params: Value) -> Option<AppResult<Value>> {
+    let result = match method {
+        "ping" => Ok(Value::from("pong")),
+        // Something else changed the database, reload the reminders
+        "refresh" => {
+            refresh_scheduler();
+            Ok(Value::Null)
+        }
+        "status" | "get_status" => to_value(crate::get_status()),
+        "pause" | "pause_reminders" => {
+            params!(params, minutes: Option<i64>).and_then(|(minutes,)| to_value(crate::pause_reminders(minutes)))
+        }
+        "resume" | "resume_reminders" => to_value(crate::resume_reminders()),
+        "trigger_now" => params!(params, id: i64).and_then(|(id,)| to_value(crate::trigger_now(id))),
+        "get_upcoming_triggers" => params!(
+            params,
+            id: Option<i64>,
+            count: Option<usize>,
+            until: Option<DateTime<Utc>>,
+        )
+        .and_then(|(id, count, until)| to_value(crate::get_upcoming_triggers(id, count, until))),
+
+        "get_reminders" => to_value(crate::get_reminders()),
+        "get_reminder" => params!(params, id: i64).and_then(|(id,)| to_value(crate::get_reminder(id))),
+        "create_reminder" => params!(params, reminder: CreateReminder)
+            .and_then(|(reminder,)| to_value(crate::create_reminder(reminder))),
+        "create_one_shot_reminder" => params!(
+            params,
+            name: String,
+            message: Option<String>,
+            when: String,
+            sound: Option<String>,
+        )
+        .and_then(|(name, message, when, sound)| {
+            to_value(crate::create_one_shot_reminder(name, message, when, sound))
+        }),
+        "update_reminder" => params!(params, reminder: UpdateReminder)
+            .and_then(|(reminder,)| to_value(crate::update_reminder(reminder))),
+        "delete_reminder" => params!(params, id: i64).and_then(|(id,)| to_value(crate::delete_reminder(id))),
+        "toggle_reminder" => params!(params, id: i64, enabled: bool)
+            .and_then(|(id, enabled)| to_value(crate::toggle_reminder(id, enabled))),
+        "dismiss_reminder" => params!(params, id: i64).and_then(|(id,)| to_value(crate::dismiss_reminder(id))),
+        "snooze_reminder" => params!(params, id: i64, minutes: i32)
+            .and_then(|(id, minutes)| to_value(crate::snooze_reminder(id, minutes))),
+        "get_pending_reminders" => to_value(crate::get_pending_reminders()),
+        "record_game_result" => params!(params, id: i64, game: String, won: bool)
+            .and_then(|(id, game, won)| to_value(crate::record_game_result(id, game, won))),
+
+        "get_history" => params!(params, reminder_id: Option<i64>, from: Option<String>, to: Option<String>)
+            .and_then(|(reminder_id, from, to)| to_value(crate::get_history(reminder_id, from, to))),
+        "get_reminder_stats" => params!(params, id: i64, range: Option<StatsRange>)
+            .and_then(|(id, range)| to_value(crate::get_reminder_stats(id, range))),
+        "get_stats" => params!(params, range: Option<StatsRange>).and_then(|(range,)| to_value(crate::get_stats(range))),
+
+        "export_reminders" => params!(params, path: String).and_then(|(path,)| to_value(crate::export_reminders(path))),
+        "import_reminders" => params!(params, path: String, mode: Option<ImportMode>, dry_run: Option<bool>)
+            .and_then(|(path, mode, dry_run)| to_value(crate::import_reminders(path, mode, dry_run))),
+        "export_ics" => params!(params, path: String, ids: Option<Vec<i64>>)
+            .and_then(|(path, ids)| to_value(crate::export_ics(path, ids))),
+        "import_ics" => params!(params, path: String, dry_run: Option<bool>)
+            .and_then(|(path, dry_run)| to_value(crate::import_ics(path, dry_run))),
+
+        "get_webhooks" => params!(params, reminder_id: Option<i64>)
+            .and_then(|(reminder_id,)| to_value(crate::get_webhooks(reminder_id))),
+        "create_webhook" => params!(params, webhook: CreateWebhook)
+            .and_then(|(webhook,)| to_value(crate::create_webhook(webhook))),
+        "update_webhook" => params!(params, webhook: UpdateWebhook)
+            .and_then(|(webhook,)| to_value(crate::update_webhook(webhook))),
+        "delete_webhook" => params!(params, id: i64).and_then(|(id,)| to_value(crate::delete_webhook(id))),
+        "get_webhook_deliveries" => params!(params, webhook_id: Option<i64>, limit: Option<usize>)
+            .and_then(|(webhook_id, limit)| to_value(crate::get_webhook_deliveries(webhook_id, limit))),
+
+        "get_settings" => to_value(crate::get_settings()),
+        "update_settings" => params!(params, settings: Settings)
+            .and_then(|(settings,)| to_value(crate::update_settings(settings))),
+        "get_api_token" => to_value(crate::get_api_token()),
+        "regenerate_api_token" => to_value(crate::regenerate_api_token()),
+        "get_pause_state" => to_value(crate::get_pause_state()),
+        _ => return None,
+    };
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn handle(request: Value) -> Response {
+        handle_line(&request.to_string())
+    }
+
+    #[test]
+    fn answers_status_calls() {
+        let response = handle(json!({ "jsonrpc": "2.0", "id": 7, "method": "status" }));
+
+        assert_eq!(response.id, json!(7));
+        assert!(response.error.is_none());
+        let result = response.result.unwrap();
+        assert!(result.get("running").is_some() && result.get("next_triggers").is_some(), "{}", result);
+    }
+
+    #[test]
+    fn rejects_unknown_methods() {
+        let response = handle(json!({ "jsonrpc": "2.0", "id": "a", "method": "explode" }));
+
+        assert_eq!(response.id, json!("a"));
+        let error = response.error.unwrap();
+        assert_eq!(error.code, METHOD_NOT_FOUND);
+        assert!(error.message.contains("explode"));
+    }
+
+    #[test]
+    fn rejects_bad_params_as_validation_errors() {
+        let response = handle(json!({ "jsonrpc": "2.0", "id": 1, "method": "trigger_now", "params": { "id": "x" } }));
+
+        let error = response.error.unwrap();
+        assert_eq!(error.code, APP_ERROR);
+        let data = error.data.unwrap();
+        assert_eq!(data["kind"], "validation");
+        assert_eq!(data["field"], "params");
+
+        let missing = handle(json!({ "jsonrpc": "2.0", "id": 2, "method": "snooze_reminder", "params": { "id": 1 } }));
+        assert_eq!(missing.error.unwrap().code, APP_ERROR);
+    }
+
+    #[test]
+    fn rejects_malformed_requests() {
+        let response = handle_line("{ not json");
+        assert_eq!(response.id, Value::Null);
+        assert_eq!(response.error.unwrap().code, PARSE_ERROR);
+
+        let response = handle(json!({ "jsonrpc": "1.0", "id": 3, "method": "ping" }));
+        assert_eq!(response.id, json!(3));
+        assert_eq!(response.error.unwrap().code, INVALID_REQUEST);
+
+        let response = handle(json!({ "jsonrpc": "2.0", "id": 4 }));
+        assert_eq!(response.error.unwrap().code, INVALID_REQUEST);
+    }
+}
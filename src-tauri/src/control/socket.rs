@@ -0,0 +1,168 @@
+use super::rpc::{handle_line, Request, Response};
+use crate::error::{AppError, AppResult};
+use crate::storage::data_dir;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+/// Where the running app listens for control requests. Only the owner can
+/// connect, the socket file is created with mode 0600 and connections from
+/// other users are turned away.
+pub fn socket_path() -> PathBuf {
+    data_dir().join("control.sock")
+}
+
+/// Accepts control connections until the app exits
+#[cfg(unix)]
+pub async fn serve() -> AppResult<()> {
+    serve_at(&socket_path()).await
+}
+
+#[cfg(unix)]
+async fn serve_at(path: &Path) -> AppResult<()> {
+    use std::os::unix::fs::{MetadataExt, PermissionsExt};
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::UnixListener;
+
+    if path.exists() {
+        // Left behind by an instance that didn't shut down cleanly, unless
+        // another instance is still listening on it
+        if std::os::unix::net::UnixStream::connect(path).is_ok() {
+            return Err(AppError::Io(format!("{} is already in use", path.display())));
+        }
+        std::fs::remove_file(path)
+            .map_err(|e| AppError::Io(format!("Failed to remove {}: {}", path.display(), e)))?;
+    }
+
+    let listener = UnixListener::bind(path)
+        .map_err(|e| AppError::Io(format!("Failed to listen on {}: {}", path.display(), e)))?;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))
+        .map_err(|e| AppError::Io(format!("Failed to restrict {}: {}", path.display(), e)))?;
+    let owner = std::fs::metadata(path)
+        .map_err(|e| AppError::Io(format!("Failed to read {}: {}", path.display(), e)))?
+        .uid();
+
+    loop {
+        let (stream, _) = listener
+            .accept()
+            .await
+            .map_err(|e| AppError::Io(format!("Failed to accept a control connection: {}", e)))?;
+
+        // The file mode keeps other users out, this also refuses root and
+        // anyone let in by a later chmod
+        match stream.peer_cred() {
+            Ok(cred) if cred.uid() == owner => {}
+            Ok(cred) => {
+                eprintln!("Refused control connection from uid {}", cred.uid());
+                continue;
+            }
+            Err(e) => {
+                eprintln!("Refused control connection without credentials: {}", e);
+                continue;
+            }
+        }
+
+        tokio::spawn(async move {
+            let (reader, mut writer) = stream.into_split();
+            let mut lines = BufReader::new(reader).lines();
+
+            while let Ok(Some(line)) = lines.next_line().await {
+                if line.trim().is_empty() {
+                    continue;
+                }
+                // Methods hit the database, keep them off the async workers
+                let Ok(response) = tokio::task::spawn_blocking(move || handle_line(&line)).await else {
+                    break;
+                };
+                let Ok(mut json) = serde_json::to_string(&response) else {
+                    break;
+                };
+                json.push('\n');
+                if writer.write_all(json.as_bytes()).await.is_err() {
+                    break;
+                }
+            }
+        });
+    }
+}
+
+#[cfg(not(unix))]
+pub async fn serve() -> AppResult<()> {
+    Err(AppError::Io("The control socket is only available on Unix".to_string()))
+}
+
+/// Sends one request to the running app and waits for its response
+#[cfg(unix)]
+pub fn call(method: &str, params: Value) -> AppResult<Value> {
+    use std::io::{BufRead, BufReader, Write};
+    use std::os::unix::net::UnixStream;
+    use std::time::Duration;
+
+    let io_error = |e: std::io::Error| AppError::Io(format!("Control socket: {}", e));
+
+    let stream = UnixStream::connect(socket_path())
+        .map_err(|e| AppError::Io(format!("The app is not running: {}", e)))?;
+    stream.set_read_timeout(Some(Duration::from_secs(10))).map_err(io_error)?;
+
+    let mut request = serde_json::to_string(&Request::new(1, method, params))
+        .map_err(|e| AppError::Io(e.to_string()))?;
+    request.push('\n');
+    (&stream).write_all(request.as_bytes()).map_err(io_error)?;
+
+    let mut line = String::new();
+    BufReader::new(&stream).read_line(&mut line).map_err(io_error)?;
+    let response: Response = serde_json::from_str(&line)
+        .map_err(|e| AppError::Io(format!("Invalid response from the app: {}", e)))?;
+    response.into_result()
+}
+
+#[cfg(not(unix))]
+pub fn call(_method: &str, _params: Value) -> AppResult<Value> {
+    Err(AppError::Io("The control socket is only available on Unix".to_string()))
+}
+
+/// Asks a running app to reload its reminders, returns whether one answered
+pub fn notify_refresh() -> bool {
+    call("refresh", Value::Null).is_ok()
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::UnixStream;
+
+    #[tokio::test]
+    async fn answers_each_line_and_survives_malformed_ones() {
+        let dir = std::env::temp_dir().join(format!("mtr-control-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("control.sock");
+        let server = tokio::spawn({
+            let path = path.clone();
+            async move { serve_at(&path).await }
+        });
+        while !path.exists() {
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        // Connections wait in the backlog until the listener is restricted
+        // to the owner, which is this process
+        let stream = UnixStream::connect(&path).await.unwrap();
+        let (reader, mut writer) = stream.into_split();
+        let mut lines = BufReader::new(reader).lines();
+
+        writer.write_all(b"{ not json\n\n").await.unwrap();
+        let ping = serde_json::to_string(&Request::new(2, "ping", Value::Null)).unwrap();
+        writer.write_all(format!("{}\n", ping).as_bytes()).await.unwrap();
+
+        let malformed: Response = serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+        assert_eq!(malformed.error.map(|e| e.code), Some(-32700));
+        let pong: Response = serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+        assert_eq!(pong.id, Value::from(2));
+        assert_eq!(pong.result, Some(Value::from("pong")));
+
+        // A second instance doesn't take over the socket
+        assert!(serve_at(&path).await.is_err());
+
+        server.abort();
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,131 @@
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+
+/// Errors returned by storage and by every Tauri command.
+///
+/// Serialized for the frontend as `{ kind, message, field }` so it can tell a
+/// missing reminder from a locked database or a rejected form field.
+#[derive(Debug, thiserror::Error)]
+pub enum AppError {
+    #[error("Database error: {0}")]
+    Db(String),
+    #[error("{0} not found")]
+    NotFound(String),
+    #[error("{reason}")]
+    Validation { field: String, reason: String },
+    #[error("Scheduler error: {0}")]
+    Scheduler(String),
+    #[error("Window error: {0}")]
+    Window(String),
+    #[error("{0}")]
+    Io(String),
+}
+
+pub type AppResult<T> = Result<T, AppError>;
+
+impl AppError {
+    pub fn validation(field: impl Into<String>, reason: impl Into<String>) -> Self {
+        AppError::Validation {
+            field: field.into(),
+            reason: reason.into(),
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            AppError::Db(_) => "db",
+            AppError::NotFound(_) => "not_found",
+            AppError::Validation { .. } => "validation",
+            AppError::Scheduler(_) => "scheduler",
+            AppError::Window(_) => "window",
+            AppError::Io(_) => "io",
+        }
+    }
+
+    pub fn field(&self) -> Option<&str> {
+        match self {
+            AppError::Validation { field, .. } => Some(field),
+            _ => None,
+        }
+    }
+}
+
+impl From<rusqlite::Error> for AppError {
+    fn from(e: rusqlite::Error) -> Self {
+        AppError::Db(e.to_string())
+    }
+}
+
+impl From<tauri::Error> for AppError {
+    fn from(e: tauri::Error) -> Self {
+        AppError::Window(e.to_string())
+    }
+}
+
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("AppError", 3)?;
+        state.serialize_field("kind", self.kind())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.serialize_field("field", &self.field())?;
+        state.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    fn serialized(error: AppError) -> Value {
+        serde_json::to_value(error).unwrap()
+    }
+
+    #[test]
+    fn serializes_db_errors() {
+        assert_eq!(
+            serialized(AppError::Db("locked".to_string())),
+            json!({ "kind": "db", "message": "Database error: locked", "field": null })
+        );
+    }
+
+    #[test]
+    fn serializes_not_found_errors() {
+        assert_eq!(
+            serialized(AppError::NotFound("Reminder 3".to_string())),
+            json!({ "kind": "not_found", "message": "Reminder 3 not found", "field": null })
+        );
+    }
+
+    #[test]
+    fn serializes_validation_errors_with_their_field() {
+        assert_eq!(
+            serialized(AppError::validation("name", "Name is required")),
+            json!({ "kind": "validation", "message": "Name is required", "field": "name" })
+        );
+    }
+
+    #[test]
+    fn serializes_scheduler_errors() {
+        assert_eq!(
+            serialized(AppError::Scheduler("stopped".to_string())),
+            json!({ "kind": "scheduler", "message": "Scheduler error: stopped", "field": null })
+        );
+    }
+
+    #[test]
+    fn serializes_window_errors() {
+        assert_eq!(
+            serialized(AppError::Window("closed".to_string())),
+            json!({ "kind": "window", "message": "Window error: closed", "field": null })
+        );
+    }
+
+    #[test]
+    fn serializes_io_errors() {
+        assert_eq!(
+            serialized(AppError::Io("Failed to read x".to_string())),
+            json!({ "kind": "io", "message": "Failed to read x", "field": null })
+        );
+    }
+}
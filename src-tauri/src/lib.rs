@@ -1,92 +1,356 @@
-mod storage;
-mod reminder;
+mod api;
+// Shared with the `mtr` command-line tool
+pub mod control;
+pub mod error;
+pub mod storage;
+pub mod reminder;
+pub mod stats;
+pub mod transfer;
+mod webhook;
 
-use storage::{CreateReminder, Reminder, UpdateReminder};
-use reminder::{refresh_scheduler, start_scheduler};
+use chrono::{DateTime, Duration, Local, Utc};
+use std::path::Path;
+use error::{AppError, AppResult};
+use stats::{ReminderStats, StatsRange};
+use storage::{
+    CreateReminder, CreateWebhook, EventKind, PauseState, Reminder, ReminderEvent, Settings,
+    UpdateReminder, UpdateWebhook, Webhook, WebhookDelivery,
+};
+use transfer::{IcsExportReport, IcsImportReport, ImportMode, ImportReport};
+use reminder::{
+    parse_when, pause_scheduler, refresh_scheduler, resume_scheduler, scheduler_status, start_scheduler,
+    PlannedTrigger, SchedulerStatus, DEFAULT_UPCOMING_TRIGGERS,
+};
 use tauri::{
     image::Image,
-    menu::{MenuBuilder, MenuItemBuilder},
+    menu::{Menu, MenuBuilder, MenuItemBuilder, SubmenuBuilder},
     tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
-    Manager, Runtime,
+    Listener, Manager, Runtime,
 };
 
+const TRAY_ID: &str = "main";
+
 // CRUD Commands
 #[tauri::command]
-fn get_reminders() -> Result<Vec<Reminder>, String> {
+fn get_reminders() -> AppResult<Vec<Reminder>> {
     storage::get_all_reminders()
 }
 
 #[tauri::command]
-fn get_reminder(id: i64) -> Result<Option<Reminder>, String> {
+fn get_reminder(id: i64) -> AppResult<Option<Reminder>> {
     storage::get_reminder_by_id(id)
 }
 
 #[tauri::command]
-fn create_reminder(reminder: CreateReminder) -> Result<Reminder, String> {
+fn create_reminder(reminder: CreateReminder) -> AppResult<Reminder> {
     let result = storage::create_reminder(reminder)?;
     refresh_scheduler();
     Ok(result)
 }
 
+/// Creates a reminder that fires once, `when` being either a date/time or a
+/// phrase like "in 20 minutes" or "tomorrow 9am"
+#[tauri::command]
+fn create_one_shot_reminder(
+    name: String,
+    message: Option<String>,
+    when: String,
+    sound: Option<String>,
+) -> AppResult<Reminder> {
+    let tz = reminder::default_timezone(&storage::get_settings()?).unwrap_or_else(reminder::system_timezone);
+    let due_at = parse_when(&when, &Utc::now().with_timezone(&tz))
+        .map_err(|reason| AppError::validation("when", reason))?
+        .with_timezone(&Utc);
+    if due_at <= Utc::now() {
+        return Err(AppError::validation("when", format!("'{}' is in the past", when)));
+    }
+    
+    let result = storage::create_reminder(CreateReminder {
+        name,
+        message,
+        interval_minutes: 0,
+        schedule: Default::default(),
+        due_at: Some(due_at.to_rfc3339()),
+        enabled: true,
+        active_start_time: None,
+        active_end_time: None,
+        active_days: None,
+        sound: sound.unwrap_or_else(|| "chime".to_string()),
+        command: None,
+        delivery_style: Default::default(),
+        escalation: Vec::new(),
+        catch_up: Default::default(),
+        timezone: None,
+    })?;
+    refresh_scheduler();
+    Ok(result)
+}
+
 #[tauri::command]
-fn update_reminder(reminder: UpdateReminder) -> Result<Reminder, String> {
+fn update_reminder(reminder: UpdateReminder) -> AppResult<Reminder> {
     let result = storage::update_reminder(reminder)?;
     refresh_scheduler();
     Ok(result)
 }
 
 #[tauri::command]
-fn delete_reminder(id: i64) -> Result<(), String> {
+fn delete_reminder(id: i64) -> AppResult<()> {
     storage::delete_reminder(id)?;
     refresh_scheduler();
     Ok(())
 }
 
 #[tauri::command]
-fn toggle_reminder(id: i64, enabled: bool) -> Result<Reminder, String> {
+fn toggle_reminder(id: i64, enabled: bool) -> AppResult<Reminder> {
     let result = storage::toggle_reminder(id, enabled)?;
     refresh_scheduler();
     Ok(result)
 }
 
 #[tauri::command]
-fn dismiss_reminder(id: i64) -> Result<(), String> {
-    storage::update_last_triggered(id)?;
+fn dismiss_reminder(id: i64) -> AppResult<()> {
+    storage::dismiss_reminder(id)?;
     refresh_scheduler();
     Ok(())
 }
 
 #[tauri::command]
-fn snooze_reminder(id: i64, _minutes: i32) -> Result<(), String> {
-    // For snooze, just update the last_triggered to reset the timer
-    // The scheduler will recalculate based on the interval
-    storage::update_last_triggered(id)?;
+fn snooze_reminder(id: i64, minutes: i32) -> AppResult<Reminder> {
+    let result = storage::snooze_reminder(id, minutes)?;
     refresh_scheduler();
+    Ok(result)
+}
+
+/// Reminders that fired and are still waiting to be dismissed or snoozed
+#[tauri::command]
+fn get_pending_reminders() -> AppResult<Vec<Reminder>> {
+    storage::get_pending_reminders()
+}
+
+/// Called by the popup after each mini-game round
+#[tauri::command]
+fn record_game_result(id: i64, game: String, won: bool) -> AppResult<()> {
+    let kind = if won { EventKind::GameWon } else { EventKind::GameLost };
+    storage::record_event(id, kind, Some(serde_json::json!({ "game": game })))?;
     Ok(())
 }
 
+// History Commands
 #[tauri::command]
-fn close_popup(window: tauri::Window) -> Result<(), String> {
-    window.close().map_err(|e| e.to_string())
+fn get_history(
+    reminder_id: Option<i64>,
+    from: Option<String>,
+    to: Option<String>,
+) -> AppResult<Vec<ReminderEvent>> {
+    storage::get_events(reminder_id, from.as_deref(), to.as_deref())
 }
 
-fn setup_tray<R: Runtime>(app: &tauri::App<R>) -> Result<(), Box<dyn std::error::Error>> {
-    let show_item = MenuItemBuilder::new("Show Settings").id("show").build(app)?;
-    let pause_item = MenuItemBuilder::new("Pause All").id("pause").build(app)?;
-    let quit_item = MenuItemBuilder::new("Quit").id("quit").build(app)?;
+// Statistics Commands
+#[tauri::command]
+fn get_reminder_stats(id: i64, range: Option<StatsRange>) -> AppResult<ReminderStats> {
+    stats::get_reminder_stats(id, range.unwrap_or_default())
+}
+
+#[tauri::command]
+fn get_stats(range: Option<StatsRange>) -> AppResult<Vec<ReminderStats>> {
+    stats::get_all_stats(range.unwrap_or_default())
+}
+
+// Import / Export Commands
+/// Writes all reminders and the settings to a JSON file, returning the number
+/// of reminders written
+#[tauri::command]
+fn export_reminders(path: String) -> AppResult<usize> {
+    transfer::export_reminders(Path::new(&path))
+}
+
+/// Imports a file written by `export_reminders`. With `dry_run` nothing is
+/// changed and the report shows what would happen.
+#[tauri::command]
+fn import_reminders(path: String, mode: Option<ImportMode>, dry_run: Option<bool>) -> AppResult<ImportReport> {
+    let report = transfer::import_reminders(
+        Path::new(&path),
+        mode.unwrap_or_default(),
+        dry_run.unwrap_or(false),
+    )?;
+    if !report.dry_run {
+        refresh_scheduler();
+    }
+    Ok(report)
+}
+
+/// Writes the given reminders, or all enabled ones, to an iCalendar file
+#[tauri::command]
+fn export_ics(path: String, ids: Option<Vec<i64>>) -> AppResult<IcsExportReport> {
+    transfer::export_ics(Path::new(&path), ids.as_deref())
+}
+
+/// Creates reminders from the events with alarms in an iCalendar file
+#[tauri::command]
+fn import_ics(path: String, dry_run: Option<bool>) -> AppResult<IcsImportReport> {
+    let report = transfer::import_ics(Path::new(&path), dry_run.unwrap_or(false))?;
+    if !report.dry_run {
+        refresh_scheduler();
+    }
+    Ok(report)
+}
+
+// Webhook Commands
+/// All webhooks, or only the ones of one reminder
+#[tauri::command]
+fn get_webhooks(reminder_id: Option<i64>) -> AppResult<Vec<Webhook>> {
+    storage::get_webhooks(reminder_id)
+}
+
+#[tauri::command]
+fn create_webhook(webhook: CreateWebhook) -> AppResult<Webhook> {
+    storage::create_webhook(webhook)
+}
+
+#[tauri::command]
+fn update_webhook(webhook: UpdateWebhook) -> AppResult<Webhook> {
+    storage::update_webhook(webhook)
+}
+
+#[tauri::command]
+fn delete_webhook(id: i64) -> AppResult<()> {
+    storage::delete_webhook(id)
+}
+
+/// The delivery log, newest first
+#[tauri::command]
+fn get_webhook_deliveries(webhook_id: Option<i64>, limit: Option<usize>) -> AppResult<Vec<WebhookDelivery>> {
+    storage::get_webhook_deliveries(webhook_id, limit.unwrap_or(100))
+}
+
+// Settings Commands
+#[tauri::command]
+fn get_settings() -> AppResult<Settings> {
+    storage::get_settings()
+}
+
+#[tauri::command]
+fn update_settings(settings: Settings) -> AppResult<Settings> {
+    let settings = storage::update_settings(settings)?;
+    api::apply_api_settings(&settings);
+    // The default time zone may have changed
+    refresh_scheduler();
+    Ok(settings)
+}
+
+/// Token clients of the HTTP API have to send
+#[tauri::command]
+fn get_api_token() -> AppResult<String> {
+    storage::get_api_token()
+}
+
+#[tauri::command]
+fn regenerate_api_token() -> AppResult<String> {
+    storage::regenerate_api_token()
+}
+
+// Pause Commands
+#[tauri::command]
+fn get_pause_state() -> AppResult<PauseState> {
+    storage::get_pause_state()
+}
+
+#[tauri::command]
+fn pause_reminders(minutes: Option<i64>) -> AppResult<()> {
+    pause_scheduler(reminder::pause_end(minutes, Utc::now())?)
+}
+
+#[tauri::command]
+fn resume_reminders() -> AppResult<()> {
+    resume_scheduler()
+}
+
+/// Shows the reminder now, outside its regular schedule
+#[tauri::command]
+fn trigger_now(id: i64) -> AppResult<()> {
+    reminder::trigger_now(id)
+}
+
+#[tauri::command]
+fn get_status() -> AppResult<SchedulerStatus> {
+    Ok(scheduler_status())
+}
+
+/// The next `count` occurrences of one reminder, or of all of them, up to
+/// `until`
+#[tauri::command]
+fn get_upcoming_triggers(
+    id: Option<i64>,
+    count: Option<usize>,
+    until: Option<DateTime<Utc>>,
+) -> AppResult<Vec<PlannedTrigger>> {
+    reminder::get_upcoming_triggers(id, count.unwrap_or(DEFAULT_UPCOMING_TRIGGERS), until)
+}
+
+#[tauri::command]
+fn close_popup(window: tauri::Window) -> AppResult<()> {
+    Ok(window.close()?)
+}
+
+/// Start of the next local day, used by the "until tomorrow" pause
+fn tomorrow_start() -> Option<DateTime<Utc>> {
+    let midnight = (Local::now() + Duration::days(1)).date_naive().and_hms_opt(0, 0, 0)?;
+    midnight
+        .and_local_timezone(Local)
+        .earliest()
+        .map(|t| t.with_timezone(&Utc))
+}
+
+fn build_tray_menu<R: Runtime, M: Manager<R>>(manager: &M, pause: &PauseState) -> tauri::Result<Menu<R>> {
+    let show_item = MenuItemBuilder::new("Show Settings").id("show").build(manager)?;
+    let quit_item = MenuItemBuilder::new("Quit").id("quit").build(manager)?;
     
-    let menu = MenuBuilder::new(app)
+    let mut builder = MenuBuilder::new(manager)
         .item(&show_item)
-        .separator()
-        .item(&pause_item)
+        .separator();
+    
+    if pause.paused {
+        let until = pause
+            .until
+            .as_ref()
+            .and_then(|u| DateTime::parse_from_rfc3339(u).ok())
+            .map(|u| format!("Paused until {}", u.with_timezone(&Local).format("%a %H:%M")))
+            .unwrap_or_else(|| "Paused indefinitely".to_string());
+        let status_item = MenuItemBuilder::new(until).id("pause-status").enabled(false).build(manager)?;
+        let resume_item = MenuItemBuilder::new("Resume").id("resume").build(manager)?;
+        builder = builder.item(&status_item).item(&resume_item);
+    } else {
+        let pause_menu = SubmenuBuilder::new(manager, "Pause All")
+            .text("pause-30m", "30 minutes")
+            .text("pause-1h", "1 hour")
+            .text("pause-tomorrow", "Until tomorrow")
+            .text("pause-indefinitely", "Indefinitely")
+            .build()?;
+        builder = builder.item(&pause_menu);
+    }
+    
+    builder
         .separator()
         .item(&quit_item)
-        .build()?;
+        .build()
+}
+
+fn tray_tooltip(pending: usize) -> String {
+    match pending {
+        0 => "MultiTask Reminder".to_string(),
+        1 => "MultiTask Reminder - 1 unanswered reminder".to_string(),
+        n => format!("MultiTask Reminder - {} unanswered reminders", n),
+    }
+}
+
+fn setup_tray<R: Runtime>(app: &tauri::App<R>) -> Result<(), Box<dyn std::error::Error>> {
+    let pause = storage::get_pause_state().unwrap_or_default();
+    let menu = build_tray_menu(app, &pause)?;
     
     // Load icon - use the app's default icon
     let icon = app.default_window_icon().cloned();
     
-    let mut tray_builder = TrayIconBuilder::new()
+    let mut tray_builder = TrayIconBuilder::with_id(TRAY_ID)
         .menu(&menu);
     
     // Set icon if available
@@ -95,7 +359,7 @@ fn setup_tray<R: Runtime>(app: &tauri::App<R>) -> Result<(), Box<dyn std::error:
     }
     
     let _tray = tray_builder
-        .tooltip("MultiTask Reminder")
+        .tooltip(tray_tooltip(0))
         .on_menu_event(|app, event| {
             match event.id().as_ref() {
                 "show" => {
@@ -104,14 +368,22 @@ fn setup_tray<R: Runtime>(app: &tauri::App<R>) -> Result<(), Box<dyn std::error:
                         let _ = window.set_focus();
                     }
                 }
-                "pause" => {
-                    // TODO: Implement pause functionality
-                    println!("Pause clicked");
-                }
                 "quit" => {
                     app.exit(0);
                 }
-                _ => {}
+                id => {
+                    let result = match id {
+                        "pause-30m" => pause_scheduler(Some(Utc::now() + Duration::minutes(30))),
+                        "pause-1h" => pause_scheduler(Some(Utc::now() + Duration::hours(1))),
+                        "pause-tomorrow" => pause_scheduler(tomorrow_start()),
+                        "pause-indefinitely" => pause_scheduler(None),
+                        "resume" => resume_scheduler(),
+                        _ => Ok(()),
+                    };
+                    if let Err(e) = result {
+                        eprintln!("Tray action '{}' failed: {}", id, e);
+                    }
+                }
             }
         })
         .on_tray_icon_event(|tray, event| {
@@ -130,6 +402,32 @@ fn setup_tray<R: Runtime>(app: &tauri::App<R>) -> Result<(), Box<dyn std::error:
         })
         .build(app)?;
     
+    // The scheduler owns the pause state, rebuild the menu whenever it changes
+    let handle = app.handle().clone();
+    app.listen_any("pause-changed", move |event| {
+        let Ok(pause) = serde_json::from_str::<PauseState>(event.payload()) else {
+            return;
+        };
+        if let Some(tray) = handle.tray_by_id(TRAY_ID) {
+            match build_tray_menu(&handle, &pause) {
+                Ok(menu) => {
+                    let _ = tray.set_menu(Some(menu));
+                }
+                Err(e) => eprintln!("Failed to rebuild tray menu: {}", e),
+            }
+        }
+    });
+    
+    let handle = app.handle().clone();
+    app.listen_any("pending-changed", move |event| {
+        let Ok(pending) = serde_json::from_str::<usize>(event.payload()) else {
+            return;
+        };
+        if let Some(tray) = handle.tray_by_id(TRAY_ID) {
+            let _ = tray.set_tooltip(Some(tray_tooltip(pending)));
+        }
+    });
+    
     Ok(())
 }
 
@@ -141,14 +439,51 @@ pub fn run() {
             get_reminders,
             get_reminder,
             create_reminder,
+            create_one_shot_reminder,
             update_reminder,
             delete_reminder,
             toggle_reminder,
             dismiss_reminder,
             snooze_reminder,
+            get_pending_reminders,
+            record_game_result,
+            get_history,
+            get_reminder_stats,
+            get_stats,
+            export_reminders,
+            import_reminders,
+            export_ics,
+            import_ics,
+            get_webhooks,
+            create_webhook,
+            update_webhook,
+            delete_webhook,
+            get_webhook_deliveries,
+            get_settings,
+            update_settings,
+            get_api_token,
+            regenerate_api_token,
+            get_pause_state,
+            pause_reminders,
+            resume_reminders,
+            trigger_now,
+            get_status,
+            get_upcoming_triggers,
             close_popup,
         ])
         .setup(|app| {
+            // The settings window shows storage errors, so keep running and
+            // let every command report the problem
+            if let Err(e) = storage::init() {
+                eprintln!("Failed to initialize database: {}", e);
+            }
+            
+            // Opt-in HTTP API for other tools on this machine
+            match storage::get_settings() {
+                Ok(settings) => api::apply_api_settings(&settings),
+                Err(e) => eprintln!("Failed to load settings: {}", e),
+            }
+            
             // Setup system tray
             if let Err(e) = setup_tray(app) {
                 eprintln!("Failed to setup tray: {}", e);
@@ -162,6 +497,13 @@ pub fn run() {
                     .build()
                     .expect("Failed to create tokio runtime");
                 rt.block_on(async {
+                    // Lets the command-line tool tell the scheduler about its changes
+                    tokio::spawn(async {
+                        if let Err(e) = control::serve().await {
+                            eprintln!("Control socket stopped: {}", e);
+                        }
+                    });
+                    tokio::spawn(webhook::run_webhook_deliveries());
                     start_scheduler(handle).await;
                 });
             });
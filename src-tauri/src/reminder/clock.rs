@@ -0,0 +1,85 @@
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use std::time::{Duration, Instant};
+
+static STARTED: Lazy<Instant> = Lazy::new(Instant::now);
+
+/// Where the scheduler gets the time from
+pub trait Clock: Send + Sync {
+    /// Wall-clock time, which may jump
+    fn now(&self) -> DateTime<Utc>;
+    /// Time since an arbitrary start. Stops while the machine sleeps and
+    /// ignores changes to the wall clock.
+    fn monotonic(&self) -> Duration;
+    /// The zone that times of day like "09:00" are read in
+    fn timezone(&self) -> Tz;
+}
+
+/// The machine's clocks and time zone
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+
+    fn monotonic(&self) -> Duration {
+        STARTED.elapsed()
+    }
+
+    fn timezone(&self) -> Tz {
+        system_timezone()
+    }
+}
+
+/// The zone the operating system is set to, UTC if it can't be told
+pub fn system_timezone() -> Tz {
+    iana_time_zone::get_timezone()
+        .ok()
+        .and_then(|name| name.parse().ok())
+        .unwrap_or(Tz::UTC)
+}
+
+/// A clock that only moves when told to, for running the scheduler in tests
+pub struct FakeClock {
+    state: Mutex<(DateTime<Utc>, Duration)>,
+    timezone: Tz,
+}
+
+impl FakeClock {
+    pub fn new(now: DateTime<Utc>, timezone: Tz) -> Self {
+        Self {
+            state: Mutex::new((now, Duration::ZERO)),
+            timezone,
+        }
+    }
+
+    /// Lets time pass normally
+    pub fn advance(&self, by: Duration) {
+        let mut state = self.state.lock();
+        state.0 += chrono::Duration::from_std(by).unwrap_or_default();
+        state.1 += by;
+    }
+
+    /// Moves the wall clock only, like suspending the machine (forward) or
+    /// setting the clock (either way)
+    pub fn jump(&self, by: chrono::Duration) {
+        self.state.lock().0 += by;
+    }
+}
+
+impl Clock for FakeClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.state.lock().0
+    }
+
+    fn monotonic(&self) -> Duration {
+        self.state.lock().1
+    }
+
+    fn timezone(&self) -> Tz {
+        self.timezone
+    }
+}
@@ -0,0 +1,186 @@
+use crate::storage::{record_event, EventKind, Reminder, ReminderCommand};
+use serde::{Deserialize, Serialize};
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+use tokio::process::Command;
+
+/// Output kept in the history, per stream
+const MAX_OUTPUT_BYTES: usize = 4096;
+
+/// What happened when a reminder's command ran, stored as the metadata of its
+/// `command_ran` event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandOutcome {
+    pub program: String,
+    pub args: Vec<String>,
+    /// `None` if the command didn't start, timed out or was killed by a signal
+    pub exit_code: Option<i32>,
+    pub timed_out: bool,
+    pub error: Option<String>,
+    pub stdout: String,
+    pub stderr: String,
+    pub duration_ms: u64,
+}
+
+/// Runs the command with the reminder in its environment, killing it once the
+/// timeout is up. Nothing goes through a shell, so the reminder's text can't
+/// change what is executed.
+pub async fn run_command(reminder: &Reminder, command: &ReminderCommand) -> CommandOutcome {
+    let started = Instant::now();
+    let mut outcome = CommandOutcome {
+        program: command.program.clone(),
+        args: command.args.clone(),
+        exit_code: None,
+        timed_out: false,
+        error: None,
+        stdout: String::new(),
+        stderr: String::new(),
+        duration_ms: 0,
+    };
+
+    let child = Command::new(&command.program)
+        .args(&command.args)
+        .env("REMINDER_ID", reminder.id.to_string())
+        .env("REMINDER_NAME", &reminder.name)
+        .env("REMINDER_MESSAGE", reminder.message.as_deref().unwrap_or(""))
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn();
+
+    match child {
+        Err(e) => outcome.error = Some(format!("Failed to start {}: {}", command.program, e)),
+        Ok(child) => {
+            let timeout = Duration::from_secs(command.timeout_seconds as u64);
+            // Giving up on the wait drops the child, which kills it
+            match tokio::time::timeout(timeout, child.wait_with_output()).await {
+                Ok(Ok(output)) => {
+                    outcome.exit_code = output.status.code();
+                    outcome.stdout = truncate(&output.stdout);
+                    outcome.stderr = truncate(&output.stderr);
+                    if !output.status.success() {
+                        outcome.error = Some(format!("{} exited with {}", command.program, output.status));
+                    }
+                }
+                Ok(Err(e)) => outcome.error = Some(format!("Failed to wait for {}: {}", command.program, e)),
+                Err(_) => {
+                    outcome.timed_out = true;
+                    outcome.error = Some(format!("Killed after {} seconds", command.timeout_seconds));
+                }
+            }
+        }
+    }
+
+    outcome.duration_ms = started.elapsed().as_millis() as u64;
+    outcome
+}
+
+/// Runs the reminder's command if it has one that was switched on, and
+/// records the outcome in the history
+pub async fn run_reminder_command(reminder: &Reminder) {
+    let Some(command) = reminder.command.as_ref().filter(|c| c.enabled) else {
+        return;
+    };
+
+    let outcome = run_command(reminder, command).await;
+    if let Some(error) = &outcome.error {
+        eprintln!("Command of reminder {} failed: {}", reminder.id, error);
+    }
+    let metadata = serde_json::to_value(&outcome).ok();
+    if let Err(e) = record_event(reminder.id, EventKind::CommandRan, metadata) {
+        eprintln!("Failed to record command result: {}", e);
+    }
+}
+
+fn truncate(output: &[u8]) -> String {
+    let text = String::from_utf8_lossy(output);
+    if text.len() <= MAX_OUTPUT_BYTES {
+        return text.into_owned();
+    }
+    let mut end = MAX_OUTPUT_BYTES;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}\n[truncated]", &text[..end])
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn reminder() -> Reminder {
+        Reminder {
+            id: 12,
+            name: "Stand up; rm -rf ~".to_string(),
+            message: Some("$(whoami)".to_string()),
+            interval_minutes: 60,
+            schedule: Default::default(),
+            due_at: None,
+            enabled: true,
+            active_start_time: None,
+            active_end_time: None,
+            active_days: None,
+            sound: "chime".to_string(),
+            command: None,
+            delivery_style: Default::default(),
+            escalation: Vec::new(),
+            catch_up: Default::default(),
+            timezone: None,
+            last_triggered: None,
+            snoozed_until: None,
+            snooze_count: 0,
+            pending_since: None,
+            created_at: "2026-03-02T07:12:00+00:00".to_string(),
+        }
+    }
+
+    fn command(program: &str, args: &[&str], timeout_seconds: u32) -> ReminderCommand {
+        ReminderCommand {
+            program: program.to_string(),
+            args: args.iter().map(|a| a.to_string()).collect(),
+            timeout_seconds,
+            enabled: true,
+        }
+    }
+
+    #[tokio::test]
+    async fn passes_the_reminder_as_environment() {
+        let script = r#"printf '%s|%s|%s' "$REMINDER_ID" "$REMINDER_NAME" "$REMINDER_MESSAGE"; echo oops >&2"#;
+        let outcome = run_command(&reminder(), &command("sh", &["-c", script], 5)).await;
+
+        assert_eq!(outcome.exit_code, Some(0));
+        assert_eq!(outcome.error, None);
+        // Taken literally, not run
+        assert_eq!(outcome.stdout, "12|Stand up; rm -rf ~|$(whoami)");
+        assert_eq!(outcome.stderr, "oops\n");
+    }
+
+    #[tokio::test]
+    async fn reports_failures() {
+        let outcome = run_command(&reminder(), &command("sh", &["-c", "exit 3"], 5)).await;
+        assert_eq!(outcome.exit_code, Some(3));
+        assert!(outcome.error.is_some());
+
+        let outcome = run_command(&reminder(), &command("/nonexistent/program", &[], 5)).await;
+        assert_eq!(outcome.exit_code, None);
+        assert!(outcome.error.unwrap().starts_with("Failed to start"));
+    }
+
+    #[tokio::test]
+    async fn kills_commands_that_run_too_long() {
+        let outcome = run_command(&reminder(), &command("sleep", &["30"], 1)).await;
+
+        assert!(outcome.timed_out);
+        assert_eq!(outcome.exit_code, None);
+        assert!(outcome.duration_ms < 5000);
+    }
+
+    #[test]
+    fn truncates_long_output_on_a_character_boundary() {
+        let output = "é".repeat(MAX_OUTPUT_BYTES);
+        let truncated = truncate(output.as_bytes());
+        assert!(truncated.ends_with("\n[truncated]"));
+        assert!(truncated.len() <= MAX_OUTPUT_BYTES + "\n[truncated]".len());
+    }
+}
@@ -1,3 +1,15 @@
+mod clock;
+mod command;
+mod natural;
+mod notification;
+mod schedule;
 mod scheduler;
+mod sleep;
 
+pub use clock::*;
+pub use command::*;
+pub use natural::*;
+pub use notification::*;
+pub use schedule::*;
 pub use scheduler::*;
+pub use sleep::*;
@@ -0,0 +1,288 @@
+use super::schedule::local_to_utc;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, Weekday};
+use chrono_tz::Tz;
+
+/// Time of day used when only a day is given ("tomorrow", "friday")
+const DEFAULT_HOUR: u32 = 9;
+
+const TOO_LONG: &str = "Duration is too long";
+
+/// Parses when a one-shot reminder should fire, relative to `now`.
+///
+/// Understands absolute dates ("2026-11-03 15:00", RFC 3339), relative
+/// offsets ("in 20 minutes", "in 1h30m", "2 hours from now") and day/time
+/// combinations ("tomorrow 9am", "friday at 14:30", "at 17:00", "noon").
+pub fn parse_when(input: &str, now: &DateTime<Tz>) -> Result<DateTime<Tz>, String> {
+    let text = input.trim().to_lowercase();
+    if text.is_empty() {
+        return Err("No time given".to_string());
+    }
+
+    if let Some(absolute) = parse_absolute(input.trim(), now) {
+        return Ok(absolute);
+    }
+
+    if let Some(offset) = text
+        .strip_prefix("in ")
+        .or_else(|| text.strip_suffix(" from now"))
+    {
+        let duration = parse_duration(offset)?
+            .ok_or_else(|| format!("Could not understand the duration in '{}'", input))?;
+        return now.checked_add_signed(duration).ok_or_else(|| TOO_LONG.to_string());
+    }
+
+    parse_day_and_time(&text, now).ok_or_else(|| format!("Could not understand '{}'", input))
+}
+
+fn parse_absolute(text: &str, now: &DateTime<Tz>) -> Option<DateTime<Tz>> {
+    if let Ok(t) = DateTime::parse_from_rfc3339(text) {
+        return Some(t.with_timezone(&now.timezone()));
+    }
+
+    const FORMATS: [&str; 4] = ["%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S"];
+    let naive = FORMATS
+        .iter()
+        .find_map(|f| NaiveDateTime::parse_from_str(text, f).ok())
+        .or_else(|| {
+            NaiveDate::parse_from_str(text, "%Y-%m-%d")
+                .ok()
+                .and_then(|d| d.and_hms_opt(DEFAULT_HOUR, 0, 0))
+        })?;
+
+    resolve(naive, now)
+}
+
+/// Parses "20 minutes", "1h30m", "an hour", "half an hour", "2 days and 3 hours".
+/// `None` if it isn't a duration, an error if it is one but too long.
+fn parse_duration(text: &str) -> Result<Option<Duration>, String> {
+    let text = text.trim();
+    if text == "half an hour" {
+        return Ok(Some(Duration::minutes(30)));
+    }
+
+    // Split "1h30m" / "20min" into number and unit tokens
+    let mut tokens = Vec::new();
+    for word in text.split_whitespace().filter(|w| *w != "and") {
+        let mut current = String::new();
+        for c in word.chars().filter(|c| *c != ',') {
+            if !current.is_empty() && c.is_ascii_digit() != current.chars().all(|c| c.is_ascii_digit()) {
+                tokens.push(std::mem::take(&mut current));
+            }
+            current.push(c);
+        }
+        if !current.is_empty() {
+            tokens.push(current);
+        }
+    }
+
+    let mut total = Duration::zero();
+    let mut tokens = tokens.into_iter().peekable();
+    let mut parsed_any = false;
+
+    while let Some(token) = tokens.next() {
+        let amount: u64 = match token.as_str() {
+            "a" | "an" | "one" => 1,
+            n if n.chars().all(|c| c.is_ascii_digit()) => n.parse().map_err(|_| TOO_LONG.to_string())?,
+            _ => return Ok(None),
+        };
+        let Some(unit) = tokens.next() else {
+            return Ok(None);
+        };
+        let step = match unit.as_str() {
+            "m" | "min" | "mins" | "minute" | "minutes" => Duration::minutes(1),
+            "h" | "hr" | "hrs" | "hour" | "hours" => Duration::hours(1),
+            "d" | "day" | "days" => Duration::days(1),
+            "w" | "week" | "weeks" => Duration::weeks(1),
+            _ => return Ok(None),
+        };
+        total = i32::try_from(amount)
+            .ok()
+            .and_then(|amount| step.checked_mul(amount))
+            .and_then(|part| total.checked_add(&part))
+            .ok_or_else(|| TOO_LONG.to_string())?;
+        parsed_any = true;
+    }
+
+    Ok((parsed_any && total > Duration::zero()).then_some(total))
+}
+
+/// Parses an optional day ("today", "tonight", "tomorrow", "[next] friday")
+/// and an optional time of day in either order
+fn parse_day_and_time(text: &str, now: &DateTime<Tz>) -> Option<DateTime<Tz>> {
+    let today = now.naive_local().date();
+    let mut day: Option<NaiveDate> = None;
+    let mut default_time = NaiveTime::from_hms_opt(DEFAULT_HOUR, 0, 0)?;
+    let mut time_words = Vec::new();
+
+    for word in text.split_whitespace() {
+        match word {
+            "today" => day = Some(today),
+            "tonight" => {
+                day = Some(today);
+                default_time = NaiveTime::from_hms_opt(20, 0, 0)?;
+            }
+            "tomorrow" => day = Some(today + Duration::days(1)),
+            "at" | "on" | "next" | "this" => {}
+            w => match parse_weekday(w) {
+                Some(weekday) => day = Some(next_weekday(today, weekday)),
+                None => time_words.push(w),
+            },
+        }
+    }
+
+    let time = if time_words.is_empty() {
+        None
+    } else {
+        Some(parse_time_of_day(&time_words.join(""))?)
+    };
+
+    let naive = match (day, time) {
+        (Some(day), time) => day.and_time(time.unwrap_or(default_time)),
+        (None, Some(time)) => {
+            // A bare time means the next time the clock shows it
+            let candidate = today.and_time(time);
+            if candidate > now.naive_local() {
+                candidate
+            } else {
+                (today + Duration::days(1)).and_time(time)
+            }
+        }
+        (None, None) => return None,
+    };
+
+    resolve(naive, now)
+}
+
+/// Parses "9", "9am", "9:30", "9:30pm", "15:00", "noon" and "midnight"
+fn parse_time_of_day(text: &str) -> Option<NaiveTime> {
+    match text {
+        "noon" | "midday" => return NaiveTime::from_hms_opt(12, 0, 0),
+        "midnight" => return Some(NaiveTime::MIN),
+        _ => {}
+    }
+
+    let (clock, meridiem) = if let Some(c) = text.strip_suffix("am") {
+        (c, Some(false))
+    } else if let Some(c) = text.strip_suffix("pm") {
+        (c, Some(true))
+    } else {
+        (text, None)
+    };
+
+    let (hour, minute) = match clock.split_once(':') {
+        Some((h, m)) => (h.parse::<u32>().ok()?, m.parse::<u32>().ok()?),
+        None => (clock.parse::<u32>().ok()?, 0),
+    };
+
+    let hour = match meridiem {
+        Some(pm) => {
+            if !(1..=12).contains(&hour) {
+                return None;
+            }
+            (hour % 12) + if pm { 12 } else { 0 }
+        }
+        None => hour,
+    };
+
+    NaiveTime::from_hms_opt(hour, minute, 0)
+}
+
+fn parse_weekday(word: &str) -> Option<Weekday> {
+    match word {
+        "monday" | "mon" => Some(Weekday::Mon),
+        "tuesday" | "tue" | "tues" => Some(Weekday::Tue),
+        "wednesday" | "wed" => Some(Weekday::Wed),
+        "thursday" | "thu" | "thurs" => Some(Weekday::Thu),
+        "friday" | "fri" => Some(Weekday::Fri),
+        "saturday" | "sat" => Some(Weekday::Sat),
+        "sunday" | "sun" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+/// The next date falling on `weekday`, never today
+fn next_weekday(today: NaiveDate, weekday: Weekday) -> NaiveDate {
+    let ahead = (weekday.num_days_from_monday() as i64 - today.weekday().num_days_from_monday() as i64)
+        .rem_euclid(7);
+    today + Duration::days(if ahead == 0 { 7 } else { ahead })
+}
+
+/// Converts a wall-clock time in `now`'s zone, the same way the scheduler
+/// resolves times skipped or repeated by a DST change
+fn resolve(naive: NaiveDateTime, now: &DateTime<Tz>) -> Option<DateTime<Tz>> {
+    let tz = now.timezone();
+    local_to_utc(naive, tz).map(|t| t.with_timezone(&tz))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use chrono_tz::Europe::Berlin;
+
+    /// Wednesday afternoon, four days before the clocks go forward
+    fn now() -> DateTime<Tz> {
+        Berlin.with_ymd_and_hms(2026, 3, 25, 14, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn understands_times() {
+        let cases = [
+            ("in 20 minutes", "2026-03-25 14:20"),
+            ("in 1h30m", "2026-03-25 15:30"),
+            ("in half an hour", "2026-03-25 14:30"),
+            ("in 2 days and 3 hours", "2026-03-27 17:00"),
+            ("an hour from now", "2026-03-25 15:00"),
+            ("tomorrow 9am", "2026-03-26 09:00"),
+            ("tomorrow", "2026-03-26 09:00"),
+            ("tonight", "2026-03-25 20:00"),
+            ("friday at 14:30", "2026-03-27 14:30"),
+            // Today is Wednesday, so this is next week's
+            ("wednesday 10:00", "2026-04-01 10:00"),
+            ("at 17:00", "2026-03-25 17:00"),
+            // Already past today
+            ("at 13:00", "2026-03-26 13:00"),
+            ("noon", "2026-03-26 12:00"),
+            ("9:30pm", "2026-03-25 21:30"),
+            // 02:30 doesn't exist that night
+            ("sunday 2:30am", "2026-03-29 03:30"),
+            ("2026-11-03 15:00", "2026-11-03 15:00"),
+            ("2026-11-03", "2026-11-03 09:00"),
+            ("2026-11-03T15:00:00Z", "2026-11-03 16:00"),
+        ];
+
+        for (input, expected) in cases {
+            let at = parse_when(input, &now()).unwrap_or_else(|e| panic!("{}: {}", input, e));
+            assert_eq!(at.format("%Y-%m-%d %H:%M").to_string(), expected, "{}", input);
+        }
+    }
+
+    #[test]
+    fn moves_past_half_hour_gaps_by_their_length() {
+        // Lord Howe Island moves its clocks from 02:00 to 02:30
+        let now = chrono_tz::Australia::Lord_Howe.with_ymd_and_hms(2026, 10, 1, 12, 0, 0).unwrap();
+        let at = parse_when("sunday 2:15am", &now).unwrap();
+        assert_eq!(at.format("%Y-%m-%d %H:%M").to_string(), "2026-10-04 02:45");
+    }
+
+    #[test]
+    fn rejects_what_it_cannot_read() {
+        for input in ["", "soon", "in a while", "in 20", "in 5 parsecs", "in 1.5 hours", "13pm", "tomorrow 25:00"] {
+            assert!(parse_when(input, &now()).is_err(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn rejects_durations_that_overflow() {
+        for input in [
+            // Wraps to 5 minutes when truncated to 32 bits
+            "in 4294967301 minutes",
+            "in 99999999999999999999 weeks",
+            // Fits a duration, but not a date
+            "in 2000000000 weeks",
+            "in 2000000000 weeks and 2000000000 weeks",
+        ] {
+            assert_eq!(parse_when(input, &now()), Err(TOO_LONG.to_string()), "{}", input);
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use crate::error::{AppError, AppResult};
+use crate::storage::Reminder;
+use notify_rust::{Notification, Timeout};
+use tokio::sync::oneshot;
+
+/// Snooze offered by the notification's button
+pub const NOTIFICATION_SNOOZE_MINUTES: i32 = 5;
+
+const DONE_ACTION: &str = "done";
+const SNOOZE_ACTION: &str = "snooze";
+
+/// What the user did with a notification
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationAction {
+    Done,
+    Snooze,
+    /// Closed or clicked without choosing a button
+    Closed,
+}
+
+impl NotificationAction {
+    fn parse(action: &str) -> Self {
+        match action {
+            DONE_ACTION => NotificationAction::Done,
+            SNOOZE_ACTION => NotificationAction::Snooze,
+            _ => NotificationAction::Closed,
+        }
+    }
+}
+
+/// Shows a desktop notification for the reminder that stays until it is
+/// answered. The receiver gets the button the user chose; it is dropped
+/// without a value where the platform doesn't report actions.
+pub fn show_notification(reminder: &Reminder) -> AppResult<oneshot::Receiver<NotificationAction>> {
+    let mut notification = Notification::new();
+    notification
+        .appname("MultiTask Reminder")
+        .summary(&reminder.name)
+        .body(reminder.message.as_deref().unwrap_or(""))
+        .action(DONE_ACTION, "Done")
+        .action(SNOOZE_ACTION, &format!("Snooze {} min", NOTIFICATION_SNOOZE_MINUTES))
+        .timeout(Timeout::Never);
+    if reminder.sound != "none" {
+        notification.sound_name("message-new-instant");
+    }
+
+    let handle = notification
+        .show()
+        .map_err(|e| AppError::Window(format!("Failed to show notification: {}", e)))?;
+
+    let (tx, rx) = oneshot::channel();
+    // Waiting for the answer blocks until the notification goes away
+    tokio::task::spawn_blocking(move || {
+        handle.wait_for_action(|action| {
+            let _ = tx.send(NotificationAction::parse(action));
+        });
+    });
+    Ok(rx)
+}
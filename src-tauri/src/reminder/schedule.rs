@@ -0,0 +1,976 @@
+use crate::error::{AppError, AppResult};
+use crate::storage::{Reminder, Schedule};
+use chrono::{
+    DateTime, Datelike, Duration, LocalResult, Months, NaiveDate, NaiveDateTime, NaiveTime, Offset,
+    TimeZone, Timelike, Utc, Weekday,
+};
+use chrono_tz::Tz;
+use std::fmt;
+use std::str::FromStr;
+
+/// How many periods an RRULE is expanded before giving up on finding an
+/// occurrence (e.g. "FREQ=YEARLY;BYMONTH=2;BYMONTHDAY=30")
+const MAX_RRULE_PERIODS: i64 = 10_000;
+
+/// How many candidates of a fixed-time schedule are checked against the
+/// active window before giving up
+const MAX_FILTERED_CANDIDATES: usize = 5_000;
+
+/// Checks that a schedule can be evaluated, e.g. before it is saved
+pub fn validate_schedule(schedule: &Schedule) -> AppResult<()> {
+    let result = match schedule {
+        Schedule::Interval => Ok(()),
+        Schedule::DailyTimes { times } if times.is_empty() => {
+            Err("At least one time of day is required".to_string())
+        }
+        Schedule::DailyTimes { times } => parse_times(times).map(|_| ()),
+        Schedule::Cron { expression } => parse_cron(expression).map(|_| ()),
+        Schedule::Rrule { rule } => rule.parse::<Rrule>().map(|_| ()),
+    };
+    result.map_err(|reason| AppError::validation("schedule", reason))
+}
+
+/// Looks up an IANA time zone such as "Europe/Berlin", ignoring case
+pub fn parse_timezone(field: &str, name: &str) -> AppResult<Tz> {
+    Tz::from_str_insensitive(name).map_err(|_| {
+        AppError::validation(field, format!("Unknown time zone '{}', expected a name like Europe/Berlin", name))
+    })
+}
+
+/// The zone the reminder's times of day are read in: its own, or `default_tz`
+pub fn reminder_timezone(reminder: &Reminder, default_tz: Tz) -> Tz {
+    reminder
+        .timezone
+        .as_deref()
+        .and_then(|name| name.parse().ok())
+        .unwrap_or(default_tz)
+}
+
+/// The next occurrence of a fixed-time schedule strictly after `after`,
+/// restricted to the reminder's active days and time window. Times of day are
+/// read in the reminder's zone, `default_tz` if it has none.
+///
+/// Returns `None` for interval schedules (those are relative to the last
+/// trigger and handled by the scheduler), for schedules that cannot be parsed
+/// and for schedules that have no further occurrences.
+pub fn next_occurrence(reminder: &Reminder, after: DateTime<Utc>, default_tz: Tz) -> Option<DateTime<Utc>> {
+    let tz = reminder_timezone(reminder, default_tz);
+    let in_window = |t: &DateTime<Utc>| is_within_active_window(reminder, *t, tz);
+
+    match &reminder.schedule {
+        Schedule::Interval => None,
+        Schedule::DailyTimes { times } => {
+            let times = parse_times(times).ok()?;
+            daily_times_after(times, after, tz)
+                .take(MAX_FILTERED_CANDIDATES)
+                .find(in_window)
+        }
+        Schedule::Cron { expression } => {
+            let schedule = parse_cron(expression).ok()?;
+            schedule
+                .after(&after.with_timezone(&tz))
+                .map(|t| t.with_timezone(&Utc))
+                .take(MAX_FILTERED_CANDIDATES)
+                .find(in_window)
+        }
+        Schedule::Rrule { rule } => {
+            let rrule = rule.parse::<Rrule>().ok()?;
+            let start = rrule.dtstart.unwrap_or_else(|| rrule_default_start(reminder, tz));
+            let mut candidates = rrule.occurrences_after(start, after, tz).take(MAX_FILTERED_CANDIDATES);
+            candidates.find(in_window)
+        }
+    }
+}
+
+/// Calls `visit` with every occurrence of a fixed-time schedule after `after`
+/// up to and including `until`, soonest first, with the same restrictions as
+/// `next_occurrence`. Walks the schedule once instead of searching again from
+/// each occurrence, which matters when catching up on a long absence.
+pub fn each_occurrence_between(
+    reminder: &Reminder,
+    after: DateTime<Utc>,
+    until: DateTime<Utc>,
+    default_tz: Tz,
+    visit: &mut dyn FnMut(DateTime<Utc>),
+) {
+    let tz = reminder_timezone(reminder, default_tz);
+    let mut walk = |times: &mut dyn Iterator<Item = DateTime<Utc>>| {
+        times
+            .take_while(|t| *t <= until)
+            .filter(|t| is_within_active_window(reminder, *t, tz))
+            .for_each(&mut *visit)
+    };
+
+    match &reminder.schedule {
+        Schedule::Interval => {}
+        Schedule::DailyTimes { times } => {
+            if let Ok(times) = parse_times(times) {
+                walk(&mut daily_times_after(times, after, tz));
+            }
+        }
+        Schedule::Cron { expression } => {
+            if let Ok(schedule) = parse_cron(expression) {
+                walk(&mut schedule.after(&after.with_timezone(&tz)).map(|t| t.with_timezone(&Utc)));
+            }
+        }
+        Schedule::Rrule { rule } => {
+            if let Ok(rrule) = rule.parse::<Rrule>() {
+                let start = rrule.dtstart.unwrap_or_else(|| rrule_default_start(reminder, tz));
+                walk(&mut rrule.occurrences_after(start, after, tz));
+            }
+        }
+    }
+}
+
+/// Whether the reminder's active days and time window include the given
+/// time, as seen in its zone (`default_tz` if it has none)
+pub fn is_within_active_window(reminder: &Reminder, at: DateTime<Utc>, default_tz: Tz) -> bool {
+    let at = at.with_timezone(&reminder_timezone(reminder, default_tz));
+
+    // Check day of week
+    if let Some(days) = &reminder.active_days {
+        let current_day = at.weekday().num_days_from_monday() as u8;
+        if !days.contains(&current_day) {
+            return false;
+        }
+    }
+
+    // Check time window
+    if let (Some(start_str), Some(end_str)) = (&reminder.active_start_time, &reminder.active_end_time) {
+        if let (Ok(start), Ok(end)) = (
+            NaiveTime::parse_from_str(start_str, "%H:%M"),
+            NaiveTime::parse_from_str(end_str, "%H:%M"),
+        ) {
+            let current_time = at.time();
+
+            if start <= end {
+                // Normal window (e.g., 09:00 to 18:00)
+                if current_time < start || current_time > end {
+                    return false;
+                }
+            } else {
+                // Overnight window (e.g., 22:00 to 06:00)
+                if current_time < start && current_time > end {
+                    return false;
+                }
+            }
+        }
+    }
+
+    true
+}
+
+/// The first time from `at` on that lies inside the reminder's active days
+/// and time window, looking a week ahead
+pub fn next_active_time(reminder: &Reminder, at: DateTime<Utc>, default_tz: Tz) -> Option<DateTime<Utc>> {
+    if is_within_active_window(reminder, at, default_tz) {
+        return Some(at);
+    }
+    let tz = reminder_timezone(reminder, default_tz);
+    let window_start = reminder
+        .active_start_time
+        .as_deref()
+        .and_then(|start| NaiveTime::parse_from_str(start, "%H:%M").ok());
+    let today = at.with_timezone(&tz).date_naive();
+
+    // The window opens at its start time, or at midnight on an active day it
+    // runs into
+    (0..=8)
+        .map(|days| today + Duration::days(days))
+        .flat_map(|date| [Some(NaiveTime::MIN), window_start].into_iter().flatten().map(move |time| date.and_time(time)))
+        .filter_map(|naive| local_to_utc(naive, tz))
+        .find(|t| *t > at && is_within_active_window(reminder, *t, tz))
+}
+
+fn parse_times(times: &[String]) -> Result<Vec<NaiveTime>, String> {
+    let mut parsed = times
+        .iter()
+        .map(|t| {
+            NaiveTime::parse_from_str(t, "%H:%M")
+                .map_err(|_| format!("Invalid time of day '{}', expected HH:MM", t))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    parsed.sort();
+    parsed.dedup();
+    Ok(parsed)
+}
+
+fn parse_cron(expression: &str) -> Result<cron::Schedule, String> {
+    // Accept the classic five field format by adding the seconds field the
+    // cron crate expects. Its numeric weekdays start at 1 for Sunday, so
+    // classic 0-7 weekdays are spelled out as names.
+    let fields: Vec<&str> = expression.split_whitespace().collect();
+    let expression = if fields.len() == 5 {
+        format!("0 {} {}", fields[..4].join(" "), classic_weekdays(fields[4]))
+    } else {
+        fields.join(" ")
+    };
+
+    cron::Schedule::from_str(&expression).map_err(|e| format!("Invalid cron expression: {}", e))
+}
+
+/// Rewrites classic cron weekday numbers (0 or 7 = Sunday) to names,
+/// leaving step values (`*/2`) alone
+fn classic_weekdays(field: &str) -> String {
+    const NAMES: [&str; 8] = ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT", "SUN"];
+
+    fn flush(number: &mut String, result: &mut String, after_step: bool) {
+        if number.is_empty() {
+            return;
+        }
+        match number.parse::<usize>() {
+            Ok(n) if !after_step && n < NAMES.len() => result.push_str(NAMES[n]),
+            _ => result.push_str(number),
+        }
+        number.clear();
+    }
+
+    let mut result = String::new();
+    let mut number = String::new();
+    let mut after_step = false;
+
+    for c in field.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+        } else {
+            flush(&mut number, &mut result, after_step);
+            after_step = c == '/';
+            result.push(c);
+        }
+    }
+    flush(&mut number, &mut result, after_step);
+
+    result
+}
+
+/// Every configured time of day in `tz`, starting with the first one after `after`
+fn daily_times_after(
+    times: Vec<NaiveTime>,
+    after: DateTime<Utc>,
+    tz: Tz,
+) -> impl Iterator<Item = DateTime<Utc>> {
+    let first_day = after.with_timezone(&tz).date_naive();
+
+    first_day
+        .iter_days()
+        .flat_map(move |day| {
+            times
+                .clone()
+                .into_iter()
+                .filter_map(move |time| local_to_utc(day.and_time(time), tz))
+        })
+        .filter(move |t| *t > after)
+}
+
+/// An RRULE without DTSTART is anchored at the reminder's creation time
+fn rrule_default_start(reminder: &Reminder, tz: Tz) -> NaiveDateTime {
+    DateTime::parse_from_rfc3339(&reminder.created_at)
+        .map(|t| t.with_timezone(&tz).naive_local())
+        .unwrap_or_else(|_| Utc::now().with_timezone(&tz).naive_local())
+        .with_second(0)
+        .and_then(|t| t.with_nanosecond(0))
+        .unwrap_or_default()
+}
+
+/// Resolves a wall-clock time in `tz` the way RFC 5545 does. A time repeated
+/// when the clocks go back is the first of the two. A time skipped when they
+/// go forward is read with the offset from before the change, which moves it
+/// later by the length of the gap (02:30 becomes 03:30).
+pub(crate) fn local_to_utc(naive: NaiveDateTime, tz: Tz) -> Option<DateTime<Utc>> {
+    match tz.from_local_datetime(&naive) {
+        LocalResult::Single(t) | LocalResult::Ambiguous(t, _) => Some(t.with_timezone(&Utc)),
+        LocalResult::None => {
+            // Gaps are shorter than a day
+            let before = (1..=24)
+                .map(|hours| naive - Duration::hours(hours))
+                .find_map(|t| tz.from_local_datetime(&t).latest())?;
+            let offset = before.offset().fix().local_minus_utc();
+            Some(Utc.from_utc_datetime(&(naive - Duration::seconds(offset as i64))))
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frequency {
+    Minutely,
+    Hourly,
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+impl Frequency {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Frequency::Minutely => "MINUTELY",
+            Frequency::Hourly => "HOURLY",
+            Frequency::Daily => "DAILY",
+            Frequency::Weekly => "WEEKLY",
+            Frequency::Monthly => "MONTHLY",
+            Frequency::Yearly => "YEARLY",
+        }
+    }
+}
+
+/// When a series ends. A floating UNTIL is a wall-clock time in the zone the
+/// rule is evaluated in, like DTSTART.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Until {
+    Utc(DateTime<Utc>),
+    Local(NaiveDateTime),
+}
+
+impl Until {
+    fn resolve(&self, tz: Tz) -> Option<DateTime<Utc>> {
+        match self {
+            Until::Utc(until) => Some(*until),
+            Until::Local(until) => local_to_utc(*until, tz),
+        }
+    }
+}
+
+/// The subset of RFC 5545 recurrence rules the scheduler can evaluate
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rrule {
+    pub freq: Frequency,
+    pub interval: u32,
+    /// Weekdays with an optional ordinal, e.g. `1MO` for the first Monday of the month
+    pub by_day: Vec<(Option<i32>, Weekday)>,
+    pub by_month_day: Vec<i32>,
+    pub by_month: Vec<u32>,
+    pub by_hour: Vec<u32>,
+    pub by_minute: Vec<u32>,
+    pub count: Option<u32>,
+    pub until: Option<Until>,
+    /// Local start time, taken from a `DTSTART` line in front of the rule
+    pub dtstart: Option<NaiveDateTime>,
+}
+
+impl Rrule {
+    pub fn new(freq: Frequency) -> Self {
+        Self {
+            freq,
+            interval: 1,
+            by_day: Vec::new(),
+            by_month_day: Vec::new(),
+            by_month: Vec::new(),
+            by_hour: Vec::new(),
+            by_minute: Vec::new(),
+            count: None,
+            until: None,
+            dtstart: None,
+        }
+    }
+
+    /// Occurrences strictly after `after` for a series starting at the time
+    /// `start` in `tz`
+    pub fn occurrences_after(
+        &self,
+        start: NaiveDateTime,
+        after: DateTime<Utc>,
+        tz: Tz,
+    ) -> impl Iterator<Item = DateTime<Utc>> + '_ {
+        // Without COUNT earlier periods cannot matter, so skip straight to
+        // the one containing `after`
+        let first_period = if self.count.is_none() {
+            let after_local = after.with_timezone(&tz).naive_local();
+            (self.period_index(start, after_local) - 1).max(0)
+        } else {
+            0
+        };
+
+        let until = self.until.and_then(|until| until.resolve(tz));
+        let mut remaining = self.count;
+        (first_period..first_period + MAX_RRULE_PERIODS)
+            .flat_map(move |period| self.expand(start, period))
+            .filter(move |t| *t >= start)
+            .take_while(move |_| match remaining.as_mut() {
+                Some(0) => false,
+                Some(n) => {
+                    *n -= 1;
+                    true
+                }
+                None => true,
+            })
+            .filter_map(move |t| local_to_utc(t, tz))
+            .take_while(move |t| until.is_none_or(|until| *t <= until))
+            .filter(move |t| *t > after)
+    }
+
+    /// Which period (counted in INTERVAL steps from `start`) contains `at`
+    fn period_index(&self, start: NaiveDateTime, at: NaiveDateTime) -> i64 {
+        let units = match self.freq {
+            Frequency::Minutely => (at - start).num_minutes(),
+            Frequency::Hourly => (at - start).num_hours(),
+            Frequency::Daily => (at.date() - start.date()).num_days(),
+            Frequency::Weekly => (week_start(at.date()) - week_start(start.date())).num_weeks(),
+            Frequency::Monthly => {
+                (at.year() - start.year()) as i64 * 12 + at.month() as i64 - start.month() as i64
+            }
+            Frequency::Yearly => (at.year() - start.year()) as i64,
+        };
+        units / self.interval as i64
+    }
+
+    /// All candidate times within one period, in ascending order
+    fn expand(&self, start: NaiveDateTime, period: i64) -> Vec<NaiveDateTime> {
+        let step = period * self.interval as i64;
+        let mut result = match self.freq {
+            Frequency::Minutely => {
+                let t = start + Duration::minutes(step);
+                if self.matches_date(t.date()) && self.matches_time(t) {
+                    vec![t]
+                } else {
+                    Vec::new()
+                }
+            }
+            Frequency::Hourly => {
+                let hour = (start + Duration::hours(step)).with_minute(0).unwrap_or(start);
+                if !self.matches_date(hour.date())
+                    || !(self.by_hour.is_empty() || self.by_hour.contains(&hour.hour()))
+                {
+                    return Vec::new();
+                }
+                self.minutes(start)
+                    .into_iter()
+                    .filter_map(|m| hour.with_minute(m))
+                    .collect()
+            }
+            Frequency::Daily => {
+                let day = start.date() + Duration::days(step);
+                if self.matches_date(day) {
+                    self.times_on(day, start)
+                } else {
+                    Vec::new()
+                }
+            }
+            Frequency::Weekly => {
+                let monday = week_start(start.date()) + Duration::weeks(step);
+                let weekdays: Vec<Weekday> = if self.by_day.is_empty() {
+                    vec![start.weekday()]
+                } else {
+                    self.by_day.iter().map(|(_, wd)| *wd).collect()
+                };
+                monday
+                    .iter_days()
+                    .take(7)
+                    .filter(|d| weekdays.contains(&d.weekday()) && self.matches_month(*d))
+                    .flat_map(|d| self.times_on(d, start))
+                    .collect()
+            }
+            Frequency::Monthly => {
+                let Some(first) = first_of_month(start.date()).checked_add_months(Months::new(step as u32)) else {
+                    return Vec::new();
+                };
+                if !self.matches_month(first) {
+                    return Vec::new();
+                }
+                self.days_in_month(first, start)
+                    .into_iter()
+                    .flat_map(|d| self.times_on(d, start))
+                    .collect()
+            }
+            Frequency::Yearly => {
+                let year = start.year() + step as i32;
+                let months = if self.by_month.is_empty() {
+                    vec![start.month()]
+                } else {
+                    self.by_month.clone()
+                };
+                months
+                    .into_iter()
+                    .filter_map(|m| NaiveDate::from_ymd_opt(year, m, 1))
+                    .flat_map(|first| self.days_in_month(first, start))
+                    .flat_map(|d| self.times_on(d, start))
+                    .collect()
+            }
+        };
+        result.sort();
+        result.dedup();
+        result
+    }
+
+    /// Days of the month starting at `first` selected by BYMONTHDAY / BYDAY
+    fn days_in_month(&self, first: NaiveDate, start: NaiveDateTime) -> Vec<NaiveDate> {
+        let days: Vec<NaiveDate> = first
+            .iter_days()
+            .take_while(|d| d.month() == first.month())
+            .collect();
+        let len = days.len() as i32;
+
+        let by_month_day = |d: &NaiveDate| {
+            let day = d.day() as i32;
+            self.by_month_day
+                .iter()
+                .any(|&md| md == day || (md < 0 && len + md + 1 == day))
+        };
+        let by_day = |d: &NaiveDate| {
+            let nth = (d.day() as i32 - 1) / 7 + 1;
+            let nth_from_end = (len - d.day() as i32) / 7 + 1;
+            self.by_day.iter().any(|(ordinal, wd)| {
+                *wd == d.weekday()
+                    && match ordinal {
+                        None => true,
+                        Some(n) if *n > 0 => *n == nth,
+                        Some(n) => -*n == nth_from_end,
+                    }
+            })
+        };
+
+        days.into_iter()
+            .filter(|d| match (self.by_month_day.is_empty(), self.by_day.is_empty()) {
+                (true, true) => d.day() == start.day(),
+                (false, true) => by_month_day(d),
+                (true, false) => by_day(d),
+                (false, false) => by_month_day(d) && by_day(d),
+            })
+            .collect()
+    }
+
+    fn times_on(&self, day: NaiveDate, start: NaiveDateTime) -> Vec<NaiveDateTime> {
+        let hours = if self.by_hour.is_empty() {
+            vec![start.hour()]
+        } else {
+            self.by_hour.clone()
+        };
+        let minutes = self.minutes(start);
+
+        hours
+            .iter()
+            .flat_map(|h| minutes.iter().filter_map(move |m| NaiveTime::from_hms_opt(*h, *m, 0)))
+            .map(|t| day.and_time(t))
+            .collect()
+    }
+
+    fn minutes(&self, start: NaiveDateTime) -> Vec<u32> {
+        if self.by_minute.is_empty() {
+            vec![start.minute()]
+        } else {
+            self.by_minute.clone()
+        }
+    }
+
+    /// Date filters for the frequencies that don't expand by day
+    fn matches_date(&self, day: NaiveDate) -> bool {
+        self.matches_month(day)
+            && (self.by_day.is_empty() || self.by_day.iter().any(|(_, wd)| *wd == day.weekday()))
+            && (self.by_month_day.is_empty() || self.by_month_day.contains(&(day.day() as i32)))
+    }
+
+    fn matches_month(&self, day: NaiveDate) -> bool {
+        self.by_month.is_empty() || self.by_month.contains(&day.month())
+    }
+
+    fn matches_time(&self, t: NaiveDateTime) -> bool {
+        (self.by_hour.is_empty() || self.by_hour.contains(&t.hour()))
+            && (self.by_minute.is_empty() || self.by_minute.contains(&t.minute()))
+    }
+}
+
+impl FromStr for Rrule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut dtstart = None;
+        let mut rule = None;
+
+        for line in s.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            if let Some(value) = line.strip_prefix("DTSTART:") {
+                dtstart = Some(parse_ical_local(value)?);
+            } else {
+                rule = Some(line.strip_prefix("RRULE:").unwrap_or(line));
+            }
+        }
+
+        let rule = rule.ok_or("Recurrence rule is empty")?;
+        let mut freq = None;
+        let mut parts = Vec::new();
+
+        for part in rule.split(';').filter(|p| !p.is_empty()) {
+            let (key, value) = part
+                .split_once('=')
+                .ok_or_else(|| format!("Invalid recurrence rule part '{}'", part))?;
+            if key.eq_ignore_ascii_case("FREQ") {
+                freq = Some(match value.to_ascii_uppercase().as_str() {
+                    "MINUTELY" => Frequency::Minutely,
+                    "HOURLY" => Frequency::Hourly,
+                    "DAILY" => Frequency::Daily,
+                    "WEEKLY" => Frequency::Weekly,
+                    "MONTHLY" => Frequency::Monthly,
+                    "YEARLY" => Frequency::Yearly,
+                    other => return Err(format!("Unsupported recurrence frequency '{}'", other)),
+                });
+            } else {
+                parts.push((key.to_ascii_uppercase(), value));
+            }
+        }
+
+        let mut rrule = Rrule::new(freq.ok_or("Recurrence rule has no FREQ")?);
+        rrule.dtstart = dtstart;
+
+        for (key, value) in parts {
+            match key.as_str() {
+                "INTERVAL" => {
+                    rrule.interval = parse_number(&key, value, 1, 10_000)?;
+                }
+                "COUNT" => rrule.count = Some(parse_number(&key, value, 1, u32::MAX)?),
+                "UNTIL" => {
+                    let until = parse_ical_local(value)?;
+                    rrule.until = Some(if value.trim().ends_with('Z') {
+                        Until::Utc(Utc.from_utc_datetime(&until))
+                    } else {
+                        Until::Local(until)
+                    });
+                }
+                "BYDAY" => {
+                    rrule.by_day = value
+                        .split(',')
+                        .map(parse_by_day)
+                        .collect::<Result<_, _>>()?;
+                }
+                "BYMONTHDAY" => {
+                    rrule.by_month_day = parse_list(&key, value, -31, 31)?;
+                    if rrule.by_month_day.contains(&0) {
+                        return Err("BYMONTHDAY cannot be 0".to_string());
+                    }
+                }
+                "BYMONTH" => rrule.by_month = parse_list(&key, value, 1, 12)?,
+                "BYHOUR" => rrule.by_hour = parse_list(&key, value, 0, 23)?,
+                "BYMINUTE" => rrule.by_minute = parse_list(&key, value, 0, 59)?,
+                "WKST" if value.eq_ignore_ascii_case("MO") => {}
+                other => return Err(format!("Unsupported recurrence rule part '{}'", other)),
+            }
+        }
+
+        if rrule.count.is_some() && rrule.until.is_some() {
+            return Err("COUNT and UNTIL cannot be combined".to_string());
+        }
+        let has_ordinals = rrule.by_day.iter().any(|(ordinal, _)| ordinal.is_some());
+        let ordinals_allowed = match rrule.freq {
+            Frequency::Monthly => true,
+            Frequency::Yearly => !rrule.by_month.is_empty(),
+            _ => false,
+        };
+        if has_ordinals && !ordinals_allowed {
+            return Err("Numbered BYDAY values are only supported for monthly rules or yearly rules with BYMONTH".to_string());
+        }
+
+        Ok(rrule)
+    }
+}
+
+impl fmt::Display for Rrule {
+    /// Formats the rule part only (without `RRULE:` and `DTSTART`)
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "FREQ={}", self.freq.as_str())?;
+        if self.interval != 1 {
+            write!(f, ";INTERVAL={}", self.interval)?;
+        }
+        if let Some(count) = self.count {
+            write!(f, ";COUNT={}", count)?;
+        }
+        match self.until {
+            Some(Until::Utc(until)) => write!(f, ";UNTIL={}", until.format("%Y%m%dT%H%M%SZ"))?,
+            Some(Until::Local(until)) => write!(f, ";UNTIL={}", until.format("%Y%m%dT%H%M%S"))?,
+            None => {}
+        }
+        if !self.by_month.is_empty() {
+            write!(f, ";BYMONTH={}", join(&self.by_month))?;
+        }
+        if !self.by_month_day.is_empty() {
+            write!(f, ";BYMONTHDAY={}", join(&self.by_month_day))?;
+        }
+        if !self.by_day.is_empty() {
+            let days: Vec<String> = self
+                .by_day
+                .iter()
+                .map(|(ordinal, wd)| match ordinal {
+                    Some(n) => format!("{}{}", n, weekday_code(*wd)),
+                    None => weekday_code(*wd).to_string(),
+                })
+                .collect();
+            write!(f, ";BYDAY={}", days.join(","))?;
+        }
+        if !self.by_hour.is_empty() {
+            write!(f, ";BYHOUR={}", join(&self.by_hour))?;
+        }
+        if !self.by_minute.is_empty() {
+            write!(f, ";BYMINUTE={}", join(&self.by_minute))?;
+        }
+        Ok(())
+    }
+}
+
+pub fn weekday_code(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "MO",
+        Weekday::Tue => "TU",
+        Weekday::Wed => "WE",
+        Weekday::Thu => "TH",
+        Weekday::Fri => "FR",
+        Weekday::Sat => "SA",
+        Weekday::Sun => "SU",
+    }
+}
+
+/// Parses an iCalendar DATE or DATE-TIME value (`20261103` / `20261103T150000[Z]`)
+pub fn parse_ical_local(value: &str) -> Result<NaiveDateTime, String> {
+    let trimmed = value.trim().trim_end_matches('Z');
+    NaiveDateTime::parse_from_str(trimmed, "%Y%m%dT%H%M%S")
+        .or_else(|_| {
+            NaiveDate::parse_from_str(trimmed, "%Y%m%d")
+                .map(|d| d.and_time(NaiveTime::MIN))
+        })
+        .map_err(|_| format!("Invalid date-time '{}'", value))
+}
+
+fn parse_by_day(value: &str) -> Result<(Option<i32>, Weekday), String> {
+    let value = value.trim().to_ascii_uppercase();
+    // The split below counts bytes
+    if !value.is_ascii() {
+        return Err(format!("Invalid BYDAY value '{}'", value));
+    }
+    let split = value.len().saturating_sub(2);
+    let (ordinal, code) = value.split_at(split);
+
+    let weekday = match code {
+        "MO" => Weekday::Mon,
+        "TU" => Weekday::Tue,
+        "WE" => Weekday::Wed,
+        "TH" => Weekday::Thu,
+        "FR" => Weekday::Fri,
+        "SA" => Weekday::Sat,
+        "SU" => Weekday::Sun,
+        _ => return Err(format!("Invalid BYDAY value '{}'", value)),
+    };
+    let ordinal = if ordinal.is_empty() {
+        None
+    } else {
+        match ordinal.trim_start_matches('+').parse::<i32>() {
+            Ok(n) if n != 0 && (-5..=5).contains(&n) => Some(n),
+            _ => return Err(format!("Invalid BYDAY value '{}'", value)),
+        }
+    };
+
+    Ok((ordinal, weekday))
+}
+
+fn parse_number<T>(key: &str, value: &str, min: T, max: T) -> Result<T, String>
+where
+    T: FromStr + PartialOrd,
+{
+    value
+        .trim()
+        .parse::<T>()
+        .ok()
+        .filter(|n| *n >= min && *n <= max)
+        .ok_or_else(|| format!("Invalid {} value '{}'", key, value))
+}
+
+fn parse_list<T>(key: &str, value: &str, min: T, max: T) -> Result<Vec<T>, String>
+where
+    T: FromStr + PartialOrd + Copy,
+{
+    value.split(',').map(|v| parse_number(key, v, min, max)).collect()
+}
+
+fn join<T: ToString>(values: &[T]) -> String {
+    values.iter().map(ToString::to_string).collect::<Vec<_>>().join(",")
+}
+
+fn week_start(day: NaiveDate) -> NaiveDate {
+    day - Duration::days(day.weekday().num_days_from_monday() as i64)
+}
+
+fn first_of_month(day: NaiveDate) -> NaiveDate {
+    day.with_day(1).unwrap_or(day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono_tz::Europe::Berlin;
+
+    fn naive(month: u32, day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, month, day)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+    }
+
+    /// The first `count` occurrences after `after` as Berlin wall-clock times
+    fn occurrences(rule: &str, after: NaiveDateTime, count: usize) -> Vec<String> {
+        let rrule: Rrule = rule.parse().unwrap();
+        let after = local_to_utc(after, Berlin).unwrap();
+        rrule
+            .occurrences_after(rrule.dtstart.unwrap(), after, Berlin)
+            .take(count)
+            .map(|t| t.with_timezone(&Berlin).format("%Y-%m-%d %H:%M").to_string())
+            .collect()
+    }
+
+    #[test]
+    fn rules_round_trip() {
+        for rule in [
+            "FREQ=DAILY",
+            "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,WE",
+            "FREQ=MONTHLY;COUNT=5;BYDAY=1MO,-1FR",
+            "FREQ=MONTHLY;BYMONTHDAY=1,-1",
+            "FREQ=YEARLY;UNTIL=20301231T230000Z;BYMONTH=3;BYDAY=-1SU",
+            "FREQ=DAILY;UNTIL=20301231T230000",
+            "FREQ=HOURLY;BYHOUR=9,17;BYMINUTE=0,30",
+        ] {
+            let parsed: Rrule = rule.parse().unwrap();
+            assert_eq!(parsed.to_string(), rule);
+        }
+
+        let parsed: Rrule = "DTSTART:20260302T091500\nRRULE:freq=weekly;interval=3;byday=mo;wkst=mo".parse().unwrap();
+        assert_eq!(parsed.to_string(), "FREQ=WEEKLY;INTERVAL=3;BYDAY=MO");
+        assert_eq!(parsed.dtstart, Some(naive(3, 2, 9, 15)));
+        let monthly: Rrule = "FREQ=MONTHLY;BYDAY=+2TU".parse().unwrap();
+        assert_eq!(monthly.by_day, [(Some(2), Weekday::Tue)]);
+    }
+
+    #[test]
+    fn rejects_invalid_rules() {
+        for rule in [
+            "",
+            "INTERVAL=2",
+            "FREQ=SECONDLY",
+            "FREQ=DAILY;INTERVAL=0",
+            "FREQ=DAILY;COUNT",
+            "FREQ=DAILY;BYSETPOS=1",
+            "FREQ=DAILY;BYHOUR=24",
+            "FREQ=MONTHLY;BYMONTHDAY=0",
+            "FREQ=MONTHLY;BYMONTHDAY=32",
+            "FREQ=MONTHLY;BYDAY=6MO",
+            "FREQ=MONTHLY;BYDAY=XX",
+            "FREQ=MONTHLY;BYDAY=éa",
+            "FREQ=MONTHLY;BYDAY=1Mé",
+            "FREQ=WEEKLY;BYDAY=1MO",
+            "FREQ=YEARLY;BYDAY=1MO",
+            "FREQ=DAILY;COUNT=2;UNTIL=20300101T000000Z",
+            "DTSTART:tomorrow\nRRULE:FREQ=DAILY",
+        ] {
+            assert!(rule.parse::<Rrule>().is_err(), "{:?} should be rejected", rule);
+        }
+    }
+
+    #[test]
+    fn expands_numbered_weekdays() {
+        let rule = "DTSTART:20260105T093000\nRRULE:FREQ=MONTHLY;BYDAY=1MO,-1FR";
+
+        assert_eq!(
+            occurrences(rule, naive(1, 1, 0, 0), 4),
+            ["2026-01-05 09:30", "2026-01-30 09:30", "2026-02-02 09:30", "2026-02-27 09:30"]
+        );
+    }
+
+    #[test]
+    fn negative_month_days_count_from_the_end() {
+        let rule = "DTSTART:20260131T080000\nRRULE:FREQ=MONTHLY;BYMONTHDAY=-1";
+
+        assert_eq!(
+            occurrences(rule, naive(1, 1, 0, 0), 4),
+            ["2026-01-31 08:00", "2026-02-28 08:00", "2026-03-31 08:00", "2026-04-30 08:00"]
+        );
+    }
+
+    #[test]
+    fn count_and_until_end_the_series() {
+        let counted = "DTSTART:20260302T070000\nRRULE:FREQ=DAILY;COUNT=3";
+        assert_eq!(
+            occurrences(counted, naive(3, 1, 0, 0), 10),
+            ["2026-03-02 07:00", "2026-03-03 07:00", "2026-03-04 07:00"]
+        );
+        // COUNT includes the occurrences before `after`
+        assert_eq!(occurrences(counted, naive(3, 3, 7, 0), 10), ["2026-03-04 07:00"]);
+
+        // 10:00 in Berlin is 09:00 UTC, so the last one is exactly at UNTIL
+        let until = "DTSTART:20260302T100000\nRRULE:FREQ=WEEKLY;UNTIL=20260316T090000Z";
+        assert_eq!(
+            occurrences(until, naive(3, 1, 0, 0), 10),
+            ["2026-03-02 10:00", "2026-03-09 10:00", "2026-03-16 10:00"]
+        );
+        // A floating UNTIL is read in the same zone as DTSTART
+        let floating = "DTSTART:20260302T100000\nRRULE:FREQ=WEEKLY;UNTIL=20260316T093000";
+        assert_eq!(occurrences(floating, naive(3, 1, 0, 0), 10), ["2026-03-02 10:00", "2026-03-09 10:00"]);
+        let rrule: Rrule = floating.parse().unwrap();
+        let new_york = chrono_tz::America::New_York;
+        let after = local_to_utc(naive(3, 1, 0, 0), new_york).unwrap();
+        let last = rrule.occurrences_after(naive(3, 2, 10, 0), after, new_york).last().unwrap();
+        assert_eq!(last.with_timezone(&new_york).naive_local(), naive(3, 9, 10, 0));
+    }
+
+    #[test]
+    fn intervals_skip_periods() {
+        let rule = "DTSTART:20260302T080000\nRRULE:FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,TH";
+
+        assert_eq!(
+            occurrences(rule, naive(3, 1, 0, 0), 4),
+            ["2026-03-02 08:00", "2026-03-05 08:00", "2026-03-16 08:00", "2026-03-19 08:00"]
+        );
+        // Later searches start in the right period, across the DST change
+        assert_eq!(occurrences(rule, naive(3, 20, 0, 0), 2), ["2026-03-30 08:00", "2026-04-02 08:00"]);
+
+        let hourly: Rrule = "FREQ=HOURLY;INTERVAL=3;BYMINUTE=0,30".parse().unwrap();
+        assert_eq!(hourly.expand(naive(3, 2, 9, 0), 1), [naive(3, 2, 12, 0), naive(3, 2, 12, 30)]);
+    }
+
+    #[test]
+    fn cron_accepts_classic_and_seconds_fields() {
+        // Saturday
+        let after = Berlin.with_ymd_and_hms(2026, 3, 7, 0, 0, 0).unwrap();
+        let next = |expression: &str| parse_cron(expression).unwrap().after(&after).next().unwrap().naive_local();
+
+        assert_eq!(next("30 9 * * 1-5"), naive(3, 9, 9, 30));
+        assert_eq!(next("0 12 * * 0"), naive(3, 8, 12, 0));
+        assert_eq!(next("0 12 * * 7"), naive(3, 8, 12, 0));
+        assert_eq!(next("15 30 9 * * MON"), naive(3, 9, 9, 30) + Duration::seconds(15));
+
+        assert!(parse_cron("every day").is_err());
+        assert!(parse_cron("61 * * * *").is_err());
+    }
+
+    #[test]
+    fn classic_weekday_numbers_become_names() {
+        for (field, expected) in [
+            ("0", "SUN"),
+            ("7", "SUN"),
+            ("1-5", "MON-FRI"),
+            ("1,3,5", "MON,WED,FRI"),
+            ("0-6/2", "SUN-SAT/2"),
+            ("*/2", "*/2"),
+            ("*", "*"),
+            ("MON-FRI", "MON-FRI"),
+        ] {
+            assert_eq!(classic_weekdays(field), expected, "{}", field);
+        }
+    }
+
+    #[test]
+    fn validates_every_kind_of_schedule() {
+        let times = |times: &[&str]| Schedule::DailyTimes { times: times.iter().map(|t| t.to_string()).collect() };
+        let valid = [
+            Schedule::Interval,
+            times(&["08:00", "20:00"]),
+            Schedule::Cron { expression: "0 9 * * 1-5".to_string() },
+            Schedule::Rrule { rule: "FREQ=WEEKLY;BYDAY=MO".to_string() },
+        ];
+        let invalid = [
+            times(&[]),
+            times(&["08:00", "8pm"]),
+            Schedule::Cron { expression: "0 9 * *".to_string() },
+            Schedule::Rrule { rule: "FREQ=WEEKLY;BYDAY=1MO".to_string() },
+        ];
+
+        for schedule in valid {
+            assert!(validate_schedule(&schedule).is_ok(), "{:?}", schedule);
+        }
+        for schedule in invalid {
+            let error = validate_schedule(&schedule).expect_err("schedule is invalid");
+            assert_eq!(error.field(), Some("schedule"), "{:?}", schedule);
+        }
+    }
+}
@@ -1,21 +1,298 @@
-use crate::storage::{get_enabled_reminders, update_last_triggered, Reminder};
-use chrono::{DateTime, Local, NaiveTime, Utc, Datelike};
+use crate::error::{AppError, AppResult};
+use crate::storage::{
+    dismiss_reminder, get_enabled_reminders, get_pause_state, get_reminder_by_id, get_settings,
+    record_event, set_pause_state, snooze_reminder, update_last_triggered, CatchUpPolicy, DeliveryStyle,
+    EscalationAction, EscalationStep, EventKind, PausePolicy, PauseState, Reminder, Schedule, Settings,
+};
+use super::clock::{system_timezone, Clock, SystemClock};
+use super::command::run_reminder_command;
+use super::notification::{show_notification, NotificationAction, NOTIFICATION_SNOOZE_MINUTES};
+use super::schedule::{each_occurrence_between, is_within_active_window, next_active_time, next_occurrence};
+use super::sleep::watch_sleep;
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
 use once_cell::sync::Lazy;
 use parking_lot::Mutex;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, VecDeque};
 use std::time::Duration;
 use tauri::{AppHandle, Emitter, Manager, WebviewUrl, WebviewWindowBuilder};
-use tokio::sync::mpsc;
+use tokio::sync::{broadcast, mpsc};
 use tokio::time::sleep;
 
 static SCHEDULER_TX: Lazy<Mutex<Option<mpsc::Sender<SchedulerCommand>>>> = Lazy::new(|| Mutex::new(None));
+static STATUS: Lazy<Mutex<SchedulerStatus>> = Lazy::new(|| Mutex::new(SchedulerStatus::default()));
+static TRIGGERED: Lazy<broadcast::Sender<Reminder>> = Lazy::new(|| broadcast::channel(64).0);
+
+/// Longest timed pause, about a year
+const MAX_PAUSE_MINUTES: i64 = 366 * 24 * 60;
+
+/// How far the wall clock may drift from the monotonic one between two
+/// wake-ups before the scheduler treats it as a jump
+const CLOCK_JUMP_TOLERANCE_SECONDS: i64 = 10;
+
+/// Occurrences `get_upcoming_triggers` returns unless asked for a count
+pub const DEFAULT_UPCOMING_TRIGGERS: usize = 10;
+/// Most occurrences `get_upcoming_triggers` returns at once
+pub const MAX_UPCOMING_TRIGGERS: usize = 100;
+
+/// When a reminder fires next, as planned by the running scheduler
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlannedTrigger {
+    pub reminder_id: i64,
+    pub name: String,
+    pub at: String,
+}
+
+/// Snapshot of the scheduler, taken each time it wakes up
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SchedulerStatus {
+    pub running: bool,
+    pub pause: PauseState,
+    pub enabled_reminders: usize,
+    /// Reminders that fired and are waiting to be dismissed or snoozed
+    pub pending_reminders: usize,
+    /// Soonest first. Interval reminders outside their active window are
+    /// listed but wait for the window to open.
+    pub next_triggers: Vec<PlannedTrigger>,
+}
+
+/// A fired reminder that hasn't been answered yet and still has escalation
+/// steps to go
+struct Outstanding {
+    reminder: Reminder,
+    triggered_at: DateTime<Utc>,
+    /// Index of the next step in `reminder.escalation`
+    next_step: usize,
+    /// Changed by `ChangeSound` steps
+    sound: String,
+}
+
+impl Outstanding {
+    fn next_step_at(&self) -> Option<DateTime<Utc>> {
+        self.reminder
+            .escalation
+            .get(self.next_step)
+            .map(|step| self.triggered_at + chrono::Duration::minutes(step.after_minutes as i64))
+    }
+}
 
 #[derive(Debug)]
 pub enum SchedulerCommand {
     Refresh,
+    /// Pause all reminders, either until the given time or indefinitely
+    Pause { until: Option<DateTime<Utc>> },
+    Resume,
+    /// Fire a reminder right away, outside its schedule
+    TriggerNow { id: i64 },
+    /// The machine woke up from suspend
+    Woke,
     Stop,
 }
 
+/// Where the scheduler sends reminders as they come due
+pub trait TriggerSink {
+    /// The reminder is due, `metadata` describes the occurrence
+    fn fire(&mut self, reminder: &Reminder, metadata: serde_json::Value);
+    /// Occurrences that passed without firing, e.g. while the app was closed
+    fn missed(&mut self, reminder: &Reminder, metadata: serde_json::Value);
+}
+
+/// Shows fired reminders in the app and keeps them until they are answered
+struct AppSink<'a> {
+    app_handle: &'a AppHandle,
+    outstanding: &'a mut HashMap<i64, Outstanding>,
+}
+
+impl TriggerSink for AppSink<'_> {
+    fn fire(&mut self, reminder: &Reminder, metadata: serde_json::Value) {
+        fire(self.app_handle, reminder, metadata, self.outstanding);
+    }
+    
+    fn missed(&mut self, reminder: &Reminder, metadata: serde_json::Value) {
+        if let Err(e) = record_event(reminder.id, EventKind::Missed, Some(metadata)) {
+            eprintln!("Failed to record missed occurrences: {}", e);
+        }
+    }
+}
+
+/// Decides when reminders fire. Loading reminders and showing them is left
+/// to the caller, so this runs the same against a fake clock.
+struct Planner {
+    next_triggers: HashMap<i64, DateTime<Utc>>,
+    /// Missed occurrences still to fire, oldest first
+    catch_up_queue: HashMap<i64, VecDeque<DateTime<Utc>>>,
+    catch_up_due: bool,
+    /// Set when the machine reports waking up
+    woke: bool,
+    /// Monotonic and wall-clock time at the last clock check
+    clock_mark: (Duration, DateTime<Utc>),
+    /// Zone for reminders without their own, the clock's if `None`
+    default_timezone: Option<Tz>,
+}
+
+impl Planner {
+    fn new(clock: &dyn Clock) -> Self {
+        Self {
+            next_triggers: HashMap::new(),
+            catch_up_queue: HashMap::new(),
+            catch_up_due: true,
+            woke: false,
+            clock_mark: (clock.monotonic(), clock.now()),
+            default_timezone: None,
+        }
+    }
+    
+    /// Plans every reminder again from its stored state
+    fn refresh(&mut self) {
+        self.next_triggers.clear();
+    }
+    
+    /// The zone reminders without their own are read in
+    fn timezone(&self, clock: &dyn Clock) -> Tz {
+        self.default_timezone.unwrap_or_else(|| clock.timezone())
+    }
+    
+    /// Compares the clocks since the last check. The monotonic clock stops
+    /// while the machine sleeps and ignores changes to the wall clock, so a
+    /// difference means planned times are stale. Returns how far the wall
+    /// clock jumped if everything was rescheduled.
+    fn check_clock(&mut self, clock: &dyn Clock) -> Option<chrono::Duration> {
+        let (monotonic, wall) = (clock.monotonic(), clock.now());
+        let elapsed = chrono::Duration::from_std(monotonic.saturating_sub(self.clock_mark.0)).unwrap_or_default();
+        let jump = (wall - self.clock_mark.1) - elapsed;
+        self.clock_mark = (monotonic, wall);
+        
+        if !self.woke && jump.num_seconds().abs() <= CLOCK_JUMP_TOLERANCE_SECONDS {
+            return None;
+        }
+        self.woke = false;
+        // Whatever passed meanwhile is caught up
+        self.next_triggers.clear();
+        self.catch_up_due = true;
+        Some(jump)
+    }
+    
+    /// Fires whatever is due and returns how long to wait before looking again
+    fn tick(
+        &mut self,
+        reminders: &[Reminder],
+        pause: &PauseState,
+        clock: &dyn Clock,
+        sink: &mut dyn TriggerSink,
+    ) -> Duration {
+        let now = clock.now();
+        let tz = self.timezone(clock);
+        
+        // Deal with what was missed while the app was closed or the machine
+        // was asleep. Occurrences missed during a pause are left to the pause
+        // policy.
+        if self.catch_up_due {
+            if !pause.paused {
+                catch_up(reminders, now, tz, &mut self.catch_up_queue, sink);
+            }
+            self.catch_up_due = false;
+        }
+        
+        // Calculate next trigger times
+        for reminder in reminders {
+            if let Entry::Vacant(entry) = self.next_triggers.entry(reminder.id) {
+                if let Some(next) = calculate_next_trigger(reminder, now, tz) {
+                    entry.insert(next);
+                }
+            }
+        }
+        
+        // Remove triggers for deleted/disabled reminders
+        let active_ids: Vec<i64> = reminders.iter().map(|r| r.id).collect();
+        self.next_triggers.retain(|id, _| active_ids.contains(id));
+        self.catch_up_queue.retain(|id, queue| active_ids.contains(id) && !queue.is_empty());
+        
+        if pause.paused {
+            // Nothing fires while paused, just wake up in time for the end of the pause
+            return match pause_until(pause) {
+                Some(until) => Duration::from_millis(
+                    until.signed_duration_since(now).num_milliseconds().clamp(0, 60000) as u64,
+                ),
+                None => Duration::from_secs(60),
+            };
+        }
+        
+        // Caught-up occurrences fire one at a time, each once the previous
+        // one was answered
+        let mut fired_catch_up = false;
+        for reminder in reminders {
+            if reminder.pending_since.is_some() {
+                continue;
+            }
+            if let Some(at) = self.catch_up_queue.get_mut(&reminder.id).and_then(VecDeque::pop_front) {
+                let metadata = serde_json::json!({
+                    "scheduled_for": at.to_rfc3339(),
+                    "snoozed": false,
+                    "catch_up": true,
+                });
+                sink.fire(reminder, metadata);
+                fired_catch_up = true;
+            }
+        }
+        
+        // Find the next reminder to trigger
+        let mut soonest: Option<(DateTime<Utc>, &Reminder)> = None;
+        
+        for reminder in reminders {
+            if let Some(next_time) = self.next_triggers.get(&reminder.id) {
+                // Fixed-time schedules only produce occurrences inside the
+                // active window, interval reminders wait for it to open
+                let in_window = reminder.schedule != Schedule::Interval
+                    || is_within_active_window(reminder, now, tz);
+                if in_window && soonest.is_none_or(|(soonest_time, _)| *next_time < soonest_time) {
+                    soonest = Some((*next_time, reminder));
+                }
+            }
+        }
+        
+        let sleep_duration = if let Some((next_time, reminder)) = soonest {
+            let duration = next_time.signed_duration_since(now);
+            
+            if duration.num_milliseconds() <= 0 {
+                // Trigger now!
+                let metadata = serde_json::json!({
+                    "scheduled_for": next_time.to_rfc3339(),
+                    "snoozed": reminder.snoozed_until.is_some(),
+                });
+                sink.fire(reminder, metadata);
+                
+                // Calculate next trigger time, counting from this trigger like
+                // the stored state does. The snooze that scheduled it (if any)
+                // has been consumed by it.
+                let mut triggered = reminder.clone();
+                triggered.last_triggered = Some(now.to_rfc3339());
+                triggered.snoozed_until = None;
+                match calculate_next_trigger(&triggered, now, tz) {
+                    Some(next) => self.next_triggers.insert(reminder.id, next),
+                    None => self.next_triggers.remove(&reminder.id),
+                };
+                
+                Duration::from_millis(100) // Small delay before next check
+            } else {
+                // Sleep until next trigger (max 1 minute to allow for refresh)
+                Duration::from_millis(duration.num_milliseconds().min(60000) as u64)
+            }
+        } else {
+            // No reminders, sleep for a bit
+            Duration::from_secs(10)
+        };
+        
+        // Silent reminders don't wait for an answer before their next catch-up
+        if fired_catch_up {
+            sleep_duration.min(Duration::from_millis(100))
+        } else {
+            sleep_duration
+        }
+    }
+}
+
 pub async fn start_scheduler(app_handle: AppHandle) {
     let (tx, mut rx) = mpsc::channel::<SchedulerCommand>(32);
     
@@ -25,81 +302,122 @@ pub async fn start_scheduler(app_handle: AppHandle) {
     }
     
     let handle = app_handle.clone();
-    let mut next_triggers: HashMap<i64, DateTime<Utc>> = HashMap::new();
+    let clock = SystemClock;
+    let mut planner = Planner::new(&clock);
+    planner.default_timezone = load_default_timezone();
+    let mut outstanding: HashMap<i64, Outstanding> = HashMap::new();
+    // Forces the first count out
+    let mut published_pending = usize::MAX;
+    let mut pause = get_pause_state().unwrap_or_else(|e| {
+        eprintln!("Failed to load pause state: {}", e);
+        PauseState::default()
+    });
+    
+    // Reschedule as soon as the machine is back, rather than once the current
+    // sleep runs out
+    tokio::spawn(async {
+        if let Err(e) = watch_sleep(wake_scheduler).await {
+            eprintln!("Not watching for suspend: {}", e);
+        }
+    });
     
     loop {
-            // Load enabled reminders
-            let reminders = match get_enabled_reminders() {
-                Ok(r) => r,
-                Err(e) => {
-                    eprintln!("Failed to load reminders: {}", e);
-                    sleep(Duration::from_secs(5)).await;
-                    continue;
-                }
-            };
-            
-            // Calculate next trigger times
-            for reminder in &reminders {
-                if !next_triggers.contains_key(&reminder.id) {
-                    let next = calculate_next_trigger(reminder);
-                    next_triggers.insert(reminder.id, next);
-                }
+        // Load enabled reminders
+        let reminders = match get_enabled_reminders() {
+            Ok(r) => r,
+            Err(e) => {
+                eprintln!("Failed to load reminders: {}", e);
+                sleep(Duration::from_secs(5)).await;
+                continue;
             }
-            
-            // Remove triggers for deleted/disabled reminders
-            let active_ids: Vec<i64> = reminders.iter().map(|r| r.id).collect();
-            next_triggers.retain(|id, _| active_ids.contains(id));
-            
-            // Find the next reminder to trigger
-            let now = Utc::now();
-            let mut soonest: Option<(i64, DateTime<Utc>, &Reminder)> = None;
-            
-            for reminder in &reminders {
-                if let Some(next_time) = next_triggers.get(&reminder.id) {
-                    if is_within_active_window(reminder) {
-                        if soonest.is_none() || next_time < &soonest.as_ref().unwrap().1 {
-                            soonest = Some((reminder.id, *next_time, reminder));
-                        }
-                    }
-                }
+        };
+        
+        if let Some(jump) = planner.check_clock(&clock) {
+            // Escalation goes by the time the machine was awake
+            for entry in outstanding.values_mut() {
+                entry.triggered_at += jump;
             }
-            
-            // Calculate sleep duration
-            let sleep_duration = if let Some((id, next_time, reminder)) = soonest {
-                let duration = next_time.signed_duration_since(now);
-                
-                if duration.num_milliseconds() <= 0 {
-                    // Trigger now!
-                    trigger_reminder(&handle, reminder).await;
-                    
-                    // Update last triggered
-                    if let Err(e) = update_last_triggered(id) {
-                        eprintln!("Failed to update last_triggered: {}", e);
-                    }
-                    
-                    // Calculate next trigger time
-                    let next = calculate_next_trigger(reminder);
-                    next_triggers.insert(id, next);
-                    
-                    Duration::from_millis(100) // Small delay before next check
-                } else {
-                    // Sleep until next trigger (max 1 minute to allow for refresh)
-                    Duration::from_millis(duration.num_milliseconds().min(60000) as u64)
-                }
-            } else {
-                // No reminders, sleep for a bit
-                Duration::from_secs(10)
-            };
-            
-            // Sleep with ability to be interrupted by commands
+        }
+        
+        let now = clock.now();
+        
+        // Lift a timed pause once it has run out
+        if pause.paused && pause_until(&pause).is_some_and(|until| until <= now) {
+            pause = resume(&handle, &reminders, &mut planner, &clock);
+        }
+        
+        // Forget reminders that were answered, disabled or deleted since they fired
+        outstanding.retain(|id, _| reminders.iter().any(|r| r.id == *id && r.pending_since.is_some()));
+        let pending = reminders.iter().filter(|r| r.pending_since.is_some()).count();
+        if pending != published_pending {
+            published_pending = pending;
+            if let Err(e) = handle.emit("pending-changed", pending) {
+                eprintln!("Failed to emit pending event: {}", e);
+            }
+        }
+        if !pause.paused {
+            escalate(&handle, &mut outstanding, now);
+        }
+        
+        let mut sink = AppSink {
+            app_handle: &handle,
+            outstanding: &mut outstanding,
+        };
+        let sleep_duration = planner.tick(&reminders, &pause, &clock, &mut sink);
+        
+        // Also wake up for the next escalation step
+        let next_escalation = outstanding.values().filter_map(Outstanding::next_step_at).min();
+        let sleep_duration = match next_escalation {
+            Some(at) if !pause.paused => sleep_duration.min(Duration::from_millis(
+                at.signed_duration_since(now).num_milliseconds().max(0) as u64,
+            )),
+            _ => sleep_duration,
+        };
+        
+        publish_status(&reminders, &planner.next_triggers, &pause);
+        
         // Sleep with ability to be interrupted by commands
         tokio::select! {
             _ = sleep(sleep_duration) => {}
             cmd = rx.recv() => {
                 match cmd {
                     Some(SchedulerCommand::Refresh) => {
-                        // Clear next triggers to recalculate
-                        next_triggers.clear();
+                        planner.refresh();
+                        planner.default_timezone = load_default_timezone();
+                    }
+                    Some(SchedulerCommand::Pause { until }) => {
+                        pause = PauseState {
+                            paused: true,
+                            until: until.map(|u| u.to_rfc3339()),
+                        };
+                        if let Err(e) = set_pause_state(&pause) {
+                            eprintln!("Failed to save pause state: {}", e);
+                        }
+                        emit_pause_changed(&handle, &pause);
+                    }
+                    Some(SchedulerCommand::Resume) => {
+                        if pause.paused {
+                            pause = resume(&handle, &reminders, &mut planner, &clock);
+                        }
+                    }
+                    Some(SchedulerCommand::TriggerNow { id }) => {
+                        match get_reminder_by_id(id) {
+                            Ok(Some(reminder)) => {
+                                let metadata = serde_json::json!({
+                                    "scheduled_for": clock.now().to_rfc3339(),
+                                    "snoozed": false,
+                                    "manual": true,
+                                });
+                                fire(&handle, &reminder, metadata, &mut outstanding);
+                                // Interval reminders count from this trigger
+                                planner.next_triggers.remove(&id);
+                            }
+                            Ok(None) => eprintln!("Reminder {} to trigger no longer exists", id),
+                            Err(e) => eprintln!("Failed to load reminder {}: {}", id, e),
+                        }
+                    }
+                    Some(SchedulerCommand::Woke) => {
+                        planner.woke = true;
                     }
                     Some(SchedulerCommand::Stop) | None => {
                         break;
@@ -108,63 +426,487 @@ pub async fn start_scheduler(app_handle: AppHandle) {
             }
         }
     }
+    
+    *SCHEDULER_TX.lock() = None;
+    STATUS.lock().running = false;
 }
 
-fn calculate_next_trigger(reminder: &Reminder) -> DateTime<Utc> {
-    let now = Utc::now();
+/// Shows the reminder and records that it fired
+fn fire(
+    app_handle: &AppHandle,
+    reminder: &Reminder,
+    metadata: serde_json::Value,
+    outstanding: &mut HashMap<i64, Outstanding>,
+) {
+    // The previous occurrence was never answered
+    if let Some(pending_since) = &reminder.pending_since {
+        let metadata = serde_json::json!({ "triggered_at": pending_since });
+        if let Err(e) = record_event(reminder.id, EventKind::Missed, Some(metadata)) {
+            eprintln!("Failed to record missed reminder: {}", e);
+        }
+    }
     
-    if let Some(last_triggered) = &reminder.last_triggered {
-        if let Ok(last) = DateTime::parse_from_rfc3339(last_triggered) {
-            let last_utc = last.with_timezone(&Utc);
-            let next = last_utc + chrono::Duration::minutes(reminder.interval_minutes as i64);
-            
-            if next > now {
-                return next;
+    trigger_reminder(app_handle, reminder);
+    // Nobody listening is fine
+    let _ = TRIGGERED.send(reminder.clone());
+    
+    // Silent reminders have nothing to answer
+    let silent = reminder.delivery_style == DeliveryStyle::Silent;
+    if let Err(e) = update_last_triggered(reminder.id, !silent) {
+        eprintln!("Failed to update last_triggered: {}", e);
+    }
+    if reminder.escalation.is_empty() || silent {
+        outstanding.remove(&reminder.id);
+    } else {
+        outstanding.insert(
+            reminder.id,
+            Outstanding {
+                reminder: reminder.clone(),
+                triggered_at: Utc::now(),
+                next_step: 0,
+                sound: reminder.sound.clone(),
+            },
+        );
+    }
+    if let Err(e) = record_event(reminder.id, EventKind::Triggered, Some(metadata)) {
+        eprintln!("Failed to record trigger: {}", e);
+    }
+    
+    if reminder.command.as_ref().is_some_and(|c| c.enabled) {
+        // Runs on its own so a slow command doesn't hold up other reminders
+        let reminder = reminder.clone();
+        tokio::spawn(async move { run_reminder_command(&reminder).await });
+    }
+}
+
+/// Applies every escalation step that has come due
+fn escalate(app_handle: &AppHandle, outstanding: &mut HashMap<i64, Outstanding>, now: DateTime<Utc>) {
+    for entry in outstanding.values_mut() {
+        while entry.next_step_at().is_some_and(|at| at <= now) {
+            let step = entry.reminder.escalation[entry.next_step].clone();
+            entry.next_step += 1;
+            apply_escalation(app_handle, entry, &step);
+        }
+    }
+    outstanding.retain(|_, o| o.next_step_at().is_some());
+}
+
+fn apply_escalation(app_handle: &AppHandle, entry: &mut Outstanding, step: &EscalationStep) {
+    let id = entry.reminder.id;
+    
+    match &step.action {
+        EscalationAction::Webhook => {
+            let metadata = serde_json::json!({
+                "triggered_at": entry.triggered_at.to_rfc3339(),
+                "after_minutes": step.after_minutes,
+            });
+            if let Err(e) = record_event(id, EventKind::Escalated, Some(metadata)) {
+                eprintln!("Failed to record escalation: {}", e);
             }
+            return;
         }
+        EscalationAction::ChangeSound { sound } => entry.sound = sound.clone(),
+        EscalationAction::ReplaySound | EscalationAction::Refocus => {}
     }
     
-    // If no last trigger or it's in the past, trigger after interval from now
-    now + chrono::Duration::minutes(reminder.interval_minutes as i64)
+    let Some(window) = app_handle.get_webview_window(&popup_label(id)) else {
+        // A notification or a closed popup escalates to a new popup, which
+        // plays the sound itself
+        let mut reminder = entry.reminder.clone();
+        reminder.sound = entry.sound.clone();
+        return show_popup(app_handle, &reminder);
+    };
+    
+    if step.action == EscalationAction::Refocus {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.center();
+        let _ = window.set_always_on_top(true);
+        let _ = window.set_focus();
+    } else {
+        let payload = serde_json::json!({ "sound": entry.sound });
+        if let Err(e) = app_handle.emit_to(popup_label(id).as_str(), "reminder-escalated", payload) {
+            eprintln!("Failed to emit escalation event: {}", e);
+        }
+    }
 }
 
-fn is_within_active_window(reminder: &Reminder) -> bool {
-    let local = Local::now();
+fn publish_status(reminders: &[Reminder], next_triggers: &HashMap<i64, DateTime<Utc>>, pause: &PauseState) {
+    let mut planned: Vec<(DateTime<Utc>, &Reminder)> = reminders
+        .iter()
+        .filter_map(|r| next_triggers.get(&r.id).map(|at| (*at, r)))
+        .collect();
+    planned.sort_by_key(|(at, _)| *at);
     
-    // Check day of week
-    if let Some(days) = &reminder.active_days {
-        let current_day = local.weekday().num_days_from_monday() as u8;
-        if !days.contains(&current_day) {
-            return false;
+    *STATUS.lock() = SchedulerStatus {
+        running: true,
+        pause: pause.clone(),
+        enabled_reminders: reminders.len(),
+        pending_reminders: reminders.iter().filter(|r| r.pending_since.is_some()).count(),
+        next_triggers: planned
+            .into_iter()
+            .map(|(at, r)| PlannedTrigger {
+                reminder_id: r.id,
+                name: r.name.clone(),
+                at: at.to_rfc3339(),
+            })
+            .collect(),
+    };
+}
+
+/// The default zone from the settings, `None` to follow the system
+pub fn default_timezone(settings: &Settings) -> Option<Tz> {
+    settings.default_timezone.as_deref().and_then(|name| name.parse().ok())
+}
+
+fn load_default_timezone() -> Option<Tz> {
+    match get_settings() {
+        Ok(settings) => default_timezone(&settings),
+        Err(e) => {
+            eprintln!("Failed to load settings: {}", e);
+            None
         }
     }
+}
+
+fn pause_until(pause: &PauseState) -> Option<DateTime<Utc>> {
+    pause
+        .until
+        .as_ref()
+        .and_then(|u| DateTime::parse_from_rfc3339(u).ok())
+        .map(|u| u.with_timezone(&Utc))
+}
+
+/// Ends the current pause and applies the pause policy to everything that
+/// came due in the meantime
+fn resume(app_handle: &AppHandle, reminders: &[Reminder], planner: &mut Planner, clock: &dyn Clock) -> PauseState {
+    let state = PauseState::default();
+    if let Err(e) = set_pause_state(&state) {
+        eprintln!("Failed to save pause state: {}", e);
+    }
     
-    // Check time window
-    if let (Some(start_str), Some(end_str)) = (&reminder.active_start_time, &reminder.active_end_time) {
-        if let (Ok(start), Ok(end)) = (
-            NaiveTime::parse_from_str(start_str, "%H:%M"),
-            NaiveTime::parse_from_str(end_str, "%H:%M"),
-        ) {
-            let current_time = local.time();
-            
-            if start <= end {
-                // Normal window (e.g., 09:00 to 18:00)
-                if current_time < start || current_time > end {
-                    return false;
-                }
+    let policy = get_settings().map(|s| s.pause_policy).unwrap_or_default();
+    let (now, tz) = (clock.now(), planner.timezone(clock));
+    // The pause policy covers anything missed meanwhile
+    planner.catch_up_due = false;
+    
+    for reminder in reminders {
+        let next = planner
+            .next_triggers
+            .get(&reminder.id)
+            .copied()
+            .or_else(|| calculate_next_trigger(reminder, now, tz));
+        
+        match after_pause(reminder, next, policy, now, tz) {
+            Some(next) => planner.next_triggers.insert(reminder.id, next),
+            None => planner.next_triggers.remove(&reminder.id),
+        };
+    }
+    
+    emit_pause_changed(app_handle, &state);
+    state
+}
+
+/// When a reminder planned for `next` fires after a pause that ended at
+/// `resumed_at`
+fn after_pause(
+    reminder: &Reminder,
+    next: Option<DateTime<Utc>>,
+    policy: PausePolicy,
+    resumed_at: DateTime<Utc>,
+    tz: Tz,
+) -> Option<DateTime<Utc>> {
+    match policy {
+        PausePolicy::Drop => match next {
+            Some(next) if next <= resumed_at => next_trigger_after(reminder, resumed_at, tz),
+            next => next,
+        },
+        PausePolicy::FireOnResume => {
+            // A refresh during the pause moves overdue reminders past its end,
+            // so also look at the stored due time
+            if next.is_some_and(|next| next <= resumed_at) || due_time(reminder, tz).is_some_and(|due| due <= resumed_at) {
+                Some(resumed_at)
             } else {
-                // Overnight window (e.g., 22:00 to 06:00)
-                if current_time < start && current_time > end {
-                    return false;
-                }
+                next
             }
         }
     }
+}
+
+fn emit_pause_changed(app_handle: &AppHandle, state: &PauseState) {
+    if let Err(e) = app_handle.emit("pause-changed", state.clone()) {
+        eprintln!("Failed to emit pause event: {}", e);
+    }
+}
+
+/// When the reminder is due according to its stored state, which may be in the past
+fn due_time(reminder: &Reminder, tz: Tz) -> Option<DateTime<Utc>> {
+    // A pending snooze takes precedence over the regular interval
+    if let Some(snoozed_until) = &reminder.snoozed_until {
+        if let Ok(until) = DateTime::parse_from_rfc3339(snoozed_until) {
+            return Some(until.with_timezone(&Utc));
+        }
+    }
+    
+    // One-shot reminders are due until they have fired
+    if let Some(due_at) = &reminder.due_at {
+        if reminder.last_triggered.is_some() {
+            return None;
+        }
+        return DateTime::parse_from_rfc3339(due_at)
+            .ok()
+            .map(|due| due.with_timezone(&Utc));
+    }
+    
+    let last_triggered = reminder.last_triggered.as_ref()?;
+    let last = DateTime::parse_from_rfc3339(last_triggered).ok()?;
+    next_trigger_after(reminder, last.with_timezone(&Utc), tz)
+}
+
+/// The regular trigger following one at `after`, ignoring snoozes
+fn next_trigger_after(reminder: &Reminder, after: DateTime<Utc>, tz: Tz) -> Option<DateTime<Utc>> {
+    if reminder.due_at.is_some() {
+        // One-shot reminders never repeat
+        return None;
+    }
     
-    true
+    match reminder.schedule {
+        Schedule::Interval => {
+            Some(after + chrono::Duration::minutes(reminder.interval_minutes as i64))
+        }
+        _ => next_occurrence(reminder, after, tz),
+    }
 }
 
-async fn trigger_reminder(app_handle: &AppHandle, reminder: &Reminder) {
+/// Occurrences a reminder missed, of which only the latest few are kept
+#[derive(Debug, Default)]
+struct Missed {
+    count: usize,
+    first: Option<DateTime<Utc>>,
+    /// The latest occurrences, oldest first
+    latest: VecDeque<DateTime<Utc>>,
+}
+
+impl Missed {
+    fn push(&mut self, at: DateTime<Utc>, keep: usize) {
+        self.count += 1;
+        self.first.get_or_insert(at);
+        self.latest.push_back(at);
+        if self.latest.len() > keep {
+            self.latest.pop_front();
+        }
+    }
+}
+
+/// Regular occurrences from the stored due time up to `now` that never fired,
+/// keeping the latest `keep` of them. Snoozes and one-shot reminders fire late
+/// instead.
+fn missed_occurrences(reminder: &Reminder, now: DateTime<Utc>, tz: Tz, keep: usize) -> Missed {
+    let mut missed = Missed::default();
+    if reminder.snoozed_until.is_some() || reminder.due_at.is_some() {
+        return missed;
+    }
+    let Some(due) = due_time(reminder, tz).filter(|due| *due <= now) else {
+        return missed;
+    };
+    
+    if reminder.schedule != Schedule::Interval {
+        missed.push(due, keep);
+        each_occurrence_between(reminder, due, now, tz, &mut |at| missed.push(at, keep));
+        return missed;
+    }
+    
+    let step = reminder.interval_minutes as i64 * 60;
+    if step <= 0 {
+        return missed;
+    }
+    let steps = (now - due).num_seconds() / step + 1;
+    // Without active days or a window every step counts, so only the latest
+    // ones need to be looked at
+    let unrestricted = reminder.active_days.is_none()
+        && (reminder.active_start_time.is_none() || reminder.active_end_time.is_none());
+    let skipped = if unrestricted { (steps - keep as i64).max(0) } else { 0 };
+    if skipped > 0 {
+        missed.count = skipped as usize;
+        missed.first = Some(due);
+    }
+    
+    for index in skipped..steps {
+        let at = due + chrono::Duration::seconds(step * index);
+        // Interval reminders don't fire outside their active window
+        if is_within_active_window(reminder, at, tz) {
+            missed.push(at, keep);
+        }
+    }
+    missed
+}
+
+/// Applies each reminder's catch-up policy to the occurrences it missed,
+/// queueing the ones to fire and recording the rest as missed
+fn catch_up(
+    reminders: &[Reminder],
+    now: DateTime<Utc>,
+    tz: Tz,
+    queue: &mut HashMap<i64, VecDeque<DateTime<Utc>>>,
+    sink: &mut dyn TriggerSink,
+) {
+    for reminder in reminders {
+        let to_fire = match reminder.catch_up {
+            CatchUpPolicy::Skip => 0,
+            CatchUpPolicy::FireOnce => 1,
+            CatchUpPolicy::FireEach { max } => max as usize,
+        };
+        // One more than fires, to tell where the skipped ones end
+        let mut missed = missed_occurrences(reminder, now, tz, to_fire + 1);
+        if missed.count == 0 {
+            continue;
+        }
+        
+        // The latest ones are the ones that fire
+        let to_fire = to_fire.min(missed.count);
+        let latest = missed.latest.make_contiguous();
+        let (skipped, firing) = latest.split_at(latest.len() - to_fire);
+        
+        if let (Some(first), Some(last)) = (missed.first, skipped.last()) {
+            let metadata = serde_json::json!({
+                "occurrences": missed.count - to_fire,
+                "first": first.to_rfc3339(),
+                "last": last.to_rfc3339(),
+                "catch_up": true,
+            });
+            sink.missed(reminder, metadata);
+        }
+        if !firing.is_empty() {
+            queue.insert(reminder.id, firing.iter().copied().collect());
+        }
+    }
+}
+
+fn calculate_next_trigger(reminder: &Reminder, now: DateTime<Utc>, tz: Tz) -> Option<DateTime<Utc>> {
+    // A last trigger in the future means the clock was set back since, which
+    // would hold the reminder back by as much
+    let clock_set_back = reminder
+        .last_triggered
+        .as_ref()
+        .and_then(|last| DateTime::parse_from_rfc3339(last).ok())
+        .is_some_and(|last| last > now);
+    
+    if let Some(due) = due_time(reminder, tz) {
+        // A snooze or one-shot reminder that has already passed (e.g. the app
+        // was closed) fires right away
+        if (due > now && !clock_set_back) || reminder.snoozed_until.is_some() || reminder.due_at.is_some() {
+            return Some(due);
+        }
+    }
+    
+    // If no last trigger or it's in the past, continue from now
+    next_trigger_after(reminder, now, tz)
+}
+
+/// The next `count` times the reminders fire from `now` on, soonest first
+/// and no later than `until`. Follows the scheduler: snoozes fire first,
+/// interval reminders count from each trigger and wait for their active
+/// window, and nothing fires while paused.
+pub fn upcoming_triggers(
+    reminders: &[Reminder],
+    pause: &PauseState,
+    policy: PausePolicy,
+    now: DateTime<Utc>,
+    tz: Tz,
+    count: usize,
+    until: Option<DateTime<Utc>>,
+) -> Vec<PlannedTrigger> {
+    // An open-ended pause holds everything back
+    let resumed_at = match pause_until(pause) {
+        _ if !pause.paused => None,
+        Some(until) => Some(until.max(now)),
+        None => return Vec::new(),
+    };
+    
+    let mut upcoming: Vec<(DateTime<Utc>, &Reminder)> = Vec::new();
+    for reminder in reminders {
+        let mut next = calculate_next_trigger(reminder, now, tz);
+        let mut earliest = now;
+        if let Some(resumed_at) = resumed_at {
+            next = after_pause(reminder, next, policy, resumed_at, tz);
+            earliest = resumed_at;
+        }
+        
+        // Fire it over and over, storing each trigger like the scheduler does
+        let mut fired = reminder.clone();
+        let mut found = 0;
+        while found < count {
+            let Some(mut at) = next.map(|next| next.max(earliest)) else {
+                break;
+            };
+            if reminder.schedule == Schedule::Interval {
+                let Some(active) = next_active_time(reminder, at, tz) else {
+                    break;
+                };
+                at = active;
+            }
+            if until.is_some_and(|until| at > until) {
+                break;
+            }
+            
+            upcoming.push((at, reminder));
+            found += 1;
+            fired.last_triggered = Some(at.to_rfc3339());
+            fired.snoozed_until = None;
+            next = calculate_next_trigger(&fired, at, tz);
+            earliest = at;
+        }
+    }
+    
+    upcoming.sort_by_key(|(at, _)| *at);
+    upcoming
+        .into_iter()
+        .take(count)
+        .map(|(at, r)| PlannedTrigger {
+            reminder_id: r.id,
+            name: r.name.clone(),
+            at: at.to_rfc3339(),
+        })
+        .collect()
+}
+
+/// Previews the next `count` occurrences of one reminder, or of all enabled
+/// ones, from the stored reminders, pause and settings
+pub fn get_upcoming_triggers(
+    id: Option<i64>,
+    count: usize,
+    until: Option<DateTime<Utc>>,
+) -> AppResult<Vec<PlannedTrigger>> {
+    if count == 0 || count > MAX_UPCOMING_TRIGGERS {
+        return Err(AppError::validation(
+            "count",
+            format!("Count must be between 1 and {}", MAX_UPCOMING_TRIGGERS),
+        ));
+    }
+    
+    let reminders = match id {
+        Some(id) => {
+            let reminder = get_reminder_by_id(id)?.ok_or_else(|| AppError::NotFound(format!("Reminder {}", id)))?;
+            // Disabled reminders don't fire
+            if reminder.enabled { vec![reminder] } else { Vec::new() }
+        }
+        None => get_enabled_reminders()?,
+    };
+    let settings = get_settings()?;
+    let tz = default_timezone(&settings).unwrap_or_else(system_timezone);
+    
+    Ok(upcoming_triggers(
+        &reminders,
+        &get_pause_state()?,
+        settings.pause_policy,
+        Utc::now(),
+        tz,
+        count,
+        until,
+    ))
+}
+
+fn trigger_reminder(app_handle: &AppHandle, reminder: &Reminder) {
     println!("Triggering reminder: {} (ID: {})", reminder.name, reminder.id);
     
     // Emit event to frontend
@@ -172,11 +914,93 @@ async fn trigger_reminder(app_handle: &AppHandle, reminder: &Reminder) {
         eprintln!("Failed to emit reminder event: {}", e);
     }
     
+    let escalate_after = match reminder.delivery_style {
+        DeliveryStyle::Popup => return show_popup(app_handle, reminder),
+        DeliveryStyle::Silent => return,
+        DeliveryStyle::Notification => None,
+        DeliveryStyle::NotificationThenPopup { minutes } => Some(Duration::from_secs(minutes as u64 * 60)),
+    };
+    // Waiting for an answer must not hold up the scheduler
+    tokio::spawn(notify(app_handle.clone(), reminder.clone(), escalate_after));
+}
+
+/// Shows the notification and handles its buttons. With `escalate_after`, the
+/// popup follows unless the notification was answered by then; the
+/// notification keeps working afterwards.
+async fn notify(app_handle: AppHandle, reminder: Reminder, escalate_after: Option<Duration>) {
+    let mut action = match show_notification(&reminder) {
+        Ok(action) => action,
+        Err(e) => {
+            eprintln!("{}, showing the popup instead", e);
+            return show_popup(&app_handle, &reminder);
+        }
+    };
+    
+    let escalation = async {
+        match escalate_after {
+            Some(after) => sleep(after).await,
+            None => std::future::pending().await,
+        }
+    };
+    tokio::pin!(escalation);
+    
+    let mut waiting_for_action = true;
+    let mut escalation_pending = escalate_after.is_some();
+    while waiting_for_action || escalation_pending {
+        tokio::select! {
+            result = &mut action, if waiting_for_action => {
+                waiting_for_action = false;
+                // Closing the notification doesn't answer it
+                if result.is_ok_and(|chosen| apply_notification_action(&reminder, chosen)) {
+                    close_popup(&app_handle, reminder.id);
+                    return;
+                }
+            }
+            _ = &mut escalation, if escalation_pending => {
+                escalation_pending = false;
+                show_popup(&app_handle, &reminder);
+            }
+        }
+    }
+}
+
+/// Dismisses or snoozes the reminder like the popup would, returning whether
+/// that worked
+fn apply_notification_action(reminder: &Reminder, action: NotificationAction) -> bool {
+    let result = match action {
+        NotificationAction::Done => dismiss_reminder(reminder.id),
+        NotificationAction::Snooze => snooze_reminder(reminder.id, NOTIFICATION_SNOOZE_MINUTES).map(|_| ()),
+        NotificationAction::Closed => return false,
+    };
+    match result {
+        Ok(()) => {
+            refresh_scheduler();
+            true
+        }
+        Err(e) => {
+            // e.g. the snooze limit was reached
+            eprintln!("Failed to apply notification action for reminder {}: {}", reminder.id, e);
+            false
+        }
+    }
+}
+
+fn popup_label(id: i64) -> String {
+    format!("popup-{}", id)
+}
+
+fn close_popup(app_handle: &AppHandle, id: i64) {
+    if let Some(window) = app_handle.get_webview_window(&popup_label(id)) {
+        let _ = window.close();
+    }
+}
+
+fn show_popup(app_handle: &AppHandle, reminder: &Reminder) {
     // Create popup window
-    let popup_label = format!("popup-{}", reminder.id);
+    let label = popup_label(reminder.id);
     
     // Close existing popup if any
-    if let Some(window) = app_handle.get_webview_window(&popup_label) {
+    if let Some(window) = app_handle.get_webview_window(&label) {
         let _ = window.close();
     }
     
@@ -190,10 +1014,10 @@ async fn trigger_reminder(app_handle: &AppHandle, reminder: &Reminder) {
     
     match WebviewWindowBuilder::new(
         app_handle,
-        &popup_label,
+        &label,
         WebviewUrl::App(url.into()),
     )
-    .title(&format!("Reminder: {}", reminder.name))
+    .title(format!("Reminder: {}", reminder.name))
     .inner_size(450.0, 500.0)
     .resizable(false)
     .center()
@@ -219,8 +1043,491 @@ pub fn refresh_scheduler() {
     }
 }
 
+fn wake_scheduler() {
+    if let Some(tx) = SCHEDULER_TX.lock().as_ref() {
+        let _ = tx.try_send(SchedulerCommand::Woke);
+    }
+}
+
+/// Sends a command the caller needs to know was delivered
+fn send_command(command: SchedulerCommand) -> AppResult<()> {
+    let guard = SCHEDULER_TX.lock();
+    let tx = guard
+        .as_ref()
+        .ok_or_else(|| AppError::Scheduler("The scheduler is not running".to_string()))?;
+    tx.try_send(command)
+        .map_err(|e| AppError::Scheduler(e.to_string()))
+}
+
+/// When a pause of `minutes` from `now` ends, `None` pausing indefinitely.
+/// Rejects durations below a minute or above `MAX_PAUSE_MINUTES`.
+pub fn pause_end(minutes: Option<i64>, now: DateTime<Utc>) -> AppResult<Option<DateTime<Utc>>> {
+    let Some(minutes) = minutes else {
+        return Ok(None);
+    };
+    if !(1..=MAX_PAUSE_MINUTES).contains(&minutes) {
+        return Err(AppError::validation(
+            "minutes",
+            format!("Pause duration must be between 1 and {} minutes", MAX_PAUSE_MINUTES),
+        ));
+    }
+    Ok(Some(now + chrono::Duration::minutes(minutes)))
+}
+
+pub fn pause_scheduler(until: Option<DateTime<Utc>>) -> AppResult<()> {
+    send_command(SchedulerCommand::Pause { until })
+}
+
+pub fn resume_scheduler() -> AppResult<()> {
+    send_command(SchedulerCommand::Resume)
+}
+
+/// Fires a reminder right away. Its regular schedule continues afterwards.
+pub fn trigger_now(id: i64) -> AppResult<()> {
+    if get_reminder_by_id(id)?.is_none() {
+        return Err(AppError::NotFound(format!("Reminder {}", id)));
+    }
+    send_command(SchedulerCommand::TriggerNow { id })
+}
+
+/// Every reminder the scheduler fires from now on, for listeners outside the
+/// app window. A receiver that falls behind skips the oldest ones.
+pub fn subscribe_triggers() -> broadcast::Receiver<Reminder> {
+    TRIGGERED.subscribe()
+}
+
+/// What the scheduler is doing, as of the last time it woke up
+pub fn scheduler_status() -> SchedulerStatus {
+    STATUS.lock().clone()
+}
+
 pub fn stop_scheduler() {
     if let Some(tx) = SCHEDULER_TX.lock().as_ref() {
         let _ = tx.try_send(SchedulerCommand::Stop);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reminder::FakeClock;
+    use crate::storage::CatchUpPolicy;
+    use chrono::TimeZone;
+    use chrono_tz::Europe::Berlin;
+    
+    fn reminder(id: i64, interval_minutes: i32) -> Reminder {
+        Reminder {
+            id,
+            name: format!("Reminder {}", id),
+            message: None,
+            interval_minutes,
+            schedule: Schedule::Interval,
+            due_at: None,
+            enabled: true,
+            active_start_time: None,
+            active_end_time: None,
+            active_days: None,
+            sound: "chime".to_string(),
+            command: None,
+            delivery_style: Default::default(),
+            escalation: Vec::new(),
+            catch_up: Default::default(),
+            timezone: None,
+            last_triggered: None,
+            snoozed_until: None,
+            snooze_count: 0,
+            pending_since: None,
+            created_at: "2026-03-02T07:12:00+00:00".to_string(),
+        }
+    }
+    
+    fn berlin(month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        Berlin
+            .with_ymd_and_hms(2026, month, day, hour, minute, 0)
+            .earliest()
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+    
+    /// Stands in for the app, remembering what fired when
+    #[derive(Default)]
+    struct Recorder {
+        now: DateTime<Utc>,
+        fired: Vec<(i64, DateTime<Utc>)>,
+        missed: Vec<(i64, serde_json::Value)>,
+    }
+    
+    impl TriggerSink for Recorder {
+        fn fire(&mut self, reminder: &Reminder, _metadata: serde_json::Value) {
+            self.fired.push((reminder.id, self.now));
+        }
+        
+        fn missed(&mut self, reminder: &Reminder, metadata: serde_json::Value) {
+            self.missed.push((reminder.id, metadata));
+        }
+    }
+    
+    /// Runs the scheduler loop against a fake clock in Berlin
+    struct Harness {
+        clock: FakeClock,
+        planner: Planner,
+        reminders: Vec<Reminder>,
+        pause: PauseState,
+        sink: Recorder,
+    }
+    
+    impl Harness {
+        fn new(start: DateTime<Utc>, reminders: Vec<Reminder>) -> Self {
+            let clock = FakeClock::new(start, Berlin);
+            let planner = Planner::new(&clock);
+            Self {
+                clock,
+                planner,
+                reminders,
+                pause: PauseState::default(),
+                sink: Recorder::default(),
+            }
+        }
+        
+        /// Sleeps as long as the scheduler asks to until the wall clock shows
+        /// `until`, storing each trigger like `update_last_triggered` does
+        fn run_until(&mut self, until: DateTime<Utc>) {
+            while self.clock.now() < until {
+                self.planner.check_clock(&self.clock);
+                self.sink.now = self.clock.now();
+                let fired = self.sink.fired.len();
+                let wait = self.planner.tick(&self.reminders, &self.pause, &self.clock, &mut self.sink);
+                
+                for (id, at) in &self.sink.fired[fired..] {
+                    let reminder = self.reminders.iter_mut().find(|r| r.id == *id).unwrap();
+                    reminder.last_triggered = Some(at.to_rfc3339());
+                    reminder.snoozed_until = None;
+                }
+                let left = (until - self.clock.now()).to_std().unwrap_or_default();
+                self.clock.advance(wait.min(left));
+            }
+        }
+        
+        /// When the reminder fired, on the Berlin wall clock
+        fn fired(&self, id: i64) -> Vec<String> {
+            self.sink
+                .fired
+                .iter()
+                .filter(|(fired_id, _)| *fired_id == id)
+                .map(|(_, at)| at.with_timezone(&Berlin).format("%a %H:%M").to_string())
+                .collect()
+        }
+    }
+    
+    #[test]
+    fn intervals_count_from_the_last_trigger() {
+        let mut r = reminder(1, 30);
+        r.last_triggered = Some(berlin(3, 4, 9, 50).to_rfc3339());
+        let mut harness = Harness::new(berlin(3, 4, 10, 0), vec![r]);
+        
+        harness.run_until(berlin(3, 4, 12, 0));
+        assert_eq!(harness.fired(1), ["Wed 10:20", "Wed 10:50", "Wed 11:20", "Wed 11:50"]);
+    }
+    
+    #[test]
+    fn intervals_wait_for_an_overnight_window_to_open() {
+        let mut r = reminder(1, 60);
+        r.active_start_time = Some("22:00".to_string());
+        r.active_end_time = Some("06:00".to_string());
+        r.last_triggered = Some(berlin(3, 5, 4, 40).to_rfc3339());
+        let mut harness = Harness::new(berlin(3, 5, 5, 30), vec![r]);
+        
+        harness.run_until(berlin(3, 5, 23, 30));
+        assert_eq!(harness.fired(1), ["Thu 05:40", "Thu 22:00", "Thu 23:00"]);
+    }
+    
+    #[test]
+    fn fixed_times_respect_day_filters() {
+        let mut r = reminder(1, 60);
+        r.schedule = Schedule::DailyTimes {
+            times: vec!["09:00".to_string(), "17:30".to_string()],
+        };
+        r.active_days = Some(vec![0, 1, 2, 3, 4]);
+        let mut harness = Harness::new(berlin(3, 6, 12, 0), vec![r]);
+        
+        harness.run_until(berlin(3, 10, 10, 0));
+        assert_eq!(harness.fired(1), ["Fri 17:30", "Mon 09:00", "Mon 17:30", "Tue 09:00"]);
+    }
+    
+    #[test]
+    fn fixed_times_move_past_the_spring_gap_and_fire_once_in_the_autumn_overlap() {
+        let mut r = reminder(1, 60);
+        r.schedule = Schedule::DailyTimes {
+            times: vec!["02:30".to_string()],
+        };
+        
+        let mut harness = Harness::new(berlin(3, 28, 0, 0), vec![r.clone()]);
+        harness.run_until(berlin(3, 31, 0, 0));
+        // 02:30 doesn't exist on Sunday, it fires an hour later instead
+        assert_eq!(harness.fired(1), ["Sat 02:30", "Sun 03:30", "Mon 02:30"]);
+        
+        let mut harness = Harness::new(berlin(10, 24, 0, 0), vec![r]);
+        harness.run_until(berlin(10, 26, 0, 0));
+        assert_eq!(harness.fired(1), ["Sat 02:30", "Sun 02:30"]);
+        // The first of the two, still in summer time
+        assert_eq!(harness.sink.fired[1].1.to_rfc3339(), "2026-10-25T00:30:00+00:00");
+    }
+    
+    #[test]
+    fn fixed_times_follow_the_reminders_own_zone() {
+        let mut r = reminder(1, 60);
+        r.schedule = Schedule::DailyTimes {
+            times: vec!["09:00".to_string()],
+        };
+        r.active_days = Some(vec![0, 1, 2, 3, 4]);
+        r.timezone = Some("America/New_York".to_string());
+        let mut harness = Harness::new(berlin(3, 6, 12, 0), vec![r]);
+        
+        // New York moves its clocks three weeks before Berlin does
+        harness.run_until(berlin(3, 11, 0, 0));
+        assert_eq!(harness.fired(1), ["Fri 15:00", "Mon 14:00", "Tue 14:00"]);
+    }
+    
+    #[test]
+    fn windows_without_a_zone_use_the_default_one() {
+        let mut r = reminder(1, 60);
+        r.active_start_time = Some("09:00".to_string());
+        r.active_end_time = Some("11:00".to_string());
+        r.last_triggered = Some(berlin(3, 4, 0, 0).to_rfc3339());
+        let mut harness = Harness::new(berlin(3, 4, 6, 0), vec![r]);
+        harness.planner.default_timezone = Some(chrono_tz::Europe::London);
+        
+        harness.run_until(berlin(3, 4, 13, 0));
+        assert_eq!(harness.fired(1), ["Wed 10:00", "Wed 11:00", "Wed 12:00"]);
+    }
+    
+    #[test]
+    fn intervals_keep_their_length_across_dst_changes() {
+        let mut r = reminder(1, 60);
+        r.last_triggered = Some(berlin(3, 29, 0, 30).to_rfc3339());
+        let mut harness = Harness::new(berlin(3, 29, 0, 45), vec![r]);
+        
+        harness.run_until(berlin(3, 29, 5, 0));
+        assert_eq!(harness.fired(1), ["Sun 01:30", "Sun 03:30", "Sun 04:30"]);
+        for pair in harness.sink.fired.windows(2) {
+            assert_eq!((pair[1].1 - pair[0].1).num_minutes(), 60);
+        }
+    }
+    
+    #[test]
+    fn refresh_keeps_the_interval_and_picks_up_snoozes() {
+        let mut r = reminder(1, 60);
+        r.last_triggered = Some(berlin(3, 4, 10, 0).to_rfc3339());
+        let mut harness = Harness::new(berlin(3, 4, 10, 0), vec![r]);
+        
+        harness.run_until(berlin(3, 4, 10, 25));
+        harness.planner.refresh();
+        harness.run_until(berlin(3, 4, 11, 10));
+        assert_eq!(harness.fired(1), ["Wed 11:00"]);
+        
+        harness.reminders[0].snoozed_until = Some(berlin(3, 4, 11, 15).to_rfc3339());
+        harness.planner.refresh();
+        harness.run_until(berlin(3, 4, 12, 20));
+        // The interval continues from the snoozed trigger
+        assert_eq!(harness.fired(1), ["Wed 11:00", "Wed 11:15", "Wed 12:15"]);
+    }
+    
+    #[test]
+    fn nothing_fires_while_paused() {
+        let mut r = reminder(1, 30);
+        r.last_triggered = Some(berlin(3, 4, 10, 0).to_rfc3339());
+        let mut harness = Harness::new(berlin(3, 4, 10, 0), vec![r]);
+        harness.pause = PauseState {
+            paused: true,
+            until: Some(berlin(3, 4, 11, 0).to_rfc3339()),
+        };
+        
+        harness.run_until(berlin(3, 4, 11, 0));
+        assert!(harness.fired(1).is_empty());
+    }
+    
+    #[test]
+    fn waking_up_catches_up_by_policy() {
+        let mut skip = reminder(1, 60);
+        skip.last_triggered = Some(berlin(3, 4, 10, 0).to_rfc3339());
+        let mut once = reminder(2, 60);
+        once.last_triggered = skip.last_triggered.clone();
+        once.catch_up = CatchUpPolicy::FireOnce;
+        let mut harness = Harness::new(berlin(3, 4, 10, 0), vec![skip, once]);
+        
+        harness.run_until(berlin(3, 4, 10, 30));
+        // Asleep until 13:30
+        harness.clock.jump(chrono::Duration::hours(3));
+        harness.run_until(berlin(3, 4, 14, 0));
+        
+        assert!(harness.fired(1).is_empty());
+        assert_eq!(harness.fired(2), ["Wed 13:30"]);
+        let missed: Vec<(i64, u64)> = harness
+            .sink
+            .missed
+            .iter()
+            .map(|(id, metadata)| (*id, metadata["occurrences"].as_u64().unwrap()))
+            .collect();
+        assert_eq!(missed, [(1, 3), (2, 2)]);
+        
+        // Both carry on an hour after waking up
+        harness.run_until(berlin(3, 4, 14, 40));
+        assert_eq!(harness.fired(1), ["Wed 14:30"]);
+        assert_eq!(harness.fired(2), ["Wed 13:30", "Wed 14:30"]);
+    }
+    
+    #[test]
+    fn long_absences_fire_the_latest_and_count_every_missed_occurrence() {
+        let mut interval = reminder(1, 1);
+        interval.catch_up = CatchUpPolicy::FireOnce;
+        interval.last_triggered = Some(berlin(3, 4, 10, 0).to_rfc3339());
+        let mut windowed = interval.clone();
+        windowed.id = 2;
+        windowed.active_start_time = Some("06:00".to_string());
+        windowed.active_end_time = Some("22:00".to_string());
+        let mut cron = interval.clone();
+        cron.id = 3;
+        cron.schedule = Schedule::Cron { expression: "* * * * *".to_string() };
+        
+        // Ten days of minutes, more than a step-by-step scan used to look at
+        let mut harness = Harness::new(berlin(3, 14, 10, 0), vec![interval, windowed, cron]);
+        harness.run_until(berlin(3, 14, 10, 0) + chrono::Duration::seconds(30));
+        
+        for id in 1..=3 {
+            assert_eq!(harness.fired(id), ["Sat 10:00"]);
+        }
+        let missed: Vec<(i64, u64, String)> = harness
+            .sink
+            .missed
+            .iter()
+            .map(|(id, metadata)| {
+                (*id, metadata["occurrences"].as_u64().unwrap(), metadata["last"].as_str().unwrap().to_string())
+            })
+            .collect();
+        let last = berlin(3, 14, 9, 59).to_rfc3339();
+        // The second one sleeps from 22:01 to 05:59, ten nights of 479 minutes
+        assert_eq!(missed, [(1, 14_399, last.clone()), (2, 14_399 - 4_790, last.clone()), (3, 14_399, last)]);
+    }
+    
+    #[test]
+    fn pauses_are_bounded() {
+        let now = berlin(3, 4, 10, 0);
+        
+        assert_eq!(pause_end(None, now).unwrap(), None);
+        assert_eq!(pause_end(Some(30), now).unwrap(), Some(berlin(3, 4, 10, 30)));
+        assert!(pause_end(Some(MAX_PAUSE_MINUTES), now).is_ok());
+        for minutes in [0, -5, MAX_PAUSE_MINUTES + 1, i64::MAX, i64::MIN] {
+            assert_eq!(pause_end(Some(minutes), now).unwrap_err().field(), Some("minutes"), "{}", minutes);
+        }
+    }
+    
+    #[test]
+    fn setting_the_clock_back_does_not_hold_reminders_back() {
+        let mut r = reminder(1, 60);
+        r.last_triggered = Some(berlin(3, 4, 10, 0).to_rfc3339());
+        let mut harness = Harness::new(berlin(3, 4, 10, 0), vec![r]);
+        
+        harness.run_until(berlin(3, 4, 10, 30));
+        harness.clock.jump(chrono::Duration::hours(-2));
+        harness.run_until(berlin(3, 4, 9, 40));
+        assert_eq!(harness.fired(1), ["Wed 09:30"]);
+    }
+    
+    /// The preview as (reminder, Berlin wall clock) pairs
+    fn preview(
+        reminders: &[Reminder],
+        pause: &PauseState,
+        policy: PausePolicy,
+        now: DateTime<Utc>,
+        count: usize,
+        until: Option<DateTime<Utc>>,
+    ) -> Vec<(i64, String)> {
+        upcoming_triggers(reminders, pause, policy, now, Berlin, count, until)
+            .into_iter()
+            .map(|t| {
+                let at = DateTime::parse_from_rfc3339(&t.at).unwrap().with_timezone(&Berlin);
+                (t.reminder_id, at.format("%a %H:%M").to_string())
+            })
+            .collect()
+    }
+    
+    #[test]
+    fn upcoming_triggers_match_what_fires() {
+        let mut windowed = reminder(1, 90);
+        windowed.active_start_time = Some("09:00".to_string());
+        windowed.active_end_time = Some("12:00".to_string());
+        windowed.last_triggered = Some(berlin(3, 5, 11, 30).to_rfc3339());
+        let mut fixed = reminder(2, 60);
+        fixed.schedule = Schedule::DailyTimes {
+            times: vec!["10:15".to_string(), "17:00".to_string()],
+        };
+        fixed.active_days = Some(vec![4, 5]);
+        let mut snoozed = reminder(3, 240);
+        snoozed.last_triggered = Some(berlin(3, 6, 7, 0).to_rfc3339());
+        snoozed.snoozed_until = Some(berlin(3, 6, 8, 5).to_rfc3339());
+        let reminders = vec![windowed, fixed, snoozed];
+        let start = berlin(3, 6, 8, 0);
+        
+        let upcoming = preview(&reminders, &PauseState::default(), PausePolicy::Drop, start, 12, None);
+        assert_eq!(
+            upcoming,
+            [
+                (3, "Fri 08:05"),
+                (1, "Fri 09:30"),
+                (2, "Fri 10:15"),
+                (1, "Fri 11:00"),
+                (3, "Fri 12:05"),
+                (3, "Fri 16:05"),
+                (2, "Fri 17:00"),
+                (3, "Fri 20:05"),
+                (3, "Sat 00:05"),
+                (3, "Sat 04:05"),
+                (3, "Sat 08:05"),
+                // Waited for the window to open
+                (1, "Sat 09:00"),
+            ]
+            .map(|(id, at)| (id, at.to_string()))
+        );
+        
+        let mut harness = Harness::new(start, reminders);
+        harness.run_until(berlin(3, 7, 9, 5));
+        let fired: Vec<(i64, String)> = harness
+            .sink
+            .fired
+            .iter()
+            .map(|(id, at)| (*id, at.with_timezone(&Berlin).format("%a %H:%M").to_string()))
+            .collect();
+        assert_eq!(fired, upcoming);
+    }
+    
+    #[test]
+    fn upcoming_triggers_stop_at_the_limit() {
+        let mut r = reminder(1, 30);
+        r.last_triggered = Some(berlin(3, 4, 10, 0).to_rfc3339());
+        let now = berlin(3, 4, 10, 10);
+        let pause = PauseState::default();
+        
+        let upcoming = preview(&[r.clone()], &pause, PausePolicy::Drop, now, 10, Some(berlin(3, 4, 11, 30)));
+        assert_eq!(upcoming.len(), 3);
+        assert_eq!(preview(&[r], &pause, PausePolicy::Drop, now, 2, None).len(), 2);
+    }
+    
+    #[test]
+    fn upcoming_triggers_wait_for_the_pause_to_end() {
+        let mut r = reminder(1, 60);
+        r.last_triggered = Some(berlin(3, 4, 10, 0).to_rfc3339());
+        let now = berlin(3, 4, 10, 30);
+        let mut pause = PauseState {
+            paused: true,
+            until: Some(berlin(3, 4, 12, 20).to_rfc3339()),
+        };
+        
+        let drop = preview(&[r.clone()], &pause, PausePolicy::Drop, now, 2, None);
+        assert_eq!(drop, [(1, "Wed 13:20".to_string()), (1, "Wed 14:20".to_string())]);
+        let fire = preview(&[r.clone()], &pause, PausePolicy::FireOnResume, now, 2, None);
+        assert_eq!(fire, [(1, "Wed 12:20".to_string()), (1, "Wed 13:20".to_string())]);
+        
+        pause.until = None;
+        assert!(preview(&[r], &pause, PausePolicy::Drop, now, 2, None).is_empty());
+    }
+}
@@ -0,0 +1,41 @@
+use crate::error::{AppError, AppResult};
+
+#[cfg(target_os = "linux")]
+#[zbus::proxy(
+    interface = "org.freedesktop.login1.Manager",
+    default_service = "org.freedesktop.login1",
+    default_path = "/org/freedesktop/login1"
+)]
+trait Login1Manager {
+    /// Sent with `true` before the machine suspends and `false` once it is back
+    #[zbus(signal)]
+    fn prepare_for_sleep(&self, start: bool) -> zbus::Result<()>;
+}
+
+/// Calls `on_resume` each time the machine wakes up from suspend, as reported
+/// by logind. Runs until the system bus goes away.
+#[cfg(target_os = "linux")]
+pub async fn watch_sleep(on_resume: impl Fn()) -> AppResult<()> {
+    use tokio_stream::StreamExt;
+
+    let bus_error = |e: zbus::Error| AppError::Io(format!("System bus: {}", e));
+
+    let connection = zbus::Connection::system().await.map_err(bus_error)?;
+    let manager = Login1ManagerProxy::new(&connection).await.map_err(bus_error)?;
+    let mut signals = manager.receive_prepare_for_sleep().await.map_err(bus_error)?;
+
+    while let Some(signal) = signals.next().await {
+        match signal.args() {
+            Ok(args) if !args.start => on_resume(),
+            Ok(_) => {}
+            Err(e) => eprintln!("Failed to read sleep signal: {}", e),
+        }
+    }
+    Ok(())
+}
+
+/// Elsewhere, the scheduler only notices sleeping through its clock checks
+#[cfg(not(target_os = "linux"))]
+pub async fn watch_sleep(_on_resume: impl Fn()) -> AppResult<()> {
+    Err(AppError::Io("Sleep notifications are only available on Linux".to_string()))
+}
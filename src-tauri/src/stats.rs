@@ -0,0 +1,298 @@
+use crate::error::AppResult;
+use crate::reminder::{default_timezone, system_timezone};
+use crate::storage::{self, EventKind, ReminderEvent};
+use chrono::{DateTime, Datelike, Timelike, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+
+/// A dismissal counts as "on time" when it comes this soon after the trigger
+/// without any snoozing in between
+const ON_TIME_MINUTES: i64 = 10;
+
+/// Inclusive RFC 3339 bounds, either of which may be open
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StatsRange {
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct HeatmapCell {
+    pub triggered: u32,
+    pub dismissed: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReminderStats {
+    pub reminder_id: i64,
+    pub range: StatsRange,
+    /// Triggers, not counting the re-triggers caused by snoozing
+    pub trigger_count: u32,
+    pub dismiss_count: u32,
+    pub missed_count: u32,
+    pub snooze_count: u32,
+    /// Share of triggers that were eventually dismissed (0.0 - 1.0)
+    pub dismiss_rate: Option<f64>,
+    pub average_seconds_to_dismiss: Option<f64>,
+    /// Average number of snoozes per trigger
+    pub snooze_frequency: Option<f64>,
+    pub longest_on_time_streak: u32,
+    pub games_won: u32,
+    pub games_lost: u32,
+    /// Hour of the trigger in the default zone, 0 - 23
+    pub by_hour: Vec<HeatmapCell>,
+    /// Weekday of the trigger in the default zone, Monday = 0
+    pub by_weekday: Vec<HeatmapCell>,
+    /// `heatmap[weekday][hour]`
+    pub heatmap: Vec<Vec<HeatmapCell>>,
+}
+
+pub fn get_reminder_stats(id: i64, range: StatsRange) -> AppResult<ReminderStats> {
+    let events = storage::get_events(Some(id), range.from.as_deref(), range.to.as_deref())?;
+    Ok(compute_stats(id, range, &events, heatmap_timezone()?))
+}
+
+pub fn get_all_stats(range: StatsRange) -> AppResult<Vec<ReminderStats>> {
+    let events = storage::get_events(None, range.from.as_deref(), range.to.as_deref())?;
+    let tz = heatmap_timezone()?;
+
+    storage::get_all_reminders()?
+        .into_iter()
+        .map(|reminder| {
+            let own: Vec<ReminderEvent> = events
+                .iter()
+                .filter(|e| e.reminder_id == reminder.id)
+                .cloned()
+                .collect();
+            Ok(compute_stats(reminder.id, range.clone(), &own, tz))
+        })
+        .collect()
+}
+
+/// Hours and weekdays are counted in the default zone from the settings
+fn heatmap_timezone() -> AppResult<Tz> {
+    Ok(default_timezone(&storage::get_settings()?).unwrap_or_else(system_timezone))
+}
+
+/// A trigger and everything that happened until it was dismissed, missed or
+/// replaced by the next trigger
+struct Episode {
+    triggered_at: DateTime<Utc>,
+    snoozes: u32,
+    dismissed_at: Option<DateTime<Utc>>,
+}
+
+/// Folds one reminder's chronologically ordered events into statistics, with
+/// hours and weekdays as seen in `tz`
+pub fn compute_stats(reminder_id: i64, range: StatsRange, events: &[ReminderEvent], tz: Tz) -> ReminderStats {
+    let mut episodes: Vec<Episode> = Vec::new();
+    let mut open = false;
+    let mut stats = ReminderStats {
+        reminder_id,
+        range,
+        trigger_count: 0,
+        dismiss_count: 0,
+        missed_count: 0,
+        snooze_count: 0,
+        dismiss_rate: None,
+        average_seconds_to_dismiss: None,
+        snooze_frequency: None,
+        longest_on_time_streak: 0,
+        games_won: 0,
+        games_lost: 0,
+        by_hour: vec![HeatmapCell::default(); 24],
+        by_weekday: vec![HeatmapCell::default(); 7],
+        heatmap: vec![vec![HeatmapCell::default(); 24]; 7],
+    };
+
+    for event in events {
+        let Ok(at) = DateTime::parse_from_rfc3339(&event.occurred_at) else {
+            continue;
+        };
+        let at = at.with_timezone(&Utc);
+
+        match event.kind {
+            EventKind::Triggered => {
+                let from_snooze = event
+                    .metadata
+                    .as_ref()
+                    .and_then(|m| m.get("snoozed"))
+                    .and_then(|s| s.as_bool())
+                    .unwrap_or(false);
+                // The re-trigger after a snooze belongs to the running episode
+                if !(from_snooze && open) {
+                    episodes.push(Episode {
+                        triggered_at: at,
+                        snoozes: 0,
+                        dismissed_at: None,
+                    });
+                    open = true;
+                }
+            }
+            EventKind::Snoozed => {
+                stats.snooze_count += 1;
+                if let (true, Some(episode)) = (open, episodes.last_mut()) {
+                    episode.snoozes += 1;
+                }
+            }
+            EventKind::Dismissed => {
+                stats.dismiss_count += 1;
+                if let (true, Some(episode)) = (open, episodes.last_mut()) {
+                    episode.dismissed_at = Some(at);
+                }
+                open = false;
+            }
+            EventKind::Missed => {
+                // Catch-up records everything missed while the app was away at once
+                let occurrences = event
+                    .metadata
+                    .as_ref()
+                    .and_then(|m| m.get("occurrences"))
+                    .and_then(|n| n.as_u64())
+                    .unwrap_or(1);
+                stats.missed_count += occurrences as u32;
+                open = false;
+            }
+            EventKind::GameWon => stats.games_won += 1,
+            EventKind::GameLost => stats.games_lost += 1,
+            EventKind::CommandRan | EventKind::Escalated => {}
+        }
+    }
+
+    stats.trigger_count = episodes.len() as u32;
+
+    let mut dismiss_seconds = Vec::new();
+    let mut streak = 0;
+
+    for episode in &episodes {
+        let local = episode.triggered_at.with_timezone(&tz);
+        let (weekday, hour) = (local.weekday().num_days_from_monday() as usize, local.hour() as usize);
+        let dismissed = episode.dismissed_at.is_some();
+
+        for cell in [
+            &mut stats.by_hour[hour],
+            &mut stats.by_weekday[weekday],
+            &mut stats.heatmap[weekday][hour],
+        ] {
+            cell.triggered += 1;
+            if dismissed {
+                cell.dismissed += 1;
+            }
+        }
+
+        let on_time = match episode.dismissed_at {
+            Some(dismissed_at) => {
+                let elapsed = dismissed_at - episode.triggered_at;
+                dismiss_seconds.push(elapsed.num_milliseconds() as f64 / 1000.0);
+                episode.snoozes == 0 && elapsed.num_minutes() < ON_TIME_MINUTES
+            }
+            None => false,
+        };
+        streak = if on_time { streak + 1 } else { 0 };
+        stats.longest_on_time_streak = stats.longest_on_time_streak.max(streak);
+    }
+
+    if !episodes.is_empty() {
+        let count = episodes.len() as f64;
+        stats.dismiss_rate = Some(dismiss_seconds.len() as f64 / count);
+        stats.snooze_frequency = Some(episodes.iter().map(|e| e.snoozes).sum::<u32>() as f64 / count);
+    }
+    if !dismiss_seconds.is_empty() {
+        stats.average_seconds_to_dismiss =
+            Some(dismiss_seconds.iter().sum::<f64>() / dismiss_seconds.len() as f64);
+    }
+
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use chrono_tz::Europe::Berlin;
+
+    /// An event at a Berlin wall-clock time in March 2026
+    fn event(kind: EventKind, day: u32, hour: u32, minute: u32, metadata: Option<serde_json::Value>) -> ReminderEvent {
+        let at = Berlin.with_ymd_and_hms(2026, 3, day, hour, minute, 0).unwrap();
+        ReminderEvent {
+            id: 0,
+            reminder_id: 1,
+            kind,
+            occurred_at: at.with_timezone(&Utc).to_rfc3339(),
+            metadata,
+        }
+    }
+
+    fn events() -> Vec<ReminderEvent> {
+        let snoozed = || Some(serde_json::json!({ "snoozed": true }));
+        vec![
+            // Monday, answered within two minutes
+            event(EventKind::Triggered, 2, 9, 0, None),
+            event(EventKind::Dismissed, 2, 9, 2, None),
+            event(EventKind::GameWon, 2, 9, 2, None),
+            // Snoozed twice, the re-triggers belong to the same episode
+            event(EventKind::Triggered, 2, 10, 0, None),
+            event(EventKind::Snoozed, 2, 10, 1, None),
+            event(EventKind::Triggered, 2, 10, 10, snoozed()),
+            event(EventKind::Snoozed, 2, 10, 11, None),
+            event(EventKind::Triggered, 2, 10, 20, snoozed()),
+            event(EventKind::Dismissed, 2, 10, 30, None),
+            // Tuesday night, 22:30 UTC
+            event(EventKind::Triggered, 3, 23, 30, None),
+            event(EventKind::Dismissed, 3, 23, 31, None),
+            // Wednesday just after midnight, which is still Tuesday in UTC
+            event(EventKind::Triggered, 4, 0, 30, None),
+            event(EventKind::Missed, 4, 1, 0, None),
+            // Catch-up reports everything missed while the app was closed at once
+            event(EventKind::Missed, 5, 9, 0, Some(serde_json::json!({ "occurrences": 4 }))),
+        ]
+    }
+
+    #[test]
+    fn folds_events_into_episodes() {
+        let stats = compute_stats(1, StatsRange::default(), &events(), Berlin);
+
+        assert_eq!(stats.trigger_count, 4);
+        assert_eq!(stats.dismiss_count, 3);
+        assert_eq!(stats.snooze_count, 2);
+        assert_eq!(stats.missed_count, 5);
+        assert_eq!(stats.games_won, 1);
+        assert_eq!(stats.dismiss_rate, Some(0.75));
+        assert_eq!(stats.snooze_frequency, Some(0.5));
+        // 2, 30 and 1 minutes
+        assert_eq!(stats.average_seconds_to_dismiss, Some((120.0 + 1800.0 + 60.0) / 3.0));
+        assert_eq!(stats.longest_on_time_streak, 1);
+    }
+
+    #[test]
+    fn heatmap_buckets_by_the_given_zone() {
+        let cell = |c: HeatmapCell| (c.triggered, c.dismissed);
+
+        let berlin = compute_stats(1, StatsRange::default(), &events(), Berlin);
+        assert_eq!(cell(berlin.by_hour[9]), (1, 1));
+        assert_eq!(cell(berlin.by_hour[10]), (1, 1));
+        assert_eq!(cell(berlin.by_hour[23]), (1, 1));
+        assert_eq!(cell(berlin.by_hour[0]), (1, 0));
+        let weekdays: Vec<(u32, u32)> = berlin.by_weekday.iter().copied().map(cell).collect();
+        assert_eq!(weekdays, [(2, 2), (1, 1), (1, 0), (0, 0), (0, 0), (0, 0), (0, 0)]);
+        assert_eq!(cell(berlin.heatmap[2][0]), (1, 0));
+        assert_eq!(berlin.heatmap.iter().flatten().map(|c| c.triggered).sum::<u32>(), 4);
+
+        let utc = compute_stats(1, StatsRange::default(), &events(), Tz::UTC);
+        assert_eq!(cell(utc.by_hour[8]), (1, 1));
+        assert_eq!(cell(utc.by_hour[22]), (1, 1));
+        assert_eq!(cell(utc.by_hour[23]), (1, 0));
+        assert_eq!(cell(utc.heatmap[1][23]), (1, 0));
+        assert_eq!(utc.by_weekday[1].triggered, 2);
+    }
+
+    #[test]
+    fn without_triggers_the_rates_are_empty() {
+        let stats = compute_stats(1, StatsRange::default(), &[], Berlin);
+
+        assert_eq!(stats.trigger_count, 0);
+        assert_eq!(stats.dismiss_rate, None);
+        assert_eq!(stats.average_seconds_to_dismiss, None);
+        assert_eq!(stats.snooze_frequency, None);
+    }
+}
@@ -1,76 +1,215 @@
 use chrono::Utc;
 use once_cell::sync::Lazy;
-use parking_lot::Mutex;
+use parking_lot::{Mutex, MutexGuard};
 use rusqlite::{Connection, Result as SqliteResult, params};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
-static DB: Lazy<Mutex<Connection>> = Lazy::new(|| {
-    let conn = init_db().expect("Failed to initialize database");
-    Mutex::new(conn)
-});
+use crate::error::{AppError, AppResult};
+use super::migrations::migrate;
+use super::events::{delete_events_for_reminder, record_event, EventKind};
+use super::settings::{get_settings, store_settings, Settings};
+use super::webhooks::delete_webhooks_for_reminder;
 
-fn get_db_path() -> PathBuf {
+/// The connection, or why the database could not be opened. A failure is
+/// reported by every storage call instead of taking the app down.
+static DB: Lazy<Result<Mutex<Connection>, String>> = Lazy::new(|| init_db().map(Mutex::new));
+
+pub(super) fn db() -> AppResult<MutexGuard<'static, Connection>> {
+    match DB.as_ref() {
+        Ok(conn) => Ok(conn.lock()),
+        Err(e) => Err(AppError::Db(format!("Database unavailable: {}", e))),
+    }
+}
+
+/// Opens and migrates the database, returning the error if that failed
+pub fn init() -> AppResult<()> {
+    db().map(|_| ())
+}
+
+/// Directory holding the database and other per-user state
+pub fn data_dir() -> PathBuf {
     let data_dir = dirs::data_local_dir()
         .unwrap_or_else(|| PathBuf::from("."))
         .join("multitask-reminder");
     
     std::fs::create_dir_all(&data_dir).ok();
-    data_dir.join("reminders.db")
+    data_dir
 }
 
-fn init_db() -> SqliteResult<Connection> {
+fn get_db_path() -> PathBuf {
+    data_dir().join("reminders.db")
+}
+
+fn init_db() -> Result<Connection, String> {
     let db_path = get_db_path();
-    let conn = Connection::open(db_path)?;
-    
-    conn.execute(
-        "CREATE TABLE IF NOT EXISTS reminders (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            name TEXT NOT NULL,
-            message TEXT,
-            interval_minutes INTEGER NOT NULL,
-            enabled INTEGER NOT NULL DEFAULT 1,
-            active_start_time TEXT,
-            active_end_time TEXT,
-            active_days TEXT,
-            sound TEXT DEFAULT 'chime',
-            last_triggered TEXT,
-            created_at TEXT NOT NULL
-        )",
-        [],
-    )?;
+    let mut conn = Connection::open(&db_path)
+        .map_err(|e| format!("Failed to open {}: {}", db_path.display(), e))?;
+    // The command-line tool writes to the same database
+    conn.busy_timeout(std::time::Duration::from_secs(5))
+        .map_err(|e| e.to_string())?;
     
-    // Migration: add sound column if it doesn't exist
-    let _ = conn.execute("ALTER TABLE reminders ADD COLUMN sound TEXT DEFAULT 'chime'", []);
+    migrate(&mut conn)?;
     
     Ok(conn)
 }
 
+/// When a reminder fires. Everything except `Interval` is evaluated in local
+/// time and additionally filtered by the active days and time window.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Schedule {
+    /// Every `interval_minutes` after the last trigger
+    #[default]
+    Interval,
+    /// Fixed times of day ("HH:MM")
+    DailyTimes { times: Vec<String> },
+    /// Cron expression, with or without a seconds field
+    Cron { expression: String },
+    /// RFC 5545 recurrence rule, optionally preceded by a DTSTART line
+    Rrule { rule: String },
+}
+
+/// A program to run whenever the reminder fires. It is started directly, not
+/// through a shell, with the reminder in `REMINDER_ID`, `REMINDER_NAME` and
+/// `REMINDER_MESSAGE`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReminderCommand {
+    pub program: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default = "default_command_timeout")]
+    pub timeout_seconds: u32,
+    /// Nothing runs unless this was switched on for this reminder. Imports
+    /// switch it off.
+    #[serde(default)]
+    pub enabled: bool,
+}
+
+pub const DEFAULT_COMMAND_TIMEOUT_SECONDS: u32 = 30;
+
+fn default_command_timeout() -> u32 {
+    DEFAULT_COMMAND_TIMEOUT_SECONDS
+}
+
+/// How a reminder shows up when it fires
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DeliveryStyle {
+    /// The popup window with the mini-game
+    #[default]
+    Popup,
+    /// A desktop notification with Done and Snooze buttons
+    Notification,
+    /// A notification, followed by the popup if it is still unanswered after
+    /// `minutes`
+    NotificationThenPopup { minutes: u32 },
+    /// Only recorded in the history
+    Silent,
+}
+
+/// Something that happens while a fired reminder stays unanswered
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EscalationStep {
+    /// Minutes after the reminder fired
+    pub after_minutes: u32,
+    pub action: EscalationAction,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum EscalationAction {
+    /// Plays the sound again
+    ReplaySound,
+    /// Brings the popup back to the front and centers it
+    Refocus,
+    /// Plays a different sound, which is also used by later steps
+    ChangeSound { sound: String },
+    /// Records an `escalated` event, which webhooks subscribed to it receive
+    Webhook,
+}
+
+/// What happens to occurrences that passed while the app was closed or the
+/// machine was asleep. Missed occurrences that don't fire are recorded as
+/// `missed` events.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CatchUpPolicy {
+    /// Carry on with the next regular occurrence
+    #[default]
+    Skip,
+    /// Fire once, for the latest of them
+    FireOnce,
+    /// Fire for each of the latest `max`, one after the other as they are answered
+    FireEach { max: u32 },
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Reminder {
     pub id: i64,
     pub name: String,
     pub message: Option<String>,
     pub interval_minutes: i32,
+    #[serde(default)]
+    pub schedule: Schedule,
+    /// Set for one-shot reminders, which fire once at this time (RFC 3339)
+    /// instead of following `schedule`
+    #[serde(default)]
+    pub due_at: Option<String>,
     pub enabled: bool,
     pub active_start_time: Option<String>,
     pub active_end_time: Option<String>,
     pub active_days: Option<Vec<u8>>,
     pub sound: String,
+    #[serde(default)]
+    pub command: Option<ReminderCommand>,
+    #[serde(default)]
+    pub delivery_style: DeliveryStyle,
+    /// Applied in order of `after_minutes`, see `EscalationStep`
+    #[serde(default)]
+    pub escalation: Vec<EscalationStep>,
+    #[serde(default)]
+    pub catch_up: CatchUpPolicy,
+    /// IANA zone the times of day are read in, e.g. "Europe/Berlin". `None`
+    /// uses the default zone from the settings.
+    #[serde(default)]
+    pub timezone: Option<String>,
     pub last_triggered: Option<String>,
+    pub snoozed_until: Option<String>,
+    pub snooze_count: i32,
+    /// When the reminder fired without being dismissed or snoozed since
+    #[serde(default)]
+    pub pending_since: Option<String>,
     pub created_at: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CreateReminder {
     pub name: String,
     pub message: Option<String>,
     pub interval_minutes: i32,
+    #[serde(default)]
+    pub schedule: Schedule,
+    #[serde(default)]
+    pub due_at: Option<String>,
     pub enabled: bool,
     pub active_start_time: Option<String>,
     pub active_end_time: Option<String>,
     pub active_days: Option<Vec<u8>>,
     pub sound: String,
+    #[serde(default)]
+    pub command: Option<ReminderCommand>,
+    #[serde(default)]
+    pub delivery_style: DeliveryStyle,
+    /// Applied in order of `after_minutes`, see `EscalationStep`
+    #[serde(default)]
+    pub escalation: Vec<EscalationStep>,
+    #[serde(default)]
+    pub catch_up: CatchUpPolicy,
+    /// IANA zone the times of day are read in, e.g. "Europe/Berlin". `None`
+    /// uses the default zone from the settings.
+    #[serde(default)]
+    pub timezone: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -79,158 +218,443 @@ pub struct UpdateReminder {
     pub name: String,
     pub message: Option<String>,
     pub interval_minutes: i32,
+    #[serde(default)]
+    pub schedule: Schedule,
+    #[serde(default)]
+    pub due_at: Option<String>,
     pub enabled: bool,
     pub active_start_time: Option<String>,
     pub active_end_time: Option<String>,
     pub active_days: Option<Vec<u8>>,
     pub sound: String,
+    #[serde(default)]
+    pub command: Option<ReminderCommand>,
+    #[serde(default)]
+    pub delivery_style: DeliveryStyle,
+    /// Applied in order of `after_minutes`, see `EscalationStep`
+    #[serde(default)]
+    pub escalation: Vec<EscalationStep>,
+    #[serde(default)]
+    pub catch_up: CatchUpPolicy,
+    /// IANA zone the times of day are read in, e.g. "Europe/Berlin". `None`
+    /// uses the default zone from the settings.
+    #[serde(default)]
+    pub timezone: Option<String>,
+}
+
+impl From<&Reminder> for CreateReminder {
+    /// The definition of an existing reminder, without its runtime state
+    fn from(reminder: &Reminder) -> Self {
+        CreateReminder {
+            name: reminder.name.clone(),
+            message: reminder.message.clone(),
+            interval_minutes: reminder.interval_minutes,
+            schedule: reminder.schedule.clone(),
+            due_at: reminder.due_at.clone(),
+            enabled: reminder.enabled,
+            active_start_time: reminder.active_start_time.clone(),
+            active_end_time: reminder.active_end_time.clone(),
+            active_days: reminder.active_days.clone(),
+            sound: reminder.sound.clone(),
+            command: reminder.command.clone(),
+            delivery_style: reminder.delivery_style.clone(),
+            escalation: reminder.escalation.clone(),
+            catch_up: reminder.catch_up.clone(),
+            timezone: reminder.timezone.clone(),
+        }
+    }
+}
+
+impl UpdateReminder {
+    pub fn new(id: i64, reminder: CreateReminder) -> Self {
+        UpdateReminder {
+            id,
+            name: reminder.name,
+            message: reminder.message,
+            interval_minutes: reminder.interval_minutes,
+            schedule: reminder.schedule,
+            due_at: reminder.due_at,
+            enabled: reminder.enabled,
+            active_start_time: reminder.active_start_time,
+            active_end_time: reminder.active_end_time,
+            active_days: reminder.active_days,
+            sound: reminder.sound,
+            command: reminder.command,
+            delivery_style: reminder.delivery_style,
+            escalation: reminder.escalation,
+            catch_up: reminder.catch_up,
+            timezone: reminder.timezone,
+        }
+    }
+}
+
+const REMINDER_COLUMNS: &str = "id, name, message, interval_minutes, enabled, active_start_time, active_end_time, active_days, sound, last_triggered, snoozed_until, snooze_count, created_at, schedule, due_at, command, delivery_style, escalation, pending_since, catch_up, timezone";
+
+fn row_to_reminder(row: &rusqlite::Row) -> SqliteResult<Reminder> {
+    let active_days_str: Option<String> = row.get(7)?;
+    let active_days: Option<Vec<u8>> = active_days_str
+        .and_then(|s| serde_json::from_str(&s).ok());
+    
+    Ok(Reminder {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        message: row.get(2)?,
+        interval_minutes: row.get(3)?,
+        enabled: row.get::<_, i32>(4)? != 0,
+        active_start_time: row.get(5)?,
+        active_end_time: row.get(6)?,
+        active_days,
+        sound: row.get::<_, Option<String>>(8)?.unwrap_or_else(|| "chime".to_string()),
+        last_triggered: row.get(9)?,
+        snoozed_until: row.get(10)?,
+        snooze_count: row.get(11)?,
+        created_at: row.get(12)?,
+        schedule: row
+            .get::<_, Option<String>>(13)?
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default(),
+        due_at: row.get(14)?,
+        command: row
+            .get::<_, Option<String>>(15)?
+            .and_then(|s| serde_json::from_str(&s).ok()),
+        delivery_style: row
+            .get::<_, Option<String>>(16)?
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default(),
+        escalation: row
+            .get::<_, Option<String>>(17)?
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default(),
+        pending_since: row.get(18)?,
+        catch_up: row
+            .get::<_, Option<String>>(19)?
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default(),
+        timezone: row.get(20)?,
+    })
+}
+
+fn not_found(id: i64) -> AppError {
+    AppError::NotFound(format!("Reminder {}", id))
 }
 
-pub fn get_all_reminders() -> Result<Vec<Reminder>, String> {
-    let conn = DB.lock();
+pub fn get_all_reminders() -> AppResult<Vec<Reminder>> {
+    let conn = db()?;
     
     let mut stmt = conn
-        .prepare("SELECT id, name, message, interval_minutes, enabled, active_start_time, active_end_time, active_days, sound, last_triggered, created_at FROM reminders ORDER BY created_at DESC")
-        .map_err(|e| e.to_string())?;
+        .prepare(&format!("SELECT {} FROM reminders ORDER BY created_at DESC", REMINDER_COLUMNS))?;
     
     let reminders = stmt
-        .query_map([], |row| {
-            let active_days_str: Option<String> = row.get(7)?;
-            let active_days: Option<Vec<u8>> = active_days_str
-                .and_then(|s| serde_json::from_str(&s).ok());
-            
-            Ok(Reminder {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                message: row.get(2)?,
-                interval_minutes: row.get(3)?,
-                enabled: row.get::<_, i32>(4)? != 0,
-                active_start_time: row.get(5)?,
-                active_end_time: row.get(6)?,
-                active_days,
-                sound: row.get::<_, Option<String>>(8)?.unwrap_or_else(|| "chime".to_string()),
-                last_triggered: row.get(9)?,
-                created_at: row.get(10)?,
-            })
-        })
-        .map_err(|e| e.to_string())?
-        .collect::<Result<Vec<_>, _>>()
-        .map_err(|e| e.to_string())?;
+        .query_map([], row_to_reminder)?
+        .collect::<Result<Vec<_>, _>>()?;
     
     Ok(reminders)
 }
 
-pub fn get_enabled_reminders() -> Result<Vec<Reminder>, String> {
+pub fn get_enabled_reminders() -> AppResult<Vec<Reminder>> {
     let all = get_all_reminders()?;
     Ok(all.into_iter().filter(|r| r.enabled).collect())
 }
 
-pub fn get_reminder_by_id(id: i64) -> Result<Option<Reminder>, String> {
-    let conn = DB.lock();
+pub fn get_reminder_by_id(id: i64) -> AppResult<Option<Reminder>> {
+    let conn = db()?;
     
     let mut stmt = conn
-        .prepare("SELECT id, name, message, interval_minutes, enabled, active_start_time, active_end_time, active_days, sound, last_triggered, created_at FROM reminders WHERE id = ?")
-        .map_err(|e| e.to_string())?;
+        .prepare(&format!("SELECT {} FROM reminders WHERE id = ?", REMINDER_COLUMNS))?;
+    
+    let mut rows = stmt.query(params![id])?;
     
-    let mut rows = stmt.query(params![id]).map_err(|e| e.to_string())?;
-    
-    if let Some(row) = rows.next().map_err(|e| e.to_string())? {
-        let active_days_str: Option<String> = row.get(7).map_err(|e| e.to_string())?;
-        let active_days: Option<Vec<u8>> = active_days_str
-            .and_then(|s| serde_json::from_str(&s).ok());
-        
-        Ok(Some(Reminder {
-            id: row.get(0).map_err(|e| e.to_string())?,
-            name: row.get(1).map_err(|e| e.to_string())?,
-            message: row.get(2).map_err(|e| e.to_string())?,
-            interval_minutes: row.get(3).map_err(|e| e.to_string())?,
-            enabled: row.get::<_, i32>(4).map_err(|e| e.to_string())? != 0,
-            active_start_time: row.get(5).map_err(|e| e.to_string())?,
-            active_end_time: row.get(6).map_err(|e| e.to_string())?,
-            active_days,
-            sound: row.get::<_, Option<String>>(8).map_err(|e| e.to_string())?.unwrap_or_else(|| "chime".to_string()),
-            last_triggered: row.get(9).map_err(|e| e.to_string())?,
-            created_at: row.get(10).map_err(|e| e.to_string())?,
-        }))
+    if let Some(row) = rows.next()? {
+        Ok(Some(row_to_reminder(row)?))
     } else {
         Ok(None)
     }
 }
 
-pub fn create_reminder(reminder: CreateReminder) -> Result<Reminder, String> {
-    let conn = DB.lock();
+pub fn create_reminder(reminder: CreateReminder) -> AppResult<Reminder> {
+    let conn = db()?;
+    let id = insert_reminder(&conn, reminder)?;
+    drop(conn);
+    
+    get_reminder_by_id(id)?.ok_or_else(|| not_found(id))
+}
+
+fn insert_reminder(conn: &Connection, mut reminder: CreateReminder) -> AppResult<i64> {
+    reminder.validate()?;
     let now = Utc::now().to_rfc3339();
     let active_days_json = reminder.active_days.as_ref().map(|d| serde_json::to_string(d).unwrap());
+    let schedule_json = serde_json::to_string(&reminder.schedule).unwrap();
+    let command_json = reminder.command.as_ref().map(|c| serde_json::to_string(c).unwrap());
+    let delivery_style_json = serde_json::to_string(&reminder.delivery_style).unwrap();
+    let escalation_json = serde_json::to_string(&reminder.escalation).unwrap();
+    let catch_up_json = serde_json::to_string(&reminder.catch_up).unwrap();
     
     conn.execute(
-        "INSERT INTO reminders (name, message, interval_minutes, enabled, active_start_time, active_end_time, active_days, sound, created_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        "INSERT INTO reminders (name, message, interval_minutes, schedule, due_at, enabled, active_start_time, active_end_time, active_days, sound, command, delivery_style, escalation, catch_up, timezone, created_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
         params![
             reminder.name,
             reminder.message,
             reminder.interval_minutes,
+            schedule_json,
+            reminder.due_at,
             reminder.enabled as i32,
             reminder.active_start_time,
             reminder.active_end_time,
             active_days_json,
             reminder.sound,
+            command_json,
+            delivery_style_json,
+            escalation_json,
+            catch_up_json,
+            reminder.timezone,
             now
         ],
-    ).map_err(|e| e.to_string())?;
+    )?;
     
-    let id = conn.last_insert_rowid();
+    Ok(conn.last_insert_rowid())
+}
+
+pub fn update_reminder(reminder: UpdateReminder) -> AppResult<Reminder> {
+    let id = reminder.id;
+    let conn = db()?;
+    write_reminder(&conn, reminder)?;
     drop(conn);
     
-    get_reminder_by_id(id)?.ok_or("Failed to retrieve created reminder".to_string())
+    get_reminder_by_id(id)?.ok_or_else(|| not_found(id))
 }
 
-pub fn update_reminder(reminder: UpdateReminder) -> Result<Reminder, String> {
-    let conn = DB.lock();
+fn write_reminder(conn: &Connection, mut reminder: UpdateReminder) -> AppResult<()> {
+    reminder.validate()?;
     let active_days_json = reminder.active_days.as_ref().map(|d| serde_json::to_string(d).unwrap());
+    let schedule_json = serde_json::to_string(&reminder.schedule).unwrap();
+    let command_json = reminder.command.as_ref().map(|c| serde_json::to_string(c).unwrap());
+    let delivery_style_json = serde_json::to_string(&reminder.delivery_style).unwrap();
+    let escalation_json = serde_json::to_string(&reminder.escalation).unwrap();
+    let catch_up_json = serde_json::to_string(&reminder.catch_up).unwrap();
     
+    // Moving the due date of a one-shot reminder re-arms it
     conn.execute(
-        "UPDATE reminders SET name = ?, message = ?, interval_minutes = ?, enabled = ?, active_start_time = ?, active_end_time = ?, active_days = ?, sound = ? WHERE id = ?",
+        "UPDATE reminders SET name = ?, message = ?, interval_minutes = ?, schedule = ?, last_triggered = CASE WHEN due_at IS ? THEN last_triggered ELSE NULL END, due_at = ?, enabled = ?, active_start_time = ?, active_end_time = ?, active_days = ?, sound = ?, command = ?, delivery_style = ?, escalation = ?, catch_up = ?, timezone = ? WHERE id = ?",
         params![
             reminder.name,
             reminder.message,
             reminder.interval_minutes,
+            schedule_json,
+            reminder.due_at,
+            reminder.due_at,
             reminder.enabled as i32,
             reminder.active_start_time,
             reminder.active_end_time,
             active_days_json,
             reminder.sound,
+            command_json,
+            delivery_style_json,
+            escalation_json,
+            catch_up_json,
+            reminder.timezone,
             reminder.id
         ],
-    ).map_err(|e| e.to_string())?;
+    )?;
+    Ok(())
+}
+
+pub fn delete_reminder(id: i64) -> AppResult<()> {
+    let mut conn = db()?;
+    let tx = conn.transaction()?;
+    remove_reminder(&tx, id)?;
+    tx.commit()?;
+    Ok(())
+}
+
+/// Deletes the reminder along with its webhooks and history
+fn remove_reminder(conn: &Connection, id: i64) -> AppResult<()> {
+    conn.execute("DELETE FROM reminders WHERE id = ?", params![id])?;
+    delete_webhooks_for_reminder(conn, id)?;
+    delete_events_for_reminder(conn, id)
+}
+
+/// Writes that have to happen together, like those of an import
+#[derive(Debug, Default)]
+pub struct ReminderChanges {
+    pub settings: Option<Settings>,
+    pub deletions: Vec<i64>,
+    pub updates: Vec<UpdateReminder>,
+    pub creations: Vec<CreateReminder>,
+}
+
+/// Applies all of `changes` in one transaction, so a write that fails leaves
+/// everything as it was
+pub fn apply_changes(changes: ReminderChanges) -> AppResult<()> {
+    let mut conn = db()?;
+    apply(&mut conn, changes)
+}
+
+fn apply(conn: &mut Connection, changes: ReminderChanges) -> AppResult<()> {
+    let tx = conn.transaction()?;
     
-    drop(conn);
+    if let Some(settings) = changes.settings {
+        store_settings(&tx, settings)?;
+    }
+    for id in changes.deletions {
+        remove_reminder(&tx, id)?;
+    }
+    for reminder in changes.updates {
+        write_reminder(&tx, reminder)?;
+    }
+    for reminder in changes.creations {
+        insert_reminder(&tx, reminder)?;
+    }
     
-    get_reminder_by_id(reminder.id)?.ok_or("Failed to retrieve updated reminder".to_string())
+    tx.commit()?;
+    Ok(())
 }
 
-pub fn delete_reminder(id: i64) -> Result<(), String> {
-    let conn = DB.lock();
-    conn.execute("DELETE FROM reminders WHERE id = ?", params![id])
-        .map_err(|e| e.to_string())?;
+/// Records that the reminder fired. With `awaits_answer` it stays pending
+/// until it is dismissed or snoozed.
+pub fn update_last_triggered(id: i64, awaits_answer: bool) -> AppResult<()> {
+    let conn = db()?;
+    let now = Utc::now().to_rfc3339();
+    let pending_since = awaits_answer.then(|| now.clone());
+    // A snooze is consumed by the trigger it scheduled, but the count is kept
+    // until the reminder is actually dismissed
+    conn.execute(
+        "UPDATE reminders SET last_triggered = ?, snoozed_until = NULL, pending_since = ? WHERE id = ?",
+        params![now, pending_since, id],
+    )?;
     Ok(())
 }
 
-pub fn update_last_triggered(id: i64) -> Result<(), String> {
-    let conn = DB.lock();
+/// Enabled reminders that fired and haven't been answered, longest waiting first
+pub fn get_pending_reminders() -> AppResult<Vec<Reminder>> {
+    let conn = db()?;
+    
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM reminders WHERE pending_since IS NOT NULL AND enabled = 1 ORDER BY pending_since",
+        REMINDER_COLUMNS
+    ))?;
+    
+    let reminders = stmt
+        .query_map([], row_to_reminder)?
+        .collect::<Result<Vec<_>, _>>()?;
+    
+    Ok(reminders)
+}
+
+pub fn dismiss_reminder(id: i64) -> AppResult<()> {
+    let conn = db()?;
     let now = Utc::now().to_rfc3339();
-    conn.execute(
-        "UPDATE reminders SET last_triggered = ? WHERE id = ?",
+    // One-shot reminders are done once dismissed
+    let updated = conn.execute(
+        "UPDATE reminders SET last_triggered = ?, snoozed_until = NULL, snooze_count = 0, pending_since = NULL, enabled = CASE WHEN due_at IS NULL THEN enabled ELSE 0 END WHERE id = ?",
         params![now, id],
-    ).map_err(|e| e.to_string())?;
+    )?;
+    drop(conn);
+    
+    if updated == 0 {
+        return Err(not_found(id));
+    }
+    
+    record_event(id, EventKind::Dismissed, None)?;
     Ok(())
 }
 
-pub fn toggle_reminder(id: i64, enabled: bool) -> Result<Reminder, String> {
-    let conn = DB.lock();
+pub fn snooze_reminder(id: i64, minutes: i32) -> AppResult<Reminder> {
+    if minutes <= 0 {
+        return Err(AppError::validation("minutes", "Snooze duration must be at least one minute"));
+    }
+    
+    let reminder = get_reminder_by_id(id)?.ok_or_else(|| not_found(id))?;
+    let settings = get_settings()?;
+    
+    if let Some(max) = settings.max_snooze_count {
+        if reminder.snooze_count >= max {
+            return Err(AppError::validation(
+                "minutes",
+                format!("Reminder has already been snoozed {} times", max),
+            ));
+        }
+    }
+    
+    let conn = db()?;
+    let until = (Utc::now() + chrono::Duration::minutes(minutes as i64)).to_rfc3339();
+    conn.execute(
+        "UPDATE reminders SET snoozed_until = ?, snooze_count = snooze_count + 1, pending_since = NULL WHERE id = ?",
+        params![until, id],
+    )?;
+    drop(conn);
+    
+    record_event(
+        id,
+        EventKind::Snoozed,
+        Some(serde_json::json!({ "minutes": minutes, "until": until })),
+    )?;
+    
+    get_reminder_by_id(id)?.ok_or_else(|| not_found(id))
+}
+
+pub fn toggle_reminder(id: i64, enabled: bool) -> AppResult<Reminder> {
+    let conn = db()?;
+    // A disabled reminder no longer waits for an answer
     conn.execute(
-        "UPDATE reminders SET enabled = ? WHERE id = ?",
-        params![enabled as i32, id],
-    ).map_err(|e| e.to_string())?;
+        "UPDATE reminders SET enabled = ?, pending_since = CASE WHEN ? THEN pending_since ELSE NULL END WHERE id = ?",
+        params![enabled as i32, enabled, id],
+    )?;
     drop(conn);
     
-    get_reminder_by_id(id)?.ok_or("Failed to retrieve updated reminder".to_string())
+    get_reminder_by_id(id)?.ok_or_else(|| not_found(id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reminder(name: &str) -> CreateReminder {
+        CreateReminder {
+            name: name.to_string(),
+            message: None,
+            interval_minutes: 30,
+            schedule: Schedule::Interval,
+            due_at: None,
+            enabled: true,
+            active_start_time: None,
+            active_end_time: None,
+            active_days: None,
+            sound: "chime".to_string(),
+            command: None,
+            delivery_style: DeliveryStyle::Popup,
+            escalation: Vec::new(),
+            catch_up: CatchUpPolicy::Skip,
+            timezone: None,
+        }
+    }
+
+    fn names(conn: &Connection) -> Vec<String> {
+        let mut stmt = conn.prepare("SELECT name FROM reminders ORDER BY id").unwrap();
+        stmt.query_map([], |row| row.get(0)).unwrap().collect::<Result<_, _>>().unwrap()
+    }
+
+    #[test]
+    fn changes_are_applied_all_or_nothing() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        migrate(&mut conn).unwrap();
+        let id = insert_reminder(&conn, reminder("Water")).unwrap();
+
+        let failing = ReminderChanges {
+            deletions: vec![id],
+            creations: vec![reminder("Stretch"), reminder("")],
+            ..Default::default()
+        };
+        assert!(apply(&mut conn, failing).is_err());
+        assert_eq!(names(&conn), ["Water"]);
+
+        let mut update = UpdateReminder::new(id, reminder("Drink water"));
+        update.interval_minutes = 45;
+        let changes = ReminderChanges {
+            updates: vec![update],
+            creations: vec![reminder("Stretch")],
+            ..Default::default()
+        };
+        apply(&mut conn, changes).unwrap();
+        assert_eq!(names(&conn), ["Drink water", "Stretch"]);
+    }
 }
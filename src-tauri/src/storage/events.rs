@@ -0,0 +1,245 @@
+use super::db::db;
+use super::webhooks::enqueue_deliveries;
+use crate::error::{AppError, AppResult};
+use chrono::{DateTime, SecondsFormat, Utc};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    Triggered,
+    Dismissed,
+    Snoozed,
+    GameWon,
+    GameLost,
+    /// An occurrence that was never shown or never acknowledged
+    Missed,
+    /// The reminder's command ran, with its outcome as metadata
+    CommandRan,
+    /// An unanswered reminder reached a webhook escalation step
+    Escalated,
+}
+
+impl EventKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EventKind::Triggered => "triggered",
+            EventKind::Dismissed => "dismissed",
+            EventKind::Snoozed => "snoozed",
+            EventKind::GameWon => "game_won",
+            EventKind::GameLost => "game_lost",
+            EventKind::Missed => "missed",
+            EventKind::CommandRan => "command_ran",
+            EventKind::Escalated => "escalated",
+        }
+    }
+    
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "triggered" => Some(EventKind::Triggered),
+            "dismissed" => Some(EventKind::Dismissed),
+            "snoozed" => Some(EventKind::Snoozed),
+            "game_won" => Some(EventKind::GameWon),
+            "game_lost" => Some(EventKind::GameLost),
+            "missed" => Some(EventKind::Missed),
+            "command_ran" => Some(EventKind::CommandRan),
+            "escalated" => Some(EventKind::Escalated),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReminderEvent {
+    pub id: i64,
+    pub reminder_id: i64,
+    pub kind: EventKind,
+    pub occurred_at: String,
+    pub metadata: Option<serde_json::Value>,
+}
+
+/// Event timestamps are stored as fixed-width UTC strings so they can be
+/// compared and sorted as text
+fn format_timestamp(t: DateTime<Utc>) -> String {
+    t.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+fn normalize_timestamp(field: &str, value: &str) -> AppResult<String> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|t| format_timestamp(t.with_timezone(&Utc)))
+        .map_err(|_| AppError::validation(field, format!("Invalid timestamp '{}'", value)))
+}
+
+pub fn record_event(
+    reminder_id: i64,
+    kind: EventKind,
+    metadata: Option<serde_json::Value>,
+) -> AppResult<ReminderEvent> {
+    record_event_at(reminder_id, kind, Utc::now(), metadata)
+}
+
+pub fn record_event_at(
+    reminder_id: i64,
+    kind: EventKind,
+    occurred_at: DateTime<Utc>,
+    metadata: Option<serde_json::Value>,
+) -> AppResult<ReminderEvent> {
+    let conn = db()?;
+    insert_event(&conn, reminder_id, kind, occurred_at, metadata)
+}
+
+fn insert_event(
+    conn: &Connection,
+    reminder_id: i64,
+    kind: EventKind,
+    occurred_at: DateTime<Utc>,
+    metadata: Option<serde_json::Value>,
+) -> AppResult<ReminderEvent> {
+    let occurred_at = format_timestamp(occurred_at);
+    let metadata_json = metadata.as_ref().map(|m| m.to_string());
+    
+    conn.execute(
+        "INSERT INTO reminder_events (reminder_id, kind, occurred_at, metadata) VALUES (?, ?, ?, ?)",
+        params![reminder_id, kind.as_str(), occurred_at, metadata_json],
+    )?;
+    
+    let event = ReminderEvent {
+        id: conn.last_insert_rowid(),
+        reminder_id,
+        kind,
+        occurred_at,
+        metadata,
+    };
+    enqueue_deliveries(conn, &event)?;
+    Ok(event)
+}
+
+/// Events in chronological order, optionally limited to one reminder and to
+/// an inclusive RFC 3339 time range
+pub fn get_events(
+    reminder_id: Option<i64>,
+    from: Option<&str>,
+    to: Option<&str>,
+) -> AppResult<Vec<ReminderEvent>> {
+    let conn = db()?;
+    query_events(&conn, reminder_id, from, to)
+}
+
+fn query_events(
+    conn: &Connection,
+    reminder_id: Option<i64>,
+    from: Option<&str>,
+    to: Option<&str>,
+) -> AppResult<Vec<ReminderEvent>> {
+    let from = from.map(|f| normalize_timestamp("from", f)).transpose()?;
+    let to = to.map(|t| normalize_timestamp("to", t)).transpose()?;
+    
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, reminder_id, kind, occurred_at, metadata FROM reminder_events
+             WHERE (?1 IS NULL OR reminder_id = ?1)
+               AND (?2 IS NULL OR occurred_at >= ?2)
+               AND (?3 IS NULL OR occurred_at <= ?3)
+             ORDER BY occurred_at, id",
+        )?;
+    
+    let rows = stmt
+        .query_map(params![reminder_id, from, to], |row| {
+            let kind: String = row.get(2)?;
+            let metadata: Option<String> = row.get(4)?;
+            Ok((row.get(0)?, row.get(1)?, kind, row.get(3)?, metadata))
+        })?
+        .collect::<Result<Vec<(i64, i64, String, String, Option<String>)>, _>>()?;
+    
+    // Rows with a kind this version doesn't know about are skipped
+    Ok(rows
+        .into_iter()
+        .filter_map(|(id, reminder_id, kind, occurred_at, metadata)| {
+            Some(ReminderEvent {
+                id,
+                reminder_id,
+                kind: EventKind::parse(&kind)?,
+                occurred_at,
+                metadata: metadata.and_then(|m| serde_json::from_str(&m).ok()),
+            })
+        })
+        .collect())
+}
+
+pub(super) fn delete_events_for_reminder(conn: &Connection, id: i64) -> AppResult<()> {
+    conn.execute("DELETE FROM reminder_events WHERE reminder_id = ?", params![id])?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::migrations::migrate;
+    use chrono::TimeZone;
+
+    fn database() -> Connection {
+        let mut conn = Connection::open_in_memory().unwrap();
+        migrate(&mut conn).unwrap();
+        conn
+    }
+
+    fn at(hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 2, hour, 0, 0).unwrap()
+    }
+
+    fn ids(events: &[ReminderEvent]) -> Vec<i64> {
+        events.iter().map(|e| e.id).collect()
+    }
+
+    #[test]
+    fn filters_by_reminder_and_inclusive_range() {
+        let conn = database();
+        let first = insert_event(&conn, 1, EventKind::Triggered, at(9), None).unwrap();
+        let second = insert_event(&conn, 2, EventKind::Triggered, at(10), None).unwrap();
+        let third = insert_event(&conn, 1, EventKind::Dismissed, at(11), None).unwrap();
+        // Recorded later but earlier in time
+        let early = insert_event(&conn, 1, EventKind::Missed, at(8), None).unwrap();
+
+        let all = query_events(&conn, None, None, None).unwrap();
+        assert_eq!(ids(&all), [early.id, first.id, second.id, third.id]);
+
+        let own = query_events(&conn, Some(1), None, None).unwrap();
+        assert_eq!(ids(&own), [early.id, first.id, third.id]);
+
+        let range = query_events(&conn, None, Some("2026-03-02T09:00:00Z"), Some("2026-03-02T10:00:00Z")).unwrap();
+        assert_eq!(ids(&range), [first.id, second.id]);
+
+        let both = query_events(&conn, Some(1), Some("2026-03-02T09:00:00Z"), None).unwrap();
+        assert_eq!(ids(&both), [first.id, third.id]);
+    }
+
+    #[test]
+    fn normalizes_timestamps() {
+        let conn = database();
+        let event = insert_event(&conn, 1, EventKind::Snoozed, at(9), Some(serde_json::json!({ "minutes": 5 }))).unwrap();
+        assert_eq!(event.occurred_at, "2026-03-02T09:00:00.000Z");
+
+        // The same instant with an offset and without milliseconds
+        let found = query_events(&conn, None, Some("2026-03-02T10:00:00+01:00"), Some("2026-03-02T10:00:00+01:00")).unwrap();
+        assert_eq!(ids(&found), [event.id]);
+        assert_eq!(found[0].metadata, Some(serde_json::json!({ "minutes": 5 })));
+
+        let error = query_events(&conn, None, Some("yesterday"), None).unwrap_err();
+        assert_eq!(error.field(), Some("from"));
+    }
+
+    #[test]
+    fn skips_unknown_kinds() {
+        let conn = database();
+        let known = insert_event(&conn, 1, EventKind::GameWon, at(9), None).unwrap();
+        conn.execute(
+            "INSERT INTO reminder_events (reminder_id, kind, occurred_at) VALUES (1, 'teleported', ?)",
+            params![format_timestamp(at(10))],
+        )
+        .unwrap();
+
+        let events = query_events(&conn, None, None, None).unwrap();
+        assert_eq!(ids(&events), [known.id]);
+    }
+}
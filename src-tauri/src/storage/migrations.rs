@@ -0,0 +1,330 @@
+use rusqlite::{params, Connection, Result as SqliteResult, Transaction};
+
+type Migration = fn(&Transaction) -> SqliteResult<()>;
+
+/// Schema migrations in the order they are applied. `PRAGMA user_version`
+/// stores how many of them a database has seen, so existing entries must
+/// never be edited or reordered - append a new one instead.
+///
+/// Databases from before versioning (user_version 0) may already contain
+/// some of these tables and columns, which is why the early migrations only
+/// create what is missing.
+const MIGRATIONS: &[Migration] = &[
+    create_reminders,
+    add_snoozing_and_settings,
+    add_schedule,
+    add_due_at,
+    create_reminder_events,
+    create_webhooks,
+    add_command,
+    add_delivery_style,
+    add_escalation,
+    add_pending_since,
+    add_catch_up,
+    add_timezone,
+];
+
+/// The schema version a fully migrated database has
+pub fn latest_version() -> u32 {
+    MIGRATIONS.len() as u32
+}
+
+pub fn schema_version(conn: &Connection) -> SqliteResult<u32> {
+    conn.query_row("PRAGMA user_version", [], |row| row.get(0))
+}
+
+/// Brings the database up to the latest schema. Every migration runs in its
+/// own transaction together with the version bump, so a failure leaves the
+/// database at the last version that fully applied.
+pub fn migrate(conn: &mut Connection) -> Result<(), String> {
+    let current = schema_version(conn).map_err(|e| e.to_string())?;
+
+    if current > latest_version() {
+        return Err(format!(
+            "The database has schema version {}, but this version of the app only supports up to {}. Please update the app.",
+            current,
+            latest_version()
+        ));
+    }
+
+    for (index, migration) in MIGRATIONS.iter().enumerate().skip(current as usize) {
+        let version = index as u32 + 1;
+        apply(conn, *migration, version)
+            .map_err(|e| format!("Database migration to version {} failed: {}", version, e))?;
+    }
+
+    Ok(())
+}
+
+fn apply(conn: &mut Connection, migration: Migration, version: u32) -> SqliteResult<()> {
+    let tx = conn.transaction()?;
+    migration(&tx)?;
+    tx.pragma_update(None, "user_version", version)?;
+    tx.commit()
+}
+
+fn has_column(tx: &Transaction, table: &str, column: &str) -> SqliteResult<bool> {
+    let mut stmt = tx.prepare("SELECT 1 FROM pragma_table_info(?) WHERE name = ?")?;
+    stmt.exists(params![table, column])
+}
+
+fn add_column(tx: &Transaction, table: &str, column: &str, definition: &str) -> SqliteResult<()> {
+    if !has_column(tx, table, column)? {
+        tx.execute(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition), [])?;
+    }
+    Ok(())
+}
+
+fn create_reminders(tx: &Transaction) -> SqliteResult<()> {
+    tx.execute(
+        "CREATE TABLE IF NOT EXISTS reminders (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            message TEXT,
+            interval_minutes INTEGER NOT NULL,
+            enabled INTEGER NOT NULL DEFAULT 1,
+            active_start_time TEXT,
+            active_end_time TEXT,
+            active_days TEXT,
+            sound TEXT DEFAULT 'chime',
+            last_triggered TEXT,
+            created_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+    // Tables created before the sound option existed
+    add_column(tx, "reminders", "sound", "TEXT DEFAULT 'chime'")
+}
+
+fn add_snoozing_and_settings(tx: &Transaction) -> SqliteResult<()> {
+    add_column(tx, "reminders", "snoozed_until", "TEXT")?;
+    add_column(tx, "reminders", "snooze_count", "INTEGER NOT NULL DEFAULT 0")?;
+    tx.execute(
+        "CREATE TABLE IF NOT EXISTS settings (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+fn add_schedule(tx: &Transaction) -> SqliteResult<()> {
+    add_column(tx, "reminders", "schedule", "TEXT")
+}
+
+fn add_due_at(tx: &Transaction) -> SqliteResult<()> {
+    add_column(tx, "reminders", "due_at", "TEXT")
+}
+
+fn create_reminder_events(tx: &Transaction) -> SqliteResult<()> {
+    tx.execute(
+        "CREATE TABLE IF NOT EXISTS reminder_events (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            reminder_id INTEGER NOT NULL,
+            kind TEXT NOT NULL,
+            occurred_at TEXT NOT NULL,
+            metadata TEXT
+        )",
+        [],
+    )?;
+    tx.execute(
+        "CREATE INDEX IF NOT EXISTS idx_reminder_events_reminder ON reminder_events (reminder_id, occurred_at)",
+        [],
+    )?;
+    Ok(())
+}
+
+fn create_webhooks(tx: &Transaction) -> SqliteResult<()> {
+    tx.execute(
+        "CREATE TABLE webhooks (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            reminder_id INTEGER,
+            url TEXT NOT NULL,
+            secret TEXT,
+            events TEXT NOT NULL,
+            enabled INTEGER NOT NULL DEFAULT 1,
+            created_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+    // Doubles as the delivery log, rows stay after they were delivered or
+    // gave up
+    tx.execute(
+        "CREATE TABLE webhook_deliveries (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            webhook_id INTEGER NOT NULL,
+            event_id INTEGER NOT NULL,
+            reminder_id INTEGER NOT NULL,
+            kind TEXT NOT NULL,
+            payload TEXT NOT NULL,
+            status TEXT NOT NULL,
+            attempts INTEGER NOT NULL DEFAULT 0,
+            next_attempt_at TEXT,
+            last_attempt_at TEXT,
+            response_status INTEGER,
+            last_error TEXT,
+            created_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+    tx.execute(
+        "CREATE INDEX idx_webhook_deliveries_pending ON webhook_deliveries (status, next_attempt_at)",
+        [],
+    )?;
+    Ok(())
+}
+
+fn add_command(tx: &Transaction) -> SqliteResult<()> {
+    add_column(tx, "reminders", "command", "TEXT")
+}
+
+fn add_delivery_style(tx: &Transaction) -> SqliteResult<()> {
+    add_column(tx, "reminders", "delivery_style", "TEXT")
+}
+
+fn add_escalation(tx: &Transaction) -> SqliteResult<()> {
+    add_column(tx, "reminders", "escalation", "TEXT")
+}
+
+fn add_pending_since(tx: &Transaction) -> SqliteResult<()> {
+    add_column(tx, "reminders", "pending_since", "TEXT")
+}
+
+fn add_catch_up(tx: &Transaction) -> SqliteResult<()> {
+    add_column(tx, "reminders", "catch_up", "TEXT")
+}
+
+fn add_timezone(tx: &Transaction) -> SqliteResult<()> {
+    add_column(tx, "reminders", "timezone", "TEXT")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The schema as created by the first release, before the sound option
+    /// and before any versioning
+    const V0_SCHEMA: &str = "
+        CREATE TABLE reminders (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            message TEXT,
+            interval_minutes INTEGER NOT NULL,
+            enabled INTEGER NOT NULL DEFAULT 1,
+            active_start_time TEXT,
+            active_end_time TEXT,
+            active_days TEXT,
+            last_triggered TEXT,
+            created_at TEXT NOT NULL
+        );
+        INSERT INTO reminders (name, message, interval_minutes, enabled, active_days, created_at)
+        VALUES ('Drink water', 'Stay hydrated', 45, 1, '[0,1,2,3,4]', '2025-01-01T09:00:00+00:00');
+    ";
+
+    fn columns(conn: &Connection, table: &str) -> Vec<String> {
+        let mut stmt = conn
+            .prepare("SELECT name FROM pragma_table_info(?)")
+            .unwrap();
+        stmt.query_map([table], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn migrates_v0_database_to_latest() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(V0_SCHEMA).unwrap();
+        assert_eq!(schema_version(&conn).unwrap(), 0);
+
+        migrate(&mut conn).unwrap();
+
+        assert_eq!(schema_version(&conn).unwrap(), latest_version());
+        let reminder_columns = columns(&conn, "reminders");
+        for column in ["sound", "snoozed_until", "snooze_count", "schedule", "due_at", "command", "delivery_style", "escalation", "pending_since", "catch_up", "timezone"] {
+            assert!(reminder_columns.iter().any(|c| c == column), "missing column {}", column);
+        }
+        assert!(!columns(&conn, "settings").is_empty());
+        assert!(!columns(&conn, "reminder_events").is_empty());
+        assert!(!columns(&conn, "webhooks").is_empty());
+        assert!(!columns(&conn, "webhook_deliveries").is_empty());
+
+        // Existing rows survive and pick up the column defaults
+        let (name, sound, snooze_count): (String, String, i32) = conn
+            .query_row("SELECT name, sound, snooze_count FROM reminders", [], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+            })
+            .unwrap();
+        assert_eq!(name, "Drink water");
+        assert_eq!(sound, "chime");
+        assert_eq!(snooze_count, 0);
+    }
+
+    #[test]
+    fn migrates_unversioned_database_that_already_has_columns() {
+        // Databases written by the old ad-hoc migrations have the sound column
+        // (and possibly more) while still reporting version 0
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(V0_SCHEMA).unwrap();
+        conn.execute_batch("ALTER TABLE reminders ADD COLUMN sound TEXT DEFAULT 'chime'; ALTER TABLE reminders ADD COLUMN due_at TEXT;")
+            .unwrap();
+
+        migrate(&mut conn).unwrap();
+
+        assert_eq!(schema_version(&conn).unwrap(), latest_version());
+    }
+
+    #[test]
+    fn migrates_unversioned_database_with_snoozes() {
+        // Written by the release that added snoozing through unchecked ALTERs
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(V0_SCHEMA).unwrap();
+        conn.execute_batch(
+            "ALTER TABLE reminders ADD COLUMN sound TEXT DEFAULT 'chime';
+             ALTER TABLE reminders ADD COLUMN snoozed_until TEXT;
+             ALTER TABLE reminders ADD COLUMN snooze_count INTEGER NOT NULL DEFAULT 0;
+             CREATE TABLE settings (key TEXT PRIMARY KEY, value TEXT NOT NULL);
+             INSERT INTO settings (key, value) VALUES ('max_snooze_count', '5');
+             UPDATE reminders SET snoozed_until = '2025-01-01T10:00:00+00:00', snooze_count = 2;",
+        )
+        .unwrap();
+
+        migrate(&mut conn).unwrap();
+
+        assert_eq!(schema_version(&conn).unwrap(), latest_version());
+        let (snoozed_until, snooze_count): (String, i32) = conn
+            .query_row("SELECT snoozed_until, snooze_count FROM reminders", [], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .unwrap();
+        assert_eq!(snoozed_until, "2025-01-01T10:00:00+00:00");
+        assert_eq!(snooze_count, 2);
+        let max: String = conn
+            .query_row("SELECT value FROM settings WHERE key = 'max_snooze_count'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(max, "5");
+    }
+
+    #[test]
+    fn creates_empty_database_and_is_idempotent() {
+        let mut conn = Connection::open_in_memory().unwrap();
+
+        migrate(&mut conn).unwrap();
+        migrate(&mut conn).unwrap();
+
+        assert_eq!(schema_version(&conn).unwrap(), latest_version());
+        let count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM reminders", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 0);
+    }
+
+    #[test]
+    fn rejects_database_from_newer_version() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.pragma_update(None, "user_version", latest_version() + 1).unwrap();
+
+        assert!(migrate(&mut conn).is_err());
+    }
+}
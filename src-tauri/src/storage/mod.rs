@@ -1,3 +1,12 @@
 mod db;
+mod events;
+mod migrations;
+mod settings;
+mod validation;
+mod webhooks;
 
 pub use db::*;
+pub use events::*;
+pub use settings::*;
+pub use validation::*;
+pub use webhooks::*;
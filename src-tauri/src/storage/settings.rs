@@ -0,0 +1,201 @@
+use super::db::db;
+use crate::error::{AppError, AppResult};
+use crate::reminder::parse_timezone;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+/// What happens to reminders that came due while everything was paused
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PausePolicy {
+    /// Skip them and continue with the next regular occurrence
+    #[default]
+    Drop,
+    /// Fire each of them once as soon as the pause ends
+    FireOnResume,
+}
+
+impl PausePolicy {
+    fn as_str(&self) -> &'static str {
+        match self {
+            PausePolicy::Drop => "drop",
+            PausePolicy::FireOnResume => "fire_on_resume",
+        }
+    }
+    
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "drop" => Some(PausePolicy::Drop),
+            "fire_on_resume" => Some(PausePolicy::FireOnResume),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// How many times a reminder may be snoozed before it has to be dismissed.
+    /// `None` allows unlimited snoozing.
+    pub max_snooze_count: Option<i32>,
+    pub pause_policy: PausePolicy,
+    /// Serve the HTTP API on 127.0.0.1
+    pub api_enabled: bool,
+    pub api_port: u16,
+    /// IANA zone for reminders without their own, e.g. "Europe/Berlin".
+    /// `None` follows the system.
+    pub default_timezone: Option<String>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            max_snooze_count: Some(3),
+            pause_policy: PausePolicy::default(),
+            api_enabled: false,
+            api_port: 7878,
+            default_timezone: None,
+        }
+    }
+}
+
+impl Settings {
+    /// Rejects invalid settings and spells the default zone the way the time
+    /// zone database does, with an empty one meaning the system's
+    pub fn validate(&mut self) -> AppResult<()> {
+        if let Some(max) = self.max_snooze_count {
+            if max < 0 {
+                return Err(AppError::validation("max_snooze_count", "Max snooze count cannot be negative"));
+            }
+        }
+        
+        if self.api_port < 1024 {
+            return Err(AppError::validation("api_port", "Port must be between 1024 and 65535"));
+        }
+        
+        self.default_timezone = match self.default_timezone.as_deref().map(str::trim) {
+            Some(name) if !name.is_empty() => Some(parse_timezone("default_timezone", name)?.name().to_string()),
+            _ => None,
+        };
+        Ok(())
+    }
+}
+
+/// Global "Pause All" state, owned by the scheduler
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PauseState {
+    pub paused: bool,
+    /// RFC 3339 timestamp at which the pause ends, `None` while paused indefinitely
+    pub until: Option<String>,
+}
+
+pub fn get_setting(key: &str) -> AppResult<Option<String>> {
+    let conn = db()?;
+    let value = conn.query_row(
+        "SELECT value FROM settings WHERE key = ?",
+        params![key],
+        |row| row.get(0),
+    )
+    .optional()?;
+    Ok(value)
+}
+
+pub fn set_setting(key: &str, value: &str) -> AppResult<()> {
+    let conn = db()?;
+    write_setting(&conn, key, value)
+}
+
+fn write_setting(conn: &Connection, key: &str, value: &str) -> AppResult<()> {
+    conn.execute(
+        "INSERT INTO settings (key, value) VALUES (?, ?) ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+        params![key, value],
+    )?;
+    Ok(())
+}
+
+pub fn get_settings() -> AppResult<Settings> {
+    let defaults = Settings::default();
+    
+    let max_snooze_count = match get_setting("max_snooze_count")? {
+        // An empty value is stored for "unlimited"
+        Some(v) if v.is_empty() => None,
+        Some(v) => v.parse().ok().or(defaults.max_snooze_count),
+        None => defaults.max_snooze_count,
+    };
+    
+    let pause_policy = get_setting("pause_policy")?
+        .and_then(|v| PausePolicy::parse(&v))
+        .unwrap_or(defaults.pause_policy);
+    
+    let api_enabled = get_setting("api_enabled")?.map_or(defaults.api_enabled, |v| v == "1");
+    let api_port = get_setting("api_port")?
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(defaults.api_port);
+    // An empty value is stored for "follow the system"
+    let default_timezone = get_setting("default_timezone")?.filter(|v| !v.is_empty());
+    
+    Ok(Settings {
+        max_snooze_count,
+        pause_policy,
+        api_enabled,
+        api_port,
+        default_timezone,
+    })
+}
+
+pub fn update_settings(settings: Settings) -> AppResult<Settings> {
+    let conn = db()?;
+    store_settings(&conn, settings)?;
+    drop(conn);
+    
+    get_settings()
+}
+
+/// Validates and writes the settings with `conn`, which may be in a transaction
+pub(super) fn store_settings(conn: &Connection, mut settings: Settings) -> AppResult<()> {
+    settings.validate()?;
+    
+    let default_timezone = settings.default_timezone.unwrap_or_default();
+    let max_snooze_count = settings.max_snooze_count.map(|m| m.to_string()).unwrap_or_default();
+    write_setting(conn, "max_snooze_count", &max_snooze_count)?;
+    write_setting(conn, "pause_policy", settings.pause_policy.as_str())?;
+    write_setting(conn, "api_enabled", if settings.api_enabled { "1" } else { "0" })?;
+    write_setting(conn, "api_port", &settings.api_port.to_string())?;
+    write_setting(conn, "default_timezone", &default_timezone)?;
+    Ok(())
+}
+
+/// Secret the HTTP API expects as bearer token, created on first use. It is
+/// not part of `Settings` so it never ends up in an export.
+pub fn get_api_token() -> AppResult<String> {
+    match get_setting("api_token")? {
+        Some(token) if !token.is_empty() => Ok(token),
+        _ => regenerate_api_token(),
+    }
+}
+
+/// Replaces the API token, locking out every client using the old one
+pub fn regenerate_api_token() -> AppResult<String> {
+    let mut bytes = [0u8; 32];
+    getrandom::getrandom(&mut bytes)
+        .map_err(|e| AppError::Io(format!("Failed to generate an API token: {}", e)))?;
+    let token: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    set_setting("api_token", &token)?;
+    Ok(token)
+}
+
+pub fn get_pause_state() -> AppResult<PauseState> {
+    let paused = get_setting("paused")?.is_some_and(|v| v == "1");
+    let until = get_setting("paused_until")?.filter(|v| !v.is_empty());
+    
+    Ok(PauseState {
+        paused,
+        until: if paused { until } else { None },
+    })
+}
+
+pub fn set_pause_state(state: &PauseState) -> AppResult<()> {
+    set_setting("paused", if state.paused { "1" } else { "0" })?;
+    set_setting("paused_until", state.until.as_deref().unwrap_or(""))?;
+    Ok(())
+}
@@ -0,0 +1,397 @@
+use super::db::{
+    CatchUpPolicy, CreateReminder, DeliveryStyle, EscalationAction, EscalationStep, ReminderCommand, Schedule,
+    UpdateReminder,
+};
+use crate::error::{AppError, AppResult};
+use crate::reminder::{parse_timezone, validate_schedule};
+use chrono::{DateTime, NaiveTime, SecondsFormat, Utc};
+
+/// Sounds the frontend knows how to play
+pub const SOUNDS: [&str; 6] = ["none", "chime", "bell", "ping", "alert", "gong"];
+
+const MAX_NAME_LENGTH: usize = 200;
+const MAX_COMMAND_TIMEOUT_SECONDS: u32 = 600;
+const MAX_ESCALATION_MINUTES: u32 = 24 * 60;
+const MAX_ESCALATION_STEPS: usize = 10;
+const MAX_CATCH_UP_OCCURRENCES: u32 = 50;
+
+/// The user-editable fields shared by `CreateReminder` and `UpdateReminder`
+struct Fields<'a> {
+    name: &'a mut String,
+    message: &'a mut Option<String>,
+    interval_minutes: i32,
+    schedule: &'a Schedule,
+    due_at: &'a mut Option<String>,
+    active_start_time: &'a mut Option<String>,
+    active_end_time: &'a mut Option<String>,
+    active_days: &'a mut Option<Vec<u8>>,
+    sound: &'a mut String,
+    command: &'a mut Option<ReminderCommand>,
+    delivery_style: &'a DeliveryStyle,
+    escalation: &'a mut Vec<EscalationStep>,
+    catch_up: &'a CatchUpPolicy,
+    timezone: &'a mut Option<String>,
+}
+
+impl CreateReminder {
+    /// Rejects invalid input and normalizes the rest in place, see `normalize`
+    pub fn validate(&mut self) -> AppResult<()> {
+        normalize(Fields {
+            name: &mut self.name,
+            message: &mut self.message,
+            interval_minutes: self.interval_minutes,
+            schedule: &self.schedule,
+            due_at: &mut self.due_at,
+            active_start_time: &mut self.active_start_time,
+            active_end_time: &mut self.active_end_time,
+            active_days: &mut self.active_days,
+            sound: &mut self.sound,
+            command: &mut self.command,
+            delivery_style: &self.delivery_style,
+            escalation: &mut self.escalation,
+            catch_up: &self.catch_up,
+            timezone: &mut self.timezone,
+        })
+    }
+}
+
+impl UpdateReminder {
+    /// Rejects invalid input and normalizes the rest in place, see `normalize`
+    pub fn validate(&mut self) -> AppResult<()> {
+        normalize(Fields {
+            name: &mut self.name,
+            message: &mut self.message,
+            interval_minutes: self.interval_minutes,
+            schedule: &self.schedule,
+            due_at: &mut self.due_at,
+            active_start_time: &mut self.active_start_time,
+            active_end_time: &mut self.active_end_time,
+            active_days: &mut self.active_days,
+            sound: &mut self.sound,
+            command: &mut self.command,
+            delivery_style: &self.delivery_style,
+            escalation: &mut self.escalation,
+            catch_up: &self.catch_up,
+            timezone: &mut self.timezone,
+        })
+    }
+}
+
+/// Trims text, drops an empty message, writes times as HH:MM and timestamps
+/// as UTC, sorts and de-duplicates the active days, drops a command
+/// without a program, sorts the escalation steps and spells time zones the
+/// way the time zone database does
+fn normalize(fields: Fields) -> AppResult<()> {
+    *fields.name = fields.name.trim().to_string();
+    if fields.name.is_empty() {
+        return Err(AppError::validation("name", "Name is required"));
+    }
+    if fields.name.chars().count() > MAX_NAME_LENGTH {
+        return Err(AppError::validation(
+            "name",
+            format!("Name cannot be longer than {} characters", MAX_NAME_LENGTH),
+        ));
+    }
+
+    *fields.message = fields
+        .message
+        .as_deref()
+        .map(str::trim)
+        .filter(|m| !m.is_empty())
+        .map(str::to_string);
+
+    // Only repeating interval reminders use the interval, but a negative one
+    // is never meaningful
+    let uses_interval = fields.due_at.is_none() && *fields.schedule == Schedule::Interval;
+    if fields.interval_minutes < 0 || (uses_interval && fields.interval_minutes == 0) {
+        return Err(AppError::validation("interval_minutes", "Interval must be at least 1 minute"));
+    }
+
+    validate_schedule(fields.schedule)?;
+
+    if let Some(due_at) = fields.due_at.as_mut() {
+        let parsed = DateTime::parse_from_rfc3339(due_at.trim()).map_err(|_| {
+            AppError::validation("due_at", format!("Invalid date and time '{}'", due_at))
+        })?;
+        // Same format as the timestamps written by storage, so an unchanged
+        // due date compares equal and doesn't re-arm the reminder
+        *due_at = parsed.with_timezone(&Utc).to_rfc3339_opts(SecondsFormat::AutoSi, false);
+    }
+
+    match (fields.active_start_time.as_deref(), fields.active_end_time.as_deref()) {
+        (None, None) => {}
+        (Some(_), None) => {
+            return Err(AppError::validation("active_end_time", "End time is required"));
+        }
+        (None, Some(_)) => {
+            return Err(AppError::validation("active_start_time", "Start time is required"));
+        }
+        (Some(start), Some(end)) => {
+            let start = parse_time("active_start_time", start)?;
+            let end = parse_time("active_end_time", end)?;
+            if start == end {
+                return Err(AppError::validation(
+                    "active_end_time",
+                    "End time must differ from the start time",
+                ));
+            }
+            *fields.active_start_time = Some(start.format("%H:%M").to_string());
+            *fields.active_end_time = Some(end.format("%H:%M").to_string());
+        }
+    }
+
+    if let Some(days) = fields.active_days.as_mut() {
+        if let Some(day) = days.iter().find(|d| **d > 6) {
+            return Err(AppError::validation(
+                "active_days",
+                format!("Invalid day {}, expected 0 (Monday) to 6 (Sunday)", day),
+            ));
+        }
+        if days.is_empty() {
+            return Err(AppError::validation("active_days", "Select at least one day"));
+        }
+        days.sort_unstable();
+        days.dedup();
+    }
+
+    *fields.sound = fields.sound.trim().to_lowercase();
+    if !SOUNDS.contains(&fields.sound.as_str()) {
+        return Err(AppError::validation(
+            "sound",
+            format!("Unknown sound '{}', expected one of {}", fields.sound, SOUNDS.join(", ")),
+        ));
+    }
+
+    if let Some(command) = fields.command.as_mut() {
+        command.program = command.program.trim().to_string();
+    }
+    // Clearing the program removes the command
+    if fields.command.as_ref().is_some_and(|c| c.program.is_empty()) {
+        *fields.command = None;
+    }
+    if let Some(command) = fields.command.as_ref() {
+        if command.timeout_seconds == 0 || command.timeout_seconds > MAX_COMMAND_TIMEOUT_SECONDS {
+            return Err(AppError::validation(
+                "command",
+                format!("Command timeout must be between 1 and {} seconds", MAX_COMMAND_TIMEOUT_SECONDS),
+            ));
+        }
+    }
+
+    if let DeliveryStyle::NotificationThenPopup { minutes } = fields.delivery_style {
+        if *minutes == 0 || *minutes > MAX_ESCALATION_MINUTES {
+            return Err(AppError::validation(
+                "delivery_style",
+                format!("The popup must follow within 1 to {} minutes", MAX_ESCALATION_MINUTES),
+            ));
+        }
+    }
+
+    if fields.escalation.len() > MAX_ESCALATION_STEPS {
+        return Err(AppError::validation(
+            "escalation",
+            format!("At most {} escalation steps are allowed", MAX_ESCALATION_STEPS),
+        ));
+    }
+    for step in fields.escalation.iter() {
+        if step.after_minutes == 0 || step.after_minutes > MAX_ESCALATION_MINUTES {
+            return Err(AppError::validation(
+                "escalation",
+                format!("Escalation steps must come within 1 to {} minutes", MAX_ESCALATION_MINUTES),
+            ));
+        }
+        if let EscalationAction::ChangeSound { sound } = &step.action {
+            if sound == "none" || !SOUNDS.contains(&sound.as_str()) {
+                return Err(AppError::validation(
+                    "escalation",
+                    format!("Unknown sound '{}' in escalation step", sound),
+                ));
+            }
+        }
+    }
+    // Stable, so steps at the same minute keep their order
+    fields.escalation.sort_by_key(|step| step.after_minutes);
+
+    if let CatchUpPolicy::FireEach { max } = fields.catch_up {
+        if *max == 0 || *max > MAX_CATCH_UP_OCCURRENCES {
+            return Err(AppError::validation(
+                "catch_up",
+                format!("Between 1 and {} missed occurrences can be caught up", MAX_CATCH_UP_OCCURRENCES),
+            ));
+        }
+    }
+
+    *fields.timezone = match fields.timezone.as_deref().map(str::trim) {
+        None | Some("") => None,
+        Some(name) => Some(parse_timezone("timezone", name)?.name().to_string()),
+    };
+
+    Ok(())
+}
+
+fn parse_time(field: &str, value: &str) -> AppResult<NaiveTime> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M")
+        .map_err(|_| AppError::validation(field, format!("Invalid time '{}', expected HH:MM", value)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn valid() -> CreateReminder {
+        CreateReminder {
+            name: "Stretch".to_string(),
+            message: None,
+            interval_minutes: 30,
+            schedule: Schedule::Interval,
+            due_at: None,
+            enabled: true,
+            active_start_time: None,
+            active_end_time: None,
+            active_days: None,
+            sound: "chime".to_string(),
+            command: None,
+            delivery_style: DeliveryStyle::Popup,
+            escalation: Vec::new(),
+            catch_up: CatchUpPolicy::Skip,
+            timezone: None,
+        }
+    }
+
+    fn command(program: &str, timeout_seconds: u32) -> Option<ReminderCommand> {
+        Some(ReminderCommand {
+            program: program.to_string(),
+            args: Vec::new(),
+            timeout_seconds,
+            enabled: true,
+        })
+    }
+
+    fn step(after_minutes: u32, action: EscalationAction) -> EscalationStep {
+        EscalationStep { after_minutes, action }
+    }
+
+    /// Breaks an otherwise valid reminder
+    type Change = fn(&mut CreateReminder);
+
+    #[test]
+    fn rejects_invalid_fields() {
+        let cases: Vec<(&str, Change)> = vec![
+            ("name", |r| r.name = "   ".to_string()),
+            ("name", |r| r.name = "x".repeat(MAX_NAME_LENGTH + 1)),
+            ("interval_minutes", |r| r.interval_minutes = 0),
+            ("interval_minutes", |r| {
+                r.interval_minutes = -5;
+                r.schedule = Schedule::Cron { expression: "0 9 * * *".to_string() };
+            }),
+            ("schedule", |r| r.schedule = Schedule::DailyTimes { times: Vec::new() }),
+            ("schedule", |r| r.schedule = Schedule::DailyTimes { times: vec!["25:00".to_string()] }),
+            ("schedule", |r| r.schedule = Schedule::Cron { expression: "every morning".to_string() }),
+            ("schedule", |r| r.schedule = Schedule::Rrule { rule: "FREQ=SOMETIMES".to_string() }),
+            ("due_at", |r| r.due_at = Some("tomorrow".to_string())),
+            ("active_end_time", |r| r.active_start_time = Some("09:00".to_string())),
+            ("active_start_time", |r| r.active_end_time = Some("17:00".to_string())),
+            ("active_start_time", |r| {
+                r.active_start_time = Some("9am".to_string());
+                r.active_end_time = Some("17:00".to_string());
+            }),
+            ("active_end_time", |r| {
+                r.active_start_time = Some("09:00".to_string());
+                r.active_end_time = Some("09:00".to_string());
+            }),
+            ("active_days", |r| r.active_days = Some(Vec::new())),
+            ("active_days", |r| r.active_days = Some(vec![1, 7])),
+            ("sound", |r| r.sound = "kazoo".to_string()),
+            ("command", |r| r.command = command("notify-send", 0)),
+            ("command", |r| r.command = command("notify-send", MAX_COMMAND_TIMEOUT_SECONDS + 1)),
+            ("delivery_style", |r| r.delivery_style = DeliveryStyle::NotificationThenPopup { minutes: 0 }),
+            ("delivery_style", |r| {
+                r.delivery_style = DeliveryStyle::NotificationThenPopup { minutes: MAX_ESCALATION_MINUTES + 1 }
+            }),
+            ("escalation", |r| r.escalation = vec![step(0, EscalationAction::Refocus)]),
+            ("escalation", |r| r.escalation = vec![step(MAX_ESCALATION_MINUTES + 1, EscalationAction::Refocus)]),
+            ("escalation", |r| r.escalation = vec![step(5, EscalationAction::ReplaySound); MAX_ESCALATION_STEPS + 1]),
+            ("escalation", |r| {
+                r.escalation = vec![step(5, EscalationAction::ChangeSound { sound: "none".to_string() })]
+            }),
+            ("catch_up", |r| r.catch_up = CatchUpPolicy::FireEach { max: 0 }),
+            ("catch_up", |r| r.catch_up = CatchUpPolicy::FireEach { max: MAX_CATCH_UP_OCCURRENCES + 1 }),
+            ("timezone", |r| r.timezone = Some("Mars/Olympus".to_string())),
+        ];
+
+        for (index, (field, change)) in cases.into_iter().enumerate() {
+            let mut reminder = valid();
+            change(&mut reminder);
+            let error = reminder.validate().expect_err(&format!("case {} is rejected", index));
+            assert_eq!(error.field(), Some(field), "case {}: {}", index, error);
+        }
+    }
+
+    #[test]
+    fn normalizes_accepted_input() {
+        let mut reminder = valid();
+        reminder.name = "  Stretch  ".to_string();
+        reminder.message = Some(" \n ".to_string());
+        reminder.due_at = Some(" 2026-03-10T10:00:00+01:00 ".to_string());
+        reminder.interval_minutes = 0;
+        reminder.active_start_time = Some(" 22:00".to_string());
+        reminder.active_end_time = Some("06:00 ".to_string());
+        reminder.active_days = Some(vec![4, 0, 4]);
+        reminder.sound = " Bell ".to_string();
+        reminder.command = command("  ", 0);
+        reminder.escalation = vec![
+            step(10, EscalationAction::Refocus),
+            step(5, EscalationAction::ReplaySound),
+            step(10, EscalationAction::Webhook),
+        ];
+        reminder.timezone = Some(" europe/berlin ".to_string());
+
+        reminder.validate().unwrap();
+
+        assert_eq!(reminder.name, "Stretch");
+        assert_eq!(reminder.message, None);
+        assert_eq!(reminder.due_at.as_deref(), Some("2026-03-10T09:00:00+00:00"));
+        assert_eq!(reminder.active_start_time.as_deref(), Some("22:00"));
+        assert_eq!(reminder.active_end_time.as_deref(), Some("06:00"));
+        assert_eq!(reminder.active_days, Some(vec![0, 4]));
+        assert_eq!(reminder.sound, "bell");
+        assert_eq!(reminder.command, None);
+        let order: Vec<(u32, EscalationAction)> =
+            reminder.escalation.into_iter().map(|s| (s.after_minutes, s.action)).collect();
+        assert_eq!(
+            order,
+            [
+                (5, EscalationAction::ReplaySound),
+                (10, EscalationAction::Refocus),
+                (10, EscalationAction::Webhook),
+            ]
+        );
+        assert_eq!(reminder.timezone.as_deref(), Some("Europe/Berlin"));
+    }
+
+    #[test]
+    fn empty_optional_text_becomes_none() {
+        let mut reminder = valid();
+        reminder.message = Some("  Drink water  ".to_string());
+        reminder.timezone = Some("   ".to_string());
+
+        reminder.validate().unwrap();
+
+        assert_eq!(reminder.message.as_deref(), Some("Drink water"));
+        assert_eq!(reminder.timezone, None);
+    }
+
+    #[test]
+    fn updates_are_normalized_the_same_way() {
+        let mut create = valid();
+        create.name = " Stretch ".to_string();
+        create.sound = "GONG".to_string();
+        let mut update = UpdateReminder::new(7, create);
+
+        update.validate().unwrap();
+
+        assert_eq!((update.id, update.name.as_str(), update.sound.as_str()), (7, "Stretch", "gong"));
+        update.interval_minutes = 0;
+        assert_eq!(update.validate().unwrap_err().field(), Some("interval_minutes"));
+    }
+}
@@ -0,0 +1,390 @@
+use super::db::db;
+use super::events::{EventKind, ReminderEvent};
+use crate::error::{AppError, AppResult};
+use chrono::{DateTime, SecondsFormat, Utc};
+use rusqlite::{params, Connection, OptionalExtension, Result as SqliteResult};
+use serde::{Deserialize, Serialize};
+
+/// A URL that gets a POST for every matching reminder event. Global webhooks
+/// (`reminder_id` is `None`) fire for every reminder.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Webhook {
+    pub id: i64,
+    pub reminder_id: Option<i64>,
+    pub url: String,
+    /// Key for the `X-Multitask-Signature` HMAC, unsigned without one
+    pub secret: Option<String>,
+    pub events: Vec<EventKind>,
+    pub enabled: bool,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateWebhook {
+    #[serde(default)]
+    pub reminder_id: Option<i64>,
+    pub url: String,
+    #[serde(default)]
+    pub secret: Option<String>,
+    #[serde(default = "default_webhook_events")]
+    pub events: Vec<EventKind>,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateWebhook {
+    pub id: i64,
+    #[serde(flatten)]
+    pub webhook: CreateWebhook,
+}
+
+fn default_webhook_events() -> Vec<EventKind> {
+    vec![EventKind::Triggered, EventKind::Dismissed, EventKind::Missed]
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeliveryStatus {
+    Pending,
+    Delivered,
+    /// Gave up after too many attempts or a permanent error
+    Failed,
+}
+
+impl DeliveryStatus {
+    fn as_str(&self) -> &'static str {
+        match self {
+            DeliveryStatus::Pending => "pending",
+            DeliveryStatus::Delivered => "delivered",
+            DeliveryStatus::Failed => "failed",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "pending" => Some(DeliveryStatus::Pending),
+            "delivered" => Some(DeliveryStatus::Delivered),
+            "failed" => Some(DeliveryStatus::Failed),
+            _ => None,
+        }
+    }
+}
+
+/// One event for one webhook, with the outcome of the latest attempt
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookDelivery {
+    pub id: i64,
+    pub webhook_id: i64,
+    pub event_id: i64,
+    pub reminder_id: i64,
+    pub kind: EventKind,
+    pub status: DeliveryStatus,
+    pub attempts: u32,
+    pub next_attempt_at: Option<String>,
+    pub last_attempt_at: Option<String>,
+    pub response_status: Option<u16>,
+    pub last_error: Option<String>,
+    pub created_at: String,
+}
+
+/// A pending delivery together with what is needed to send it
+#[derive(Debug, Clone)]
+pub struct QueuedDelivery {
+    pub delivery: WebhookDelivery,
+    pub url: String,
+    pub secret: Option<String>,
+    /// The JSON body, fixed when the event happened so retries send the same bytes
+    pub payload: String,
+}
+
+const WEBHOOK_COLUMNS: &str = "id, reminder_id, url, secret, events, enabled, created_at";
+const DELIVERY_COLUMNS: &str = "d.id, d.webhook_id, d.event_id, d.reminder_id, d.kind, d.status, d.attempts, d.next_attempt_at, d.last_attempt_at, d.response_status, d.last_error, d.created_at";
+
+fn timestamp(t: DateTime<Utc>) -> String {
+    t.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+fn row_to_webhook(row: &rusqlite::Row) -> SqliteResult<Webhook> {
+    let events: String = row.get(4)?;
+    Ok(Webhook {
+        id: row.get(0)?,
+        reminder_id: row.get(1)?,
+        url: row.get(2)?,
+        secret: row.get(3)?,
+        events: serde_json::from_str(&events).unwrap_or_default(),
+        enabled: row.get::<_, i32>(5)? != 0,
+        created_at: row.get(6)?,
+    })
+}
+
+/// `None` for rows with a kind or status this version doesn't know about
+fn row_to_delivery(row: &rusqlite::Row) -> SqliteResult<Option<WebhookDelivery>> {
+    let kind: String = row.get(4)?;
+    let status: String = row.get(5)?;
+    let (Some(kind), Some(status)) = (EventKind::parse(&kind), DeliveryStatus::parse(&status)) else {
+        return Ok(None);
+    };
+    Ok(Some(WebhookDelivery {
+        id: row.get(0)?,
+        webhook_id: row.get(1)?,
+        event_id: row.get(2)?,
+        reminder_id: row.get(3)?,
+        kind,
+        status,
+        attempts: row.get(6)?,
+        next_attempt_at: row.get(7)?,
+        last_attempt_at: row.get(8)?,
+        response_status: row.get(9)?,
+        last_error: row.get(10)?,
+        created_at: row.get(11)?,
+    }))
+}
+
+fn webhook_not_found(id: i64) -> AppError {
+    AppError::NotFound(format!("Webhook {}", id))
+}
+
+impl CreateWebhook {
+    pub fn validate(&mut self) -> AppResult<()> {
+        self.url = self.url.trim().to_string();
+        let rest = self
+            .url
+            .strip_prefix("http://")
+            .or_else(|| self.url.strip_prefix("https://"))
+            .ok_or_else(|| AppError::validation("url", "URL must start with http:// or https://"))?;
+        if rest.is_empty() || rest.starts_with('/') || rest.contains(char::is_whitespace) {
+            return Err(AppError::validation("url", format!("Invalid URL '{}'", self.url)));
+        }
+
+        self.secret = self.secret.take().filter(|s| !s.trim().is_empty());
+
+        if self.events.is_empty() {
+            return Err(AppError::validation("events", "Select at least one event"));
+        }
+        self.events.sort_by_key(|e| e.as_str());
+        self.events.dedup();
+        Ok(())
+    }
+}
+
+/// All webhooks, or the ones of one reminder (not including the global ones)
+pub fn get_webhooks(reminder_id: Option<i64>) -> AppResult<Vec<Webhook>> {
+    let conn = db()?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM webhooks WHERE (?1 IS NULL OR reminder_id = ?1) ORDER BY id",
+        WEBHOOK_COLUMNS
+    ))?;
+    let webhooks = stmt
+        .query_map(params![reminder_id], row_to_webhook)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(webhooks)
+}
+
+fn get_webhook(conn: &Connection, id: i64) -> AppResult<Webhook> {
+    conn.query_row(
+        &format!("SELECT {} FROM webhooks WHERE id = ?", WEBHOOK_COLUMNS),
+        params![id],
+        row_to_webhook,
+    )
+    .optional()?
+    .ok_or_else(|| webhook_not_found(id))
+}
+
+pub fn create_webhook(mut webhook: CreateWebhook) -> AppResult<Webhook> {
+    webhook.validate()?;
+    let conn = db()?;
+    if let Some(reminder_id) = webhook.reminder_id {
+        let exists = conn
+            .prepare("SELECT 1 FROM reminders WHERE id = ?")?
+            .exists(params![reminder_id])?;
+        if !exists {
+            return Err(AppError::NotFound(format!("Reminder {}", reminder_id)));
+        }
+    }
+
+    conn.execute(
+        "INSERT INTO webhooks (reminder_id, url, secret, events, enabled, created_at) VALUES (?, ?, ?, ?, ?, ?)",
+        params![
+            webhook.reminder_id,
+            webhook.url,
+            webhook.secret,
+            serde_json::to_string(&webhook.events).unwrap(),
+            webhook.enabled as i32,
+            Utc::now().to_rfc3339(),
+        ],
+    )?;
+    get_webhook(&conn, conn.last_insert_rowid())
+}
+
+/// The reminder a webhook belongs to can't be changed
+pub fn update_webhook(update: UpdateWebhook) -> AppResult<Webhook> {
+    let UpdateWebhook { id, mut webhook } = update;
+    webhook.validate()?;
+    let conn = db()?;
+    let changed = conn.execute(
+        "UPDATE webhooks SET url = ?, secret = ?, events = ?, enabled = ? WHERE id = ?",
+        params![
+            webhook.url,
+            webhook.secret,
+            serde_json::to_string(&webhook.events).unwrap(),
+            webhook.enabled as i32,
+            id,
+        ],
+    )?;
+    if changed == 0 {
+        return Err(webhook_not_found(id));
+    }
+    get_webhook(&conn, id)
+}
+
+/// Deletes the webhook and its delivery log
+pub fn delete_webhook(id: i64) -> AppResult<()> {
+    let conn = db()?;
+    conn.execute("DELETE FROM webhook_deliveries WHERE webhook_id = ?", params![id])?;
+    conn.execute("DELETE FROM webhooks WHERE id = ?", params![id])?;
+    Ok(())
+}
+
+pub(super) fn delete_webhooks_for_reminder(conn: &Connection, reminder_id: i64) -> AppResult<()> {
+    conn.execute(
+        "DELETE FROM webhook_deliveries WHERE webhook_id IN (SELECT id FROM webhooks WHERE reminder_id = ?)",
+        params![reminder_id],
+    )?;
+    conn.execute("DELETE FROM webhooks WHERE reminder_id = ?", params![reminder_id])?;
+    Ok(())
+}
+
+/// Queues the event for every enabled webhook that wants it. Called with the
+/// connection that just recorded the event.
+pub(super) fn enqueue_deliveries(conn: &Connection, event: &ReminderEvent) -> AppResult<()> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM webhooks WHERE enabled = 1 AND (reminder_id IS NULL OR reminder_id = ?)",
+        WEBHOOK_COLUMNS
+    ))?;
+    let webhooks: Vec<Webhook> = stmt
+        .query_map(params![event.reminder_id], row_to_webhook)?
+        .collect::<Result<Vec<_>, _>>()?;
+    let webhooks: Vec<&Webhook> = webhooks.iter().filter(|w| w.events.contains(&event.kind)).collect();
+    if webhooks.is_empty() {
+        return Ok(());
+    }
+
+    let reminder: Option<(String, Option<String>)> = conn
+        .query_row(
+            "SELECT name, message FROM reminders WHERE id = ?",
+            params![event.reminder_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()?;
+    let (name, message) = reminder.unzip();
+    let payload = serde_json::json!({
+        "event": event.kind,
+        "event_id": event.id,
+        "occurred_at": event.occurred_at,
+        "reminder": {
+            "id": event.reminder_id,
+            "name": name,
+            "message": message.flatten(),
+        },
+        "metadata": event.metadata,
+    })
+    .to_string();
+
+    let now = timestamp(Utc::now());
+    for webhook in webhooks {
+        conn.execute(
+            "INSERT INTO webhook_deliveries (webhook_id, event_id, reminder_id, kind, payload, status, next_attempt_at, created_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+            params![
+                webhook.id,
+                event.id,
+                event.reminder_id,
+                event.kind.as_str(),
+                payload,
+                DeliveryStatus::Pending.as_str(),
+                now,
+                now,
+            ],
+        )?;
+    }
+    Ok(())
+}
+
+/// Pending deliveries whose next attempt is due, oldest first. Deliveries of
+/// a disabled webhook wait until it is enabled again.
+pub fn get_due_deliveries(now: DateTime<Utc>, limit: usize) -> AppResult<Vec<QueuedDelivery>> {
+    let conn = db()?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {}, w.url, w.secret, d.payload FROM webhook_deliveries d
+         JOIN webhooks w ON w.id = d.webhook_id
+         WHERE d.status = ? AND d.next_attempt_at <= ? AND w.enabled = 1
+         ORDER BY d.next_attempt_at, d.id
+         LIMIT ?",
+        DELIVERY_COLUMNS
+    ))?;
+    let rows = stmt
+        .query_map(
+            params![DeliveryStatus::Pending.as_str(), timestamp(now), limit as i64],
+            |row| {
+                let Some(delivery) = row_to_delivery(row)? else {
+                    return Ok(None);
+                };
+                Ok(Some(QueuedDelivery {
+                    delivery,
+                    url: row.get(12)?,
+                    secret: row.get(13)?,
+                    payload: row.get(14)?,
+                }))
+            },
+        )?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(rows.into_iter().flatten().collect())
+}
+
+/// Stores the outcome of an attempt. `next_attempt_at` is only used while
+/// the delivery stays pending.
+pub fn record_delivery_attempt(
+    id: i64,
+    status: DeliveryStatus,
+    response_status: Option<u16>,
+    error: Option<&str>,
+    next_attempt_at: Option<DateTime<Utc>>,
+) -> AppResult<()> {
+    let conn = db()?;
+    let next_attempt_at = match status {
+        DeliveryStatus::Pending => next_attempt_at.map(timestamp),
+        _ => None,
+    };
+    conn.execute(
+        "UPDATE webhook_deliveries SET status = ?, attempts = attempts + 1, last_attempt_at = ?, response_status = ?, last_error = ?, next_attempt_at = ? WHERE id = ?",
+        params![
+            status.as_str(),
+            timestamp(Utc::now()),
+            response_status,
+            error,
+            next_attempt_at,
+            id,
+        ],
+    )?;
+    Ok(())
+}
+
+/// The delivery log, newest first
+pub fn get_webhook_deliveries(webhook_id: Option<i64>, limit: usize) -> AppResult<Vec<WebhookDelivery>> {
+    let conn = db()?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM webhook_deliveries d
+         WHERE (?1 IS NULL OR d.webhook_id = ?1)
+         ORDER BY d.id DESC
+         LIMIT ?2",
+        DELIVERY_COLUMNS
+    ))?;
+    let rows = stmt
+        .query_map(params![webhook_id, limit as i64], row_to_delivery)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(rows.into_iter().flatten().collect())
+}
@@ -0,0 +1,1171 @@
+use super::SkippedReminder;
+use crate::error::{AppError, AppResult};
+use crate::reminder::{
+    default_timezone, local_to_utc, parse_ical_local, reminder_timezone, system_timezone, Frequency, Rrule,
+};
+use crate::storage::{self, CreateReminder, Reminder, ReminderChanges, Schedule, SOUNDS};
+use chrono::{
+    DateTime, Duration, NaiveDateTime, NaiveTime, TimeZone, Timelike, Utc, Weekday,
+};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+
+const PRODID: &str = "-//MultiTask Reminder//EN";
+const UID_DOMAIN: &str = "multitask-reminder";
+
+/// Content lines longer than this many octets are folded (RFC 5545 3.1)
+const MAX_LINE_OCTETS: usize = 75;
+
+const UTC_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+const LOCAL_FORMAT: &str = "%Y%m%dT%H%M%S";
+
+const ALARM_OUT_OF_RANGE: &str = "The alarm is too far from the event";
+
+/// A content line such as `DTSTART;TZID=Europe/Berlin:20261103T090000`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Property {
+    pub name: String,
+    pub params: Vec<(String, String)>,
+    pub value: String,
+}
+
+impl Property {
+    pub fn new(name: impl Into<String>, value: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            params: Vec::new(),
+            value: value.into(),
+        }
+    }
+
+    pub fn with_param(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.params.push((name.into(), value.into()));
+        self
+    }
+
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// A `BEGIN:…` / `END:…` block with its properties and nested blocks
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Component {
+    pub name: String,
+    pub properties: Vec<Property>,
+    pub components: Vec<Component>,
+}
+
+impl Component {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            properties: Vec::new(),
+            components: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, name: impl Into<String>, value: impl Into<String>) -> &mut Self {
+        self.properties.push(Property::new(name, value));
+        self
+    }
+
+    pub fn property(&self, name: &str) -> Option<&Property> {
+        self.properties.iter().find(|p| p.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn value(&self, name: &str) -> Option<&str> {
+        self.property(name).map(|p| p.value.as_str())
+    }
+
+    pub fn children<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Component> + 'a {
+        self.components
+            .iter()
+            .filter(move |c| c.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Serializes the component with CRLF line endings and folded lines
+    pub fn to_ics(&self) -> String {
+        let mut out = String::new();
+        self.write(&mut out);
+        out
+    }
+
+    fn write(&self, out: &mut String) {
+        write_line(out, &format!("BEGIN:{}", self.name));
+        for property in &self.properties {
+            let mut line = property.name.clone();
+            for (key, value) in &property.params {
+                if value.contains([';', ':', ',']) {
+                    line.push_str(&format!(";{}=\"{}\"", key, value));
+                } else {
+                    line.push_str(&format!(";{}={}", key, value));
+                }
+            }
+            line.push(':');
+            line.push_str(&property.value);
+            write_line(out, &line);
+        }
+        for component in &self.components {
+            component.write(out);
+        }
+        write_line(out, &format!("END:{}", self.name));
+    }
+}
+
+fn write_line(out: &mut String, line: &str) {
+    let mut octets = 0;
+    for c in line.chars() {
+        if octets + c.len_utf8() > MAX_LINE_OCTETS {
+            out.push_str("\r\n ");
+            // The leading space of the continuation counts towards its length
+            octets = 1;
+        }
+        out.push(c);
+        octets += c.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+/// Parses an iCalendar stream into its top-level `VCALENDAR` component
+pub fn parse_ics(text: &str) -> Result<Component, String> {
+    // Unfold continuation lines first
+    let mut lines: Vec<String> = Vec::new();
+    for raw in text.split('\n') {
+        let raw = raw.strip_suffix('\r').unwrap_or(raw);
+        match (raw.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(continuation), Some(last)) => last.push_str(continuation),
+            _ => lines.push(raw.to_string()),
+        }
+    }
+
+    let mut stack: Vec<Component> = Vec::new();
+    for (number, line) in lines.iter().enumerate().filter(|(_, l)| !l.trim().is_empty()) {
+        let property = parse_content_line(line)
+            .ok_or_else(|| format!("Invalid iCalendar line {}: '{}'", number + 1, line))?;
+
+        if property.name.eq_ignore_ascii_case("BEGIN") {
+            stack.push(Component::new(property.value.to_ascii_uppercase()));
+        } else if property.name.eq_ignore_ascii_case("END") {
+            let component = stack
+                .pop()
+                .filter(|c| c.name.eq_ignore_ascii_case(&property.value))
+                .ok_or_else(|| format!("Unexpected END:{} on line {}", property.value, number + 1))?;
+            match stack.last_mut() {
+                Some(parent) => parent.components.push(component),
+                None if component.name == "VCALENDAR" => return Ok(component),
+                None => return Err(format!("Expected a VCALENDAR, found {}", component.name)),
+            }
+        } else {
+            stack
+                .last_mut()
+                .ok_or_else(|| format!("Property outside of a component on line {}", number + 1))?
+                .properties
+                .push(property);
+        }
+    }
+
+    Err("The calendar is incomplete".to_string())
+}
+
+/// Splits `NAME;PARAM=value;PARAM="quoted:value":VALUE`
+fn parse_content_line(line: &str) -> Option<Property> {
+    let mut in_quotes = false;
+    let mut segments = Vec::new();
+    let mut segment_start = 0;
+    let mut value_start = None;
+
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            ';' if !in_quotes => {
+                segments.push(&line[segment_start..i]);
+                segment_start = i + 1;
+            }
+            ':' if !in_quotes => {
+                segments.push(&line[segment_start..i]);
+                value_start = Some(i + 1);
+                break;
+            }
+            _ => {}
+        }
+    }
+
+    let value = &line[value_start?..];
+    let mut segments = segments.into_iter();
+    let name = segments.next().filter(|n| !n.is_empty())?.to_ascii_uppercase();
+    let params = segments
+        .filter_map(|p| p.split_once('='))
+        .map(|(key, value)| (key.to_ascii_uppercase(), value.trim_matches('"').to_string()))
+        .collect();
+
+    Some(Property {
+        name,
+        params,
+        value: value.to_string(),
+    })
+}
+
+/// Escapes a TEXT value (RFC 5545 3.3.11)
+pub fn escape_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+pub fn unescape_text(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') | Some('N') => unescaped.push('\n'),
+            Some(other) => unescaped.push(other),
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IcsExportReport {
+    /// Number of reminders written to the calendar
+    pub exported: usize,
+    pub skipped: Vec<SkippedReminder>,
+}
+
+/// Writes the given reminders, or every enabled one, to an `.ics` file
+pub fn export_ics(path: &Path, ids: Option<&[i64]>) -> AppResult<IcsExportReport> {
+    let reminders: Vec<Reminder> = storage::get_all_reminders()?
+        .into_iter()
+        .rev()
+        .filter(|r| match ids {
+            Some(ids) => ids.contains(&r.id),
+            None => r.enabled,
+        })
+        .collect();
+
+    let tz = default_timezone(&storage::get_settings()?).unwrap_or_else(system_timezone);
+    let (calendar, report) = build_calendar(&reminders, Utc::now(), tz);
+    std::fs::write(path, calendar.to_ics())
+        .map_err(|e| AppError::Io(format!("Failed to write {}: {}", path.display(), e)))?;
+    Ok(report)
+}
+
+/// Builds a calendar with one VEVENT per reminder, or several when its times
+/// of day can't be expressed by a single recurrence rule. Each event carries
+/// a VALARM so calendar clients notify at the same time the app would.
+/// Recurring events start in the reminder's zone, `default_tz` if it has none.
+pub fn build_calendar(reminders: &[Reminder], now: DateTime<Utc>, default_tz: Tz) -> (Component, IcsExportReport) {
+    let mut calendar = Component::new("VCALENDAR");
+    calendar
+        .push("VERSION", "2.0")
+        .push("PRODID", PRODID)
+        .push("CALSCALE", "GREGORIAN");
+
+    let mut report = IcsExportReport::default();
+    for reminder in reminders {
+        match reminder_events(reminder, now, default_tz) {
+            Ok(events) => {
+                calendar.components.extend(events);
+                report.exported += 1;
+            }
+            Err(reason) => report.skipped.push(SkippedReminder {
+                name: reminder.name.clone(),
+                reason,
+            }),
+        }
+    }
+
+    (calendar, report)
+}
+
+fn reminder_events(reminder: &Reminder, now: DateTime<Utc>, default_tz: Tz) -> Result<Vec<Component>, String> {
+    let tz = reminder_timezone(reminder, default_tz);
+    let starts: Vec<(Property, Option<Rrule>)> = match &reminder.due_at {
+        Some(due_at) => {
+            let due = DateTime::parse_from_rfc3339(due_at)
+                .map_err(|_| format!("Invalid due date '{}'", due_at))?;
+            vec![(Property::new("DTSTART", due.with_timezone(&Utc).format(UTC_FORMAT).to_string()), None)]
+        }
+        // Times of day stay in the reminder's zone wherever the file is opened
+        None => recurrences(reminder, tz)?
+            .into_iter()
+            .map(|(start, rule)| {
+                let start = Property::new("DTSTART", start.format(LOCAL_FORMAT).to_string())
+                    .with_param("TZID", tz.name());
+                (start, Some(rule))
+            })
+            .collect(),
+    };
+
+    let multiple = starts.len() > 1;
+    Ok(starts
+        .into_iter()
+        .enumerate()
+        .map(|(index, (dtstart, rule))| {
+            let uid = if multiple {
+                format!("reminder-{}-{}@{}", reminder.id, index + 1, UID_DOMAIN)
+            } else {
+                format!("reminder-{}@{}", reminder.id, UID_DOMAIN)
+            };
+
+            let mut event = Component::new("VEVENT");
+            event
+                .push("UID", uid)
+                .push("DTSTAMP", now.format(UTC_FORMAT).to_string());
+            event.properties.push(dtstart);
+            if let Some(rule) = rule {
+                event.push("RRULE", rule.to_string());
+            }
+            event.push("SUMMARY", escape_text(&reminder.name));
+            if let Some(message) = &reminder.message {
+                event.push("DESCRIPTION", escape_text(message));
+            }
+            event.push("X-MULTITASK-SOUND", reminder.sound.clone());
+
+            let mut alarm = Component::new("VALARM");
+            alarm
+                .push("ACTION", "DISPLAY")
+                .push("TRIGGER", "PT0S")
+                .push("DESCRIPTION", escape_text(&reminder.name));
+            event.components.push(alarm);
+            event
+        })
+        .collect())
+}
+
+/// Recurrence rules with their start times in `tz` that together produce the
+/// reminder's occurrences.
+///
+/// Interval reminders count from their last trigger, which a calendar can't
+/// express, so they are anchored at the start of the active window (or the
+/// creation time without one) instead.
+fn recurrences(reminder: &Reminder, tz: Tz) -> Result<Vec<(NaiveDateTime, Rrule)>, String> {
+    let created = DateTime::parse_from_rfc3339(&reminder.created_at)
+        .map(|t| t.with_timezone(&tz).naive_local())
+        .unwrap_or_else(|_| Utc::now().with_timezone(&tz).naive_local())
+        .with_second(0)
+        .and_then(|t| t.with_nanosecond(0))
+        .unwrap_or_default();
+    let by_day: Vec<(Option<i32>, Weekday)> = reminder
+        .active_days
+        .iter()
+        .flatten()
+        .filter_map(|d| weekday_from_index(*d))
+        .map(|wd| (None, wd))
+        .collect();
+    let window = match (&reminder.active_start_time, &reminder.active_end_time) {
+        (Some(start), Some(end)) => Some((parse_hh_mm(start)?, parse_hh_mm(end)?)),
+        _ => None,
+    };
+
+    match &reminder.schedule {
+        Schedule::Interval => {
+            let minutes = reminder.interval_minutes;
+            if minutes <= 0 {
+                return Err("Interval must be at least 1 minute".to_string());
+            }
+
+            if let Some((start, end)) = window.filter(|_| minutes < 24 * 60) {
+                let times = window_times(start, end, minutes as i64);
+                return Ok(daily_rules(&times, &by_day, created));
+            }
+
+            let (freq, interval) = if minutes % (24 * 60) == 0 {
+                (Frequency::Daily, minutes / (24 * 60))
+            } else if minutes % 60 == 0 {
+                (Frequency::Hourly, minutes / 60)
+            } else {
+                (Frequency::Minutely, minutes)
+            };
+            let mut rule = Rrule::new(freq);
+            rule.interval = interval as u32;
+            rule.by_day = by_day;
+            let start = match window {
+                Some((start, _)) => created.date().and_time(start),
+                None => created,
+            };
+            Ok(vec![(start, rule)])
+        }
+        Schedule::DailyTimes { times } => {
+            let mut parsed = times
+                .iter()
+                .map(|t| parse_hh_mm(t))
+                .collect::<Result<Vec<_>, _>>()?;
+            if let Some((start, end)) = window {
+                parsed.retain(|t| in_window(*t, start, end));
+            }
+            if parsed.is_empty() {
+                return Err("None of the times of day fall into the active window".to_string());
+            }
+            Ok(daily_rules(&parsed, &by_day, created))
+        }
+        Schedule::Cron { .. } => Err("Cron schedules can't be exported to iCalendar".to_string()),
+        Schedule::Rrule { rule } => {
+            let mut rule: Rrule = rule.parse()?;
+            let start = rule.dtstart.take().unwrap_or(created);
+            // The rule's own day filter wins, otherwise add the active days
+            if rule.by_day.is_empty() && !matches!(rule.freq, Frequency::Monthly | Frequency::Yearly) {
+                rule.by_day = by_day;
+            }
+            Ok(vec![(start, rule)])
+        }
+    }
+}
+
+/// Daily rules for a set of local times of day. Times that share the same
+/// hours are combined into one rule, since BYHOUR and BYMINUTE multiply.
+fn daily_rules(
+    times: &[NaiveTime],
+    by_day: &[(Option<i32>, Weekday)],
+    created: NaiveDateTime,
+) -> Vec<(NaiveDateTime, Rrule)> {
+    let mut hours_by_minute: BTreeMap<u32, Vec<u32>> = BTreeMap::new();
+    for time in times {
+        hours_by_minute.entry(time.minute()).or_default().push(time.hour());
+    }
+    let mut minutes_by_hours: BTreeMap<Vec<u32>, Vec<u32>> = BTreeMap::new();
+    for (minute, mut hours) in hours_by_minute {
+        hours.sort_unstable();
+        hours.dedup();
+        minutes_by_hours.entry(hours).or_default().push(minute);
+    }
+
+    minutes_by_hours
+        .into_iter()
+        .map(|(hours, minutes)| {
+            let mut rule = Rrule::new(Frequency::Daily);
+            rule.by_day = by_day.to_vec();
+            let start = NaiveTime::from_hms_opt(hours[0], minutes[0], 0)
+                .map(|t| created.date().and_time(t))
+                .unwrap_or(created);
+            rule.by_hour = hours;
+            rule.by_minute = minutes;
+            (start, rule)
+        })
+        .collect()
+}
+
+/// The times of day an interval hits when it starts at the beginning of the
+/// window and stops at its end, which may be after midnight
+fn window_times(start: NaiveTime, end: NaiveTime, minutes: i64) -> Vec<NaiveTime> {
+    let mut length = (end - start).num_minutes();
+    if length < 0 {
+        length += 24 * 60;
+    }
+    (0..=length / minutes)
+        .map(|step| start + Duration::minutes(step * minutes))
+        .collect()
+}
+
+/// Same rules as the scheduler's active window check, both ends inclusive
+fn in_window(time: NaiveTime, start: NaiveTime, end: NaiveTime) -> bool {
+    if start <= end {
+        time >= start && time <= end
+    } else {
+        time >= start || time <= end
+    }
+}
+
+fn parse_hh_mm(value: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(value, "%H:%M").map_err(|_| format!("Invalid time '{}'", value))
+}
+
+/// Active days count from Monday = 0
+fn weekday_from_index(day: u8) -> Option<Weekday> {
+    Weekday::try_from(day).ok()
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IcsImportReport {
+    pub dry_run: bool,
+    pub created: Vec<String>,
+    pub skipped: Vec<SkippedReminder>,
+    /// Parts of imported events that were ignored, e.g. excluded dates
+    pub warnings: Vec<String>,
+    /// Events without an alarm, which aren't meant to remind anyone
+    pub ignored: usize,
+}
+
+/// Creates a reminder for every event with an alarm. With `dry_run` nothing
+/// is created and the report shows what would happen.
+pub fn import_ics(path: &Path, dry_run: bool) -> AppResult<IcsImportReport> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| AppError::Io(format!("Failed to read {}: {}", path.display(), e)))?;
+    let calendar = parse_ics(&text).map_err(|reason| AppError::validation("path", reason))?;
+    let existing = storage::get_all_reminders()?
+        .iter()
+        .map(|r| name_key(&r.name))
+        .collect();
+
+    let tz = default_timezone(&storage::get_settings()?).unwrap_or_else(system_timezone);
+    let (reminders, mut report) = reminders_from_calendar(&calendar, &existing, Utc::now(), tz);
+    report.dry_run = dry_run;
+    if !dry_run {
+        storage::apply_changes(ReminderChanges {
+            creations: reminders,
+            ..Default::default()
+        })?;
+    }
+    Ok(report)
+}
+
+fn name_key(name: &str) -> String {
+    name.trim().to_lowercase()
+}
+
+/// Turns each VEVENT with a VALARM into a reminder that fires when the
+/// alarm would. Events whose name is in `existing_names` (lower case) are
+/// skipped, as are recurrence rules the scheduler can't evaluate. Times
+/// without a known zone are read in `default_tz`.
+pub fn reminders_from_calendar(
+    calendar: &Component,
+    existing_names: &HashSet<String>,
+    now: DateTime<Utc>,
+    default_tz: Tz,
+) -> (Vec<CreateReminder>, IcsImportReport) {
+    let mut names = existing_names.clone();
+    let mut reminders = Vec::new();
+    let mut report = IcsImportReport::default();
+
+    for event in calendar.children("VEVENT") {
+        let Some(alarm) = event.children("VALARM").next() else {
+            report.ignored += 1;
+            continue;
+        };
+        let name = event
+            .value("SUMMARY")
+            .map(|s| unescape_text(s).trim().to_string())
+            .filter(|s| !s.is_empty())
+            .unwrap_or_else(|| "Untitled event".to_string());
+        let mut skip = |reason: &str| {
+            report.skipped.push(SkippedReminder {
+                name: name.clone(),
+                reason: reason.to_string(),
+            })
+        };
+
+        if event.property("RECURRENCE-ID").is_some() {
+            skip("Changes to a single occurrence of a recurring event are not supported");
+            continue;
+        }
+        if names.contains(&name_key(&name)) {
+            skip("A reminder with this name already exists");
+            continue;
+        }
+
+        let result = event_to_reminder(event, alarm, &name, now, default_tz).and_then(|(mut reminder, warnings)| {
+            reminder.validate().map_err(|e| e.to_string())?;
+            Ok((reminder, warnings))
+        });
+        match result {
+            Ok((reminder, warnings)) => {
+                names.insert(name_key(&name));
+                report.created.push(name);
+                report.warnings.extend(warnings);
+                reminders.push(reminder);
+            }
+            Err(reason) => skip(&reason),
+        }
+    }
+
+    (reminders, report)
+}
+
+/// When an alarm fires: at a fixed time, or relative to the event start
+enum AlarmTime {
+    Absolute(DateTime<Utc>),
+    Relative(Duration),
+}
+
+fn event_to_reminder(
+    event: &Component,
+    alarm: &Component,
+    name: &str,
+    now: DateTime<Utc>,
+    default_tz: Tz,
+) -> Result<(CreateReminder, Vec<String>), String> {
+    let mut warnings = Vec::new();
+    let start_property = event.property("DTSTART").ok_or("The event has no DTSTART")?;
+    // Zoned times stay in their zone, which the reminder keeps
+    let zone = start_property.param("TZID").and_then(|tzid| match tzid.parse::<Tz>() {
+        Ok(zone) => Some(zone),
+        Err(_) => {
+            warnings.push(format!(
+                "{}: times in the unknown zone {} were read in {}",
+                name,
+                tzid,
+                default_tz.name()
+            ));
+            None
+        }
+    });
+    let tz = zone.unwrap_or(default_tz);
+    // UTC times become wall-clock times in the default zone, which the
+    // reminder then keeps too. Floating times follow the default zone.
+    let utc_start = start_property.value.trim().ends_with('Z');
+    let timezone = (zone.is_some() || utc_start).then(|| tz.name().to_string());
+    let start = parse_event_time(start_property, tz)?;
+
+    let alarm_time = alarm_time(event, alarm, start, tz)?;
+
+    let (schedule, due_at) = match event.value("RRULE") {
+        None => {
+            let due = match alarm_time {
+                AlarmTime::Absolute(at) => at,
+                AlarmTime::Relative(offset) => {
+                    let alarm = start.checked_add_signed(offset).ok_or(ALARM_OUT_OF_RANGE)?;
+                    local_to_utc(alarm, tz).ok_or("The alarm time doesn't exist in the event's time zone")?
+                }
+            };
+            if due <= now {
+                return Err("The alarm time has already passed".to_string());
+            }
+            (Schedule::Interval, Some(due.to_rfc3339()))
+        }
+        Some(rule_text) => {
+            let AlarmTime::Relative(offset) = alarm_time else {
+                return Err("Alarms at a fixed time are not supported for recurring events".to_string());
+            };
+            let rule: Rrule = rule_text
+                .parse()
+                .map_err(|e| format!("Unsupported recurrence rule: {}", e))?;
+
+            // The alarm series is the event series shifted by the offset,
+            // which only works while the rule doesn't pin times or days
+            let alarm_start = start.checked_add_signed(offset).ok_or(ALARM_OUT_OF_RANGE)?;
+            if offset != Duration::zero() && !(rule.by_hour.is_empty() && rule.by_minute.is_empty()) {
+                return Err("Alarms before or after the event can't be combined with BYHOUR or BYMINUTE".to_string());
+            }
+            let pins_days = !(rule.by_day.is_empty() && rule.by_month_day.is_empty() && rule.by_month.is_empty());
+            if alarm_start.date() != start.date() && pins_days {
+                return Err("The alarm falls on a different day than the event, which the recurrence rule can't express".to_string());
+            }
+            if rule.occurrences_after(alarm_start, now, tz).next().is_none() {
+                return Err("The series has no upcoming occurrences".to_string());
+            }
+
+            for (property, effect) in [("EXDATE", "reminders still fire on excluded dates"), ("RDATE", "additional dates are not included")] {
+                if event.property(property).is_some() {
+                    warnings.push(format!("{}: {} is not supported, {}", name, property, effect));
+                }
+            }
+
+            let rule = format!("DTSTART:{}\nRRULE:{}", alarm_start.format(LOCAL_FORMAT), rule);
+            (Schedule::Rrule { rule }, None)
+        }
+    };
+
+    let sound = event
+        .value("X-MULTITASK-SOUND")
+        .filter(|s| SOUNDS.contains(s))
+        .unwrap_or("chime")
+        .to_string();
+
+    Ok((
+        CreateReminder {
+            name: name.to_string(),
+            message: event.value("DESCRIPTION").map(unescape_text),
+            interval_minutes: 0,
+            schedule,
+            due_at,
+            enabled: true,
+            active_start_time: None,
+            active_end_time: None,
+            active_days: None,
+            sound,
+            command: None,
+            delivery_style: Default::default(),
+            escalation: Vec::new(),
+            catch_up: Default::default(),
+            timezone,
+        },
+        warnings,
+    ))
+}
+
+/// A DTSTART/DTEND value as wall-clock time in `tz`. UTC values are
+/// converted, zoned and floating values are taken as they are.
+fn parse_event_time(property: &Property, tz: Tz) -> Result<NaiveDateTime, String> {
+    let time = parse_ical_local(&property.value)?;
+    if property.value.trim().ends_with('Z') {
+        Ok(Utc.from_utc_datetime(&time).with_timezone(&tz).naive_local())
+    } else {
+        Ok(time)
+    }
+}
+
+fn alarm_time(event: &Component, alarm: &Component, start: NaiveDateTime, tz: Tz) -> Result<AlarmTime, String> {
+    let trigger = alarm.property("TRIGGER").ok_or("The alarm has no TRIGGER")?;
+
+    if trigger.param("VALUE").is_some_and(|v| v.eq_ignore_ascii_case("DATE-TIME")) {
+        let at = parse_ical_local(&trigger.value)?;
+        return Ok(AlarmTime::Absolute(Utc.from_utc_datetime(&at)));
+    }
+
+    let offset = parse_duration(&trigger.value)
+        .ok_or_else(|| format!("Invalid alarm TRIGGER '{}'", trigger.value))?;
+    if !trigger.param("RELATED").is_some_and(|r| r.eq_ignore_ascii_case("END")) {
+        return Ok(AlarmTime::Relative(offset));
+    }
+
+    let length = match (event.property("DTEND"), event.value("DURATION")) {
+        (Some(end), _) => parse_event_time(end, tz)? - start,
+        (None, Some(duration)) => {
+            parse_duration(duration).ok_or_else(|| format!("Invalid DURATION '{}'", duration))?
+        }
+        (None, None) => Duration::zero(),
+    };
+    length
+        .checked_add(&offset)
+        .map(AlarmTime::Relative)
+        .ok_or_else(|| ALARM_OUT_OF_RANGE.to_string())
+}
+
+/// Parses a DURATION value such as `-PT15M`, `P1D` or `P1DT2H30M`
+fn parse_duration(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    let (negative, rest) = match value.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, value.strip_prefix('+').unwrap_or(value)),
+    };
+    let rest = rest.strip_prefix('P')?;
+
+    let mut total = Duration::zero();
+    let mut number = String::new();
+    let mut in_time = false;
+    let mut parsed_any = false;
+
+    for c in rest.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        if c == 'T' && number.is_empty() && !in_time {
+            in_time = true;
+            continue;
+        }
+        let n: i64 = number.parse().ok()?;
+        number.clear();
+        // Out-of-range values fail instead of panicking
+        let part = match (c, in_time) {
+            ('W', false) => Duration::try_weeks(n),
+            ('D', false) => Duration::try_days(n),
+            ('H', true) => Duration::try_hours(n),
+            ('M', true) => Duration::try_minutes(n),
+            ('S', true) => Duration::try_seconds(n),
+            _ => return None,
+        };
+        total = total.checked_add(&part?)?;
+        parsed_any = true;
+    }
+
+    (parsed_any && number.is_empty()).then_some(if negative { -total } else { total })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn reminder(name: &str) -> Reminder {
+        Reminder {
+            id: 1,
+            name: name.to_string(),
+            message: None,
+            interval_minutes: 60,
+            schedule: Schedule::Interval,
+            due_at: None,
+            enabled: true,
+            active_start_time: None,
+            active_end_time: None,
+            active_days: None,
+            sound: "chime".to_string(),
+            command: None,
+            delivery_style: Default::default(),
+            escalation: Vec::new(),
+            catch_up: Default::default(),
+            timezone: None,
+            last_triggered: None,
+            snoozed_until: None,
+            snooze_count: 0,
+            pending_since: None,
+            created_at: "2026-03-02T07:12:00+00:00".to_string(),
+        }
+    }
+
+    fn round_trip(reminders: &[Reminder]) -> (Component, IcsExportReport) {
+        let now = Utc.with_ymd_and_hms(2026, 3, 2, 12, 0, 0).unwrap();
+        let (calendar, report) = build_calendar(reminders, now, chrono_tz::Europe::Berlin);
+        let parsed = parse_ics(&calendar.to_ics()).expect("exported calendar parses");
+        assert_eq!(parsed, calendar);
+        (parsed, report)
+    }
+
+    fn rules(calendar: &Component) -> Vec<Rrule> {
+        calendar
+            .children("VEVENT")
+            .map(|e| e.value("RRULE").expect("event has an RRULE").parse().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn interval_with_window_and_days_becomes_daily_rule() {
+        let mut r = reminder("Stretch");
+        r.interval_minutes = 30;
+        r.active_start_time = Some("09:00".to_string());
+        r.active_end_time = Some("17:30".to_string());
+        r.active_days = Some(vec![0, 1, 2, 3, 4]);
+
+        let (calendar, report) = round_trip(&[r]);
+
+        assert_eq!(report.exported, 1);
+        let mut expected = Rrule::new(Frequency::Daily);
+        expected.by_day = [Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri]
+            .into_iter()
+            .map(|wd| (None, wd))
+            .collect();
+        expected.by_hour = (9..=17).collect();
+        expected.by_minute = vec![0, 30];
+        assert_eq!(rules(&calendar), vec![expected]);
+
+        let event = calendar.children("VEVENT").next().unwrap();
+        assert!(event.value("DTSTART").unwrap().ends_with("T090000"));
+        assert_eq!(event.property("DTSTART").unwrap().param("TZID"), Some("Europe/Berlin"));
+        assert_eq!(event.children("VALARM").count(), 1);
+    }
+
+    #[test]
+    fn interval_with_uneven_step_is_split_by_minute() {
+        let mut r = reminder("Water");
+        r.interval_minutes = 45;
+        r.active_start_time = Some("09:00".to_string());
+        r.active_end_time = Some("12:00".to_string());
+
+        let (calendar, _) = round_trip(&[r]);
+
+        // 09:00 09:45 10:30 11:15 12:00
+        let rules = rules(&calendar);
+        let times: Vec<(Vec<u32>, Vec<u32>)> =
+            rules.iter().map(|r| (r.by_hour.clone(), r.by_minute.clone())).collect();
+        assert_eq!(
+            times,
+            vec![
+                (vec![9], vec![45]),
+                (vec![9, 12], vec![0]),
+                (vec![10], vec![30]),
+                (vec![11], vec![15]),
+            ]
+        );
+        assert!(calendar.children("VEVENT").all(|e| e.value("UID").unwrap().starts_with("reminder-1-")));
+    }
+
+    #[test]
+    fn interval_without_window_uses_matching_frequency() {
+        let mut hourly = reminder("Hourly");
+        hourly.interval_minutes = 120;
+        let mut minutely = reminder("Minutely");
+        minutely.interval_minutes = 25;
+        minutely.active_days = Some(vec![5, 6]);
+
+        let (calendar, _) = round_trip(&[hourly, minutely]);
+        let rules = rules(&calendar);
+
+        assert_eq!(rules[0].to_string(), "FREQ=HOURLY;INTERVAL=2");
+        assert_eq!(rules[1].to_string(), "FREQ=MINUTELY;INTERVAL=25;BYDAY=SA,SU");
+    }
+
+    #[test]
+    fn daily_times_outside_window_are_dropped() {
+        let mut r = reminder("Pills");
+        r.schedule = Schedule::DailyTimes {
+            times: vec!["08:00".to_string(), "20:00".to_string(), "23:30".to_string()],
+        };
+        r.active_start_time = Some("07:00".to_string());
+        r.active_end_time = Some("21:00".to_string());
+
+        let (calendar, _) = round_trip(&[r]);
+
+        assert_eq!(rules(&calendar)[0].to_string(), "FREQ=DAILY;BYHOUR=8,20;BYMINUTE=0");
+    }
+
+    #[test]
+    fn rrule_schedule_keeps_its_start() {
+        let mut r = reminder("Review");
+        r.schedule = Schedule::Rrule {
+            rule: "DTSTART:20260105T100000\nRRULE:FREQ=WEEKLY;BYDAY=MO".to_string(),
+        };
+
+        let (calendar, _) = round_trip(&[r]);
+        let event = calendar.children("VEVENT").next().unwrap();
+
+        assert_eq!(event.value("DTSTART"), Some("20260105T100000"));
+        assert_eq!(event.value("RRULE"), Some("FREQ=WEEKLY;BYDAY=MO"));
+    }
+
+    #[test]
+    fn one_shot_has_utc_start_and_no_rule() {
+        let mut r = reminder("Call back");
+        r.due_at = Some("2026-11-03T15:00:00+01:00".to_string());
+        r.interval_minutes = 0;
+
+        let (calendar, _) = round_trip(&[r]);
+        let event = calendar.children("VEVENT").next().unwrap();
+
+        assert_eq!(event.value("DTSTART"), Some("20261103T140000Z"));
+        assert_eq!(event.property("DTSTART").unwrap().param("TZID"), None);
+        assert!(event.value("RRULE").is_none());
+        let start = NaiveDate::from_ymd_opt(2026, 11, 3).unwrap().and_hms_opt(14, 0, 0).unwrap();
+        assert_eq!(crate::reminder::parse_ical_local(event.value("DTSTART").unwrap()), Ok(start));
+    }
+
+    #[test]
+    fn cron_schedules_are_reported() {
+        let mut r = reminder("Backup");
+        r.schedule = Schedule::Cron { expression: "0 3 * * *".to_string() };
+
+        let (calendar, report) = round_trip(&[r, reminder("Other")]);
+
+        assert_eq!(report.exported, 1);
+        assert_eq!(report.skipped.len(), 1);
+        assert_eq!(report.skipped[0].name, "Backup");
+        assert_eq!(calendar.children("VEVENT").count(), 1);
+    }
+
+    #[test]
+    fn text_is_escaped_and_long_lines_folded() {
+        let mut r = reminder("Stand-up; team A, B \\ C");
+        r.message = Some(format!("Line one\nLine two with ümlauts {}", "x".repeat(120)));
+
+        let (calendar, _) = round_trip(&[r.clone()]);
+        let event = calendar.children("VEVENT").next().unwrap();
+
+        assert_eq!(unescape_text(event.value("SUMMARY").unwrap()), r.name);
+        assert_eq!(unescape_text(event.value("DESCRIPTION").unwrap()), r.message.unwrap());
+        let text = calendar.to_ics();
+        assert!(text.split("\r\n").all(|line| line.len() <= MAX_LINE_OCTETS));
+    }
+
+    #[test]
+    fn parses_quoted_parameters_and_rejects_broken_nesting() {
+        let text = "BEGIN:VCALENDAR\nBEGIN:VEVENT\nDTSTART;TZID=\"Europe/Berlin\";X-A=\"a:b\":20261103T090000\nEND:VEVENT\nEND:VCALENDAR\n";
+        let calendar = parse_ics(text).unwrap();
+        let start = calendar.children("VEVENT").next().unwrap().property("DTSTART").unwrap();
+
+        assert_eq!(start.param("tzid"), Some("Europe/Berlin"));
+        assert_eq!(start.param("X-A"), Some("a:b"));
+        assert_eq!(start.value, "20261103T090000");
+
+        assert!(parse_ics("BEGIN:VCALENDAR\nBEGIN:VEVENT\nEND:VCALENDAR\n").is_err());
+        assert!(parse_ics("BEGIN:VCALENDAR\n").is_err());
+    }
+
+    fn import(text: &str) -> (Vec<CreateReminder>, IcsImportReport) {
+        let calendar = parse_ics(text).unwrap();
+        let existing = HashSet::from(["already here".to_string()]);
+        let now = Utc.with_ymd_and_hms(2026, 3, 2, 12, 0, 0).unwrap();
+        reminders_from_calendar(&calendar, &existing, now, chrono_tz::Europe::Berlin)
+    }
+
+    fn calendar(events: &[&str]) -> String {
+        format!("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n{}END:VCALENDAR\r\n", events.concat())
+    }
+
+    const ALARM_15_MIN: &str = "BEGIN:VALARM\r\nACTION:DISPLAY\r\nTRIGGER:-PT15M\r\nEND:VALARM\r\n";
+
+    #[test]
+    fn imports_exported_reminders() {
+        let mut weekly = reminder("Review");
+        weekly.schedule = Schedule::Rrule {
+            rule: "DTSTART:20260105T100000\nRRULE:FREQ=WEEKLY;BYDAY=MO".to_string(),
+        };
+        weekly.message = Some("Agenda; notes, etc.".to_string());
+        weekly.sound = "gong".to_string();
+        let mut once = reminder("Call back");
+        once.due_at = Some("2026-11-03T14:00:00+00:00".to_string());
+
+        let (calendar, _) = build_calendar(&[weekly.clone(), once], Utc::now(), chrono_tz::Europe::Berlin);
+        let (reminders, report) = import(&calendar.to_ics());
+
+        assert_eq!(report.created, vec!["Review", "Call back"]);
+        assert!(report.skipped.is_empty());
+        assert_eq!(reminders[0].schedule, weekly.schedule);
+        assert_eq!(reminders[0].timezone.as_deref(), Some("Europe/Berlin"));
+        assert_eq!(reminders[0].message, weekly.message);
+        assert_eq!(reminders[0].sound, "gong");
+        assert_eq!(reminders[1].due_at.as_deref(), Some("2026-11-03T14:00:00+00:00"));
+    }
+
+    #[test]
+    fn imports_exported_reminders_in_their_own_zone() {
+        let mut call = reminder("Call");
+        call.schedule = Schedule::DailyTimes { times: vec!["09:00".to_string()] };
+        call.timezone = Some("America/New_York".to_string());
+
+        let (calendar, _) = round_trip(&[call]);
+        let start = calendar.children("VEVENT").next().unwrap().property("DTSTART").unwrap();
+        assert_eq!(start.param("TZID"), Some("America/New_York"));
+        assert!(start.value.ends_with("T090000"));
+
+        let (reminders, _) = import(&calendar.to_ics());
+        assert_eq!(reminders[0].timezone.as_deref(), Some("America/New_York"));
+        let Schedule::Rrule { rule } = &reminders[0].schedule else {
+            panic!("expected an RRULE schedule, got {:?}", reminders[0].schedule);
+        };
+        assert!(rule.ends_with("RRULE:FREQ=DAILY;BYHOUR=9;BYMINUTE=0"), "{}", rule);
+    }
+
+    #[test]
+    fn one_shot_fires_at_the_alarm() {
+        let text = calendar(&[&format!(
+            "BEGIN:VEVENT\r\nSUMMARY:Dentist\r\nDESCRIPTION:Bring the\\nform\r\nDTSTART:20260310T090000Z\r\n{}END:VEVENT\r\n",
+            ALARM_15_MIN
+        )]);
+
+        let (reminders, report) = import(&text);
+
+        assert_eq!(report.created, vec!["Dentist"]);
+        assert_eq!(reminders[0].due_at.as_deref(), Some("2026-03-10T08:45:00+00:00"));
+        assert_eq!(reminders[0].message.as_deref(), Some("Bring the\nform"));
+        assert_eq!(reminders[0].schedule, Schedule::Interval);
+    }
+
+    #[test]
+    fn zoned_one_off_event_is_read_in_its_zone() {
+        let text = calendar(&[&format!(
+            "BEGIN:VEVENT\r\nSUMMARY:Call\r\nDTSTART;TZID=America/New_York:20260310T090000\r\n{}END:VEVENT\r\n",
+            ALARM_15_MIN
+        )]);
+
+        let (reminders, report) = import(&text);
+
+        assert_eq!(reminders[0].due_at.as_deref(), Some("2026-03-10T12:45:00+00:00"));
+        assert_eq!(reminders[0].timezone.as_deref(), Some("America/New_York"));
+        assert!(report.warnings.is_empty());
+    }
+
+    #[test]
+    fn utc_series_is_read_in_the_default_zone() {
+        let text = calendar(&[&format!(
+            "BEGIN:VEVENT\r\nSUMMARY:Sync\r\nDTSTART:20260302T140000Z\r\nRRULE:FREQ=WEEKLY;BYDAY=MO\r\n{}END:VEVENT\r\n",
+            ALARM_15_MIN
+        )]);
+        let calendar = parse_ics(&text).unwrap();
+        let now = Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap();
+
+        let (reminders, _) = reminders_from_calendar(&calendar, &HashSet::new(), now, chrono_tz::America::New_York);
+
+        assert_eq!(
+            reminders[0].schedule,
+            Schedule::Rrule { rule: "DTSTART:20260302T084500\nRRULE:FREQ=WEEKLY;BYDAY=MO".to_string() }
+        );
+        assert_eq!(reminders[0].timezone.as_deref(), Some("America/New_York"));
+    }
+
+    #[test]
+    fn recurring_alarm_is_shifted_with_the_series() {
+        let text = calendar(&[&format!(
+            "BEGIN:VEVENT\r\nSUMMARY:Stand-up\r\nDTSTART;TZID=Europe/Berlin:20260302T093000\r\nRRULE:FREQ=WEEKLY;BYDAY=MO,WE,FR\r\nEXDATE:20260304T093000\r\n{}END:VEVENT\r\n",
+            ALARM_15_MIN
+        )]);
+
+        let (reminders, report) = import(&text);
+
+        assert_eq!(
+            reminders[0].schedule,
+            Schedule::Rrule {
+                rule: "DTSTART:20260302T091500\nRRULE:FREQ=WEEKLY;BYDAY=MO,WE,FR".to_string()
+            }
+        );
+        assert_eq!(reminders[0].timezone.as_deref(), Some("Europe/Berlin"));
+        assert_eq!(report.warnings.len(), 1);
+        assert!(report.warnings[0].contains("EXDATE"));
+    }
+
+    #[test]
+    fn reports_events_that_cannot_be_imported() {
+        let event = |summary: &str, body: &str| {
+            format!("BEGIN:VEVENT\r\nSUMMARY:{}\r\n{}{}END:VEVENT\r\n", summary, body, ALARM_15_MIN)
+        };
+        let text = calendar(&[
+            &event("Set pos", "DTSTART:20260302T090000\r\nRRULE:FREQ=MONTHLY;BYDAY=MO;BYSETPOS=-1\r\n"),
+            &event("Past", "DTSTART:20250101T090000Z\r\n"),
+            &event("Ended", "DTSTART:20250101T090000\r\nRRULE:FREQ=DAILY;COUNT=3\r\n"),
+            &event("Early", "DTSTART:20260302T000500\r\nRRULE:FREQ=WEEKLY;BYDAY=MO\r\n"),
+            &event("Already here", "DTSTART:20260310T090000Z\r\n"),
+            "BEGIN:VEVENT\r\nSUMMARY:No alarm\r\nDTSTART:20260310T090000Z\r\nEND:VEVENT\r\n",
+        ]);
+
+        let (reminders, report) = import(&text);
+
+        assert!(reminders.is_empty());
+        assert_eq!(report.ignored, 1);
+        let reasons: Vec<(&str, &str)> = report
+            .skipped
+            .iter()
+            .map(|s| (s.name.as_str(), s.reason.as_str()))
+            .collect();
+        assert_eq!(reasons.len(), 5);
+        assert!(reasons[0].1.contains("BYSETPOS"), "{:?}", reasons[0]);
+        assert_eq!(reasons[1].1, "The alarm time has already passed");
+        assert_eq!(reasons[2].1, "The series has no upcoming occurrences");
+        assert!(reasons[3].1.contains("different day"));
+        assert_eq!(reasons[4].1, "A reminder with this name already exists");
+    }
+
+    #[test]
+    fn alarm_relative_to_end_uses_duration() {
+        let text = calendar(&[
+            "BEGIN:VEVENT\r\nSUMMARY:Wrap up\r\nDTSTART:20260310T090000Z\r\nDURATION:PT1H\r\nBEGIN:VALARM\r\nACTION:DISPLAY\r\nTRIGGER;RELATED=END:-PT5M\r\nEND:VALARM\r\nEND:VEVENT\r\n",
+        ]);
+
+        let (reminders, _) = import(&text);
+
+        assert_eq!(reminders[0].due_at.as_deref(), Some("2026-03-10T09:55:00+00:00"));
+    }
+
+    #[test]
+    fn parses_durations() {
+        assert_eq!(parse_duration("-PT15M"), Some(Duration::minutes(-15)));
+        assert_eq!(parse_duration("P1DT2H30M"), Some(Duration::minutes(24 * 60 + 150)));
+        assert_eq!(parse_duration("+P2W"), Some(Duration::weeks(2)));
+        assert_eq!(parse_duration("PT0S"), Some(Duration::zero()));
+        assert_eq!(parse_duration("P"), None);
+        assert_eq!(parse_duration("PT5"), None);
+        assert_eq!(parse_duration("P5H"), None);
+        assert_eq!(parse_duration("-P999999999999W"), None);
+        assert_eq!(parse_duration("P99999999999999999999D"), None);
+        assert_eq!(parse_duration("P15000000000WT100000000000H"), None);
+    }
+
+    #[test]
+    fn huge_alarm_offsets_skip_the_event() {
+        let text = calendar(&[
+            "BEGIN:VEVENT\r\nSUMMARY:Far\r\nDTSTART:20260310T090000\r\nBEGIN:VALARM\r\nACTION:DISPLAY\r\nTRIGGER:-P999999999999W\r\nEND:VALARM\r\nEND:VEVENT\r\n",
+            "BEGIN:VEVENT\r\nSUMMARY:Further\r\nDTSTART:20260310T090000\r\nRRULE:FREQ=DAILY\r\nBEGIN:VALARM\r\nACTION:DISPLAY\r\nTRIGGER:P1000000000D\r\nEND:VALARM\r\nEND:VEVENT\r\n",
+        ]);
+
+        let (reminders, report) = import(&text);
+
+        assert!(reminders.is_empty());
+        assert_eq!(report.skipped.len(), 2);
+    }
+}
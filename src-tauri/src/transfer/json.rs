@@ -0,0 +1,400 @@
+use crate::error::{AppError, AppResult};
+use crate::storage::{self, CreateReminder, Reminder, ReminderChanges, Settings, UpdateReminder};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::Path;
+
+/// Version of the export document. Bump it when a change can't be read by
+/// older versions; new optional fields don't need a bump.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// The reminder definitions of one installation, without their runtime
+/// state (last trigger, snoozes) or history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportDocument {
+    pub version: u32,
+    pub exported_at: String,
+    #[serde(default)]
+    pub settings: Settings,
+    pub reminders: Vec<CreateReminder>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportMode {
+    /// Add new reminders and update the ones with a matching name, keeping
+    /// everything else including the local settings
+    #[default]
+    Merge,
+    /// Make the reminders and settings match the document exactly. Reminders
+    /// with a matching name are updated in place so their history survives.
+    /// The HTTP API settings always stay as they are.
+    Replace,
+}
+
+/// A reminder from the document that was not imported
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkippedReminder {
+    pub name: String,
+    pub reason: String,
+}
+
+/// What an import changed, or would change when it was a dry run
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImportReport {
+    pub mode: ImportMode,
+    pub dry_run: bool,
+    pub created: Vec<String>,
+    pub updated: Vec<String>,
+    pub unchanged: Vec<String>,
+    pub deleted: Vec<String>,
+    pub skipped: Vec<SkippedReminder>,
+    pub settings_changed: bool,
+    /// Reminders whose command was imported switched off
+    #[serde(default)]
+    pub commands_disabled: Vec<String>,
+}
+
+pub fn export_document() -> AppResult<ExportDocument> {
+    Ok(build_document(&storage::get_all_reminders()?, storage::get_settings()?))
+}
+
+/// The document for reminders listed newest first, the way storage returns them
+fn build_document(reminders: &[Reminder], settings: Settings) -> ExportDocument {
+    ExportDocument {
+        version: FORMAT_VERSION,
+        exported_at: Utc::now().to_rfc3339(),
+        settings,
+        // Oldest first, so an import recreates them in the original order
+        reminders: reminders.iter().rev().map(CreateReminder::from).collect(),
+    }
+}
+
+/// Writes every reminder and the settings to `path`, returning how many
+/// reminders were exported
+pub fn export_reminders(path: &Path) -> AppResult<usize> {
+    let document = export_document()?;
+    let json = serde_json::to_string_pretty(&document)
+        .map_err(|e| AppError::Io(format!("Failed to serialize reminders: {}", e)))?;
+    std::fs::write(path, json)
+        .map_err(|e| AppError::Io(format!("Failed to write {}: {}", path.display(), e)))?;
+    Ok(document.reminders.len())
+}
+
+pub fn read_document(path: &Path) -> AppResult<ExportDocument> {
+    let json = std::fs::read_to_string(path)
+        .map_err(|e| AppError::Io(format!("Failed to read {}: {}", path.display(), e)))?;
+    parse_document(&json)
+}
+
+pub fn parse_document(json: &str) -> AppResult<ExportDocument> {
+    // Check the version first so a newer document gets a useful message
+    // instead of a field error
+    let value: serde_json::Value = serde_json::from_str(json)
+        .map_err(|e| AppError::validation("path", format!("Not a reminder export: {}", e)))?;
+    let version = value
+        .get("version")
+        .and_then(|v| v.as_u64())
+        .ok_or_else(|| AppError::validation("path", "Not a reminder export: missing version"))?;
+    if version > FORMAT_VERSION as u64 {
+        return Err(AppError::validation(
+            "path",
+            format!(
+                "The export has format version {}, but this version of the app only supports up to {}. Please update the app.",
+                version, FORMAT_VERSION
+            ),
+        ));
+    }
+
+    serde_json::from_value(value)
+        .map_err(|e| AppError::validation("path", format!("Invalid reminder export: {}", e)))
+}
+
+pub fn import_reminders(path: &Path, mode: ImportMode, dry_run: bool) -> AppResult<ImportReport> {
+    let document = read_document(path)?;
+    import_document(document, mode, dry_run)
+}
+
+/// The settings a Replace import stores. A file can't switch the HTTP API on
+/// or move it to another port, so those stay as they are.
+fn imported_settings(document: Settings, current: &Settings) -> Settings {
+    Settings {
+        api_enabled: current.api_enabled,
+        api_port: current.api_port,
+        ..document
+    }
+}
+
+/// Reminders are matched by name, ignoring case and surrounding whitespace
+fn name_key(name: &str) -> String {
+    name.trim().to_lowercase()
+}
+
+/// The writes an import makes, all of them already validated
+#[derive(Debug, Default)]
+struct ImportPlan<'a> {
+    settings: Option<Settings>,
+    deletions: Vec<i64>,
+    writes: Vec<(Option<&'a Reminder>, CreateReminder)>,
+}
+
+pub fn import_document(document: ExportDocument, mode: ImportMode, dry_run: bool) -> AppResult<ImportReport> {
+    let existing = storage::get_all_reminders()?;
+    let (report, plan) = plan_import(document, &existing, &storage::get_settings()?, mode, dry_run)?;
+    if dry_run {
+        return Ok(report);
+    }
+
+    let mut changes = ReminderChanges {
+        settings: plan.settings,
+        deletions: plan.deletions,
+        ..Default::default()
+    };
+    for (current, reminder) in plan.writes {
+        match current {
+            Some(current) => changes.updates.push(UpdateReminder::new(current.id, reminder)),
+            None => changes.creations.push(reminder),
+        }
+    }
+    storage::apply_changes(changes)?;
+
+    Ok(report)
+}
+
+/// Works out what importing `document` over `existing` changes without
+/// touching storage. Everything is validated here, so an import that fails
+/// does so before its first write instead of leaving half a replacement.
+fn plan_import<'a>(
+    document: ExportDocument,
+    existing: &'a [Reminder],
+    current_settings: &Settings,
+    mode: ImportMode,
+    dry_run: bool,
+) -> AppResult<(ImportReport, ImportPlan<'a>)> {
+    let mut report = ImportReport {
+        mode,
+        dry_run,
+        ..Default::default()
+    };
+    let mut plan = ImportPlan::default();
+
+    // Settings a Replace import can't store fail the whole import, in a dry
+    // run too
+    if mode == ImportMode::Replace {
+        let mut settings = imported_settings(document.settings, current_settings);
+        settings.validate()?;
+        report.settings_changed = settings != *current_settings;
+        plan.settings = Some(settings).filter(|_| report.settings_changed);
+    }
+
+    // Validate everything up front so a dry run reports the same problems a
+    // real import would run into
+    let mut seen = HashSet::new();
+    let mut incoming = Vec::new();
+    for mut reminder in document.reminders {
+        let name = reminder.name.trim().to_string();
+        if !seen.insert(name_key(&name)) {
+            report.skipped.push(SkippedReminder {
+                name,
+                reason: "Duplicate name in the import".to_string(),
+            });
+            continue;
+        }
+        match reminder.validate() {
+            Ok(()) => incoming.push(reminder),
+            Err(e) => report.skipped.push(SkippedReminder {
+                name,
+                reason: e.to_string(),
+            }),
+        }
+    }
+
+    for mut reminder in incoming {
+        let key = name_key(&reminder.name);
+        let matching = existing.iter().find(|r| name_key(&r.name) == key);
+
+        // A file can't switch a command on, it only keeps one the user
+        // already allowed
+        if let Some(command) = reminder.command.as_mut() {
+            let allowed = matching
+                .and_then(|m| m.command.as_ref())
+                .is_some_and(|c| c.enabled && c.program == command.program && c.args == command.args);
+            if command.enabled && !allowed {
+                command.enabled = false;
+                report.commands_disabled.push(reminder.name.clone());
+            }
+        }
+
+        match matching {
+            Some(current) if CreateReminder::from(current) == reminder => {
+                report.unchanged.push(reminder.name);
+            }
+            Some(current) => {
+                report.updated.push(reminder.name.clone());
+                plan.writes.push((Some(current), reminder));
+            }
+            None => {
+                report.created.push(reminder.name.clone());
+                plan.writes.push((None, reminder));
+            }
+        }
+    }
+
+    // Reminders that were skipped above still count as seen, so a broken
+    // entry doesn't delete the reminder it was meant to replace
+    if mode == ImportMode::Replace {
+        for reminder in existing {
+            if !seen.contains(&name_key(&reminder.name)) {
+                report.deleted.push(reminder.name.clone());
+                plan.deletions.push(reminder.id);
+            }
+        }
+    }
+
+    Ok((report, plan))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{PausePolicy, Schedule};
+
+    fn reminder(id: i64, name: &str) -> Reminder {
+        Reminder {
+            id,
+            name: name.to_string(),
+            message: Some("Stand up and stretch".to_string()),
+            interval_minutes: 45,
+            schedule: Schedule::Interval,
+            due_at: None,
+            enabled: true,
+            active_start_time: Some("09:00".to_string()),
+            active_end_time: Some("17:00".to_string()),
+            active_days: Some(vec![0, 1, 2, 3, 4]),
+            sound: "chime".to_string(),
+            command: None,
+            delivery_style: Default::default(),
+            escalation: Vec::new(),
+            catch_up: Default::default(),
+            timezone: Some("Europe/Berlin".to_string()),
+            last_triggered: Some("2026-03-02T09:45:00+00:00".to_string()),
+            snoozed_until: None,
+            snooze_count: 0,
+            pending_since: None,
+            created_at: "2026-03-02T07:12:00+00:00".to_string(),
+        }
+    }
+
+    fn names<'a>(plan: &'a ImportPlan) -> Vec<&'a str> {
+        plan.writes.iter().map(|(_, r)| r.name.as_str()).collect()
+    }
+
+    #[test]
+    fn exported_document_imports_unchanged() {
+        // Newest first, like storage lists them
+        let existing = vec![reminder(2, "Water"), reminder(1, "Stretch")];
+        let settings = Settings {
+            default_timezone: Some("Europe/Berlin".to_string()),
+            ..Settings::default()
+        };
+
+        let json = serde_json::to_string(&build_document(&existing, settings.clone())).unwrap();
+        let document = parse_document(&json).unwrap();
+        let (report, plan) = plan_import(document, &existing, &settings, ImportMode::Replace, false).unwrap();
+
+        assert_eq!(report.unchanged, ["Stretch", "Water"]);
+        assert!(report.created.is_empty() && report.updated.is_empty() && report.deleted.is_empty());
+        assert!(report.skipped.is_empty());
+        assert!(!report.settings_changed);
+        assert!(plan.settings.is_none() && plan.deletions.is_empty() && plan.writes.is_empty());
+    }
+
+    #[test]
+    fn replace_plans_every_change() {
+        let existing = vec![reminder(2, "Water"), reminder(1, "Stretch")];
+        let mut stretch = CreateReminder::from(&existing[1]);
+        stretch.interval_minutes = 60;
+        let document = ExportDocument {
+            version: FORMAT_VERSION,
+            exported_at: Utc::now().to_rfc3339(),
+            settings: Settings {
+                pause_policy: PausePolicy::FireOnResume,
+                ..Settings::default()
+            },
+            reminders: vec![stretch, CreateReminder::from(&reminder(0, "Read"))],
+        };
+
+        let (report, plan) = plan_import(document, &existing, &Settings::default(), ImportMode::Replace, false).unwrap();
+
+        assert_eq!(report.updated, ["Stretch"]);
+        assert_eq!(report.created, ["Read"]);
+        assert_eq!(report.deleted, ["Water"]);
+        assert!(report.settings_changed);
+        assert_eq!(names(&plan), ["Stretch", "Read"]);
+        assert_eq!(plan.deletions, [2]);
+        assert_eq!(plan.settings.map(|s| s.pause_policy), Some(PausePolicy::FireOnResume));
+    }
+
+    #[test]
+    fn invalid_settings_fail_the_replace_before_anything_is_written() {
+        let existing = vec![reminder(1, "Stretch")];
+        let document = ExportDocument {
+            version: FORMAT_VERSION,
+            exported_at: Utc::now().to_rfc3339(),
+            settings: Settings {
+                default_timezone: Some("Mars/Olympus".to_string()),
+                ..Settings::default()
+            },
+            reminders: vec![CreateReminder::from(&reminder(0, "Read"))],
+        };
+
+        let error = plan_import(document.clone(), &existing, &Settings::default(), ImportMode::Replace, false)
+            .expect_err("the zone doesn't exist");
+        assert_eq!(error.field(), Some("default_timezone"));
+
+        // A merge leaves the settings alone, so it goes ahead
+        let (report, plan) = plan_import(document, &existing, &Settings::default(), ImportMode::Merge, false).unwrap();
+        assert_eq!(report.created, ["Read"]);
+        assert!(plan.settings.is_none() && plan.deletions.is_empty());
+    }
+
+    #[test]
+    fn skipped_reminders_keep_the_ones_they_match() {
+        let existing = vec![reminder(2, "Water"), reminder(1, "Stretch")];
+        let mut broken = CreateReminder::from(&existing[1]);
+        broken.sound = "kazoo".to_string();
+        let document = ExportDocument {
+            version: FORMAT_VERSION,
+            exported_at: Utc::now().to_rfc3339(),
+            settings: Settings::default(),
+            reminders: vec![broken],
+        };
+
+        let (report, plan) = plan_import(document, &existing, &Settings::default(), ImportMode::Replace, false).unwrap();
+
+        let skipped: Vec<&str> = report.skipped.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(skipped, ["Stretch"]);
+        assert_eq!(report.deleted, ["Water"]);
+        assert!(plan.writes.is_empty());
+    }
+
+    #[test]
+    fn imported_settings_keep_the_api_as_it_is() {
+        let current = Settings::default();
+        let document = Settings {
+            max_snooze_count: None,
+            pause_policy: PausePolicy::default(),
+            api_enabled: true,
+            api_port: 9000,
+            default_timezone: Some("Europe/Berlin".to_string()),
+        };
+
+        let imported = imported_settings(document, &current);
+
+        assert!(!imported.api_enabled);
+        assert_eq!(imported.api_port, current.api_port);
+        assert_eq!(imported.max_snooze_count, None);
+        assert_eq!(imported.default_timezone.as_deref(), Some("Europe/Berlin"));
+    }
+}
@@ -0,0 +1,5 @@
+mod ics;
+mod json;
+
+pub use ics::*;
+pub use json::*;
@@ -0,0 +1,323 @@
+use super::signing::{sign, DELIVERY_HEADER, EVENT_HEADER, SIGNATURE_HEADER};
+use crate::error::{AppError, AppResult};
+use crate::storage::{self, DeliveryStatus, QueuedDelivery};
+use chrono::{DateTime, Utc};
+use std::time::Duration;
+use tokio::time::sleep;
+
+/// Attempts per delivery before it is marked as failed
+pub const MAX_ATTEMPTS: u32 = 8;
+/// Wait before the first retry, doubling with every further one
+const FIRST_RETRY: chrono::Duration = chrono::Duration::seconds(30);
+const MAX_RETRY: chrono::Duration = chrono::Duration::hours(1);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// Events recorded by the command-line tool or the HTTP API end up in the
+/// same queue, so it is polled rather than notified
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+const BATCH_SIZE: usize = 20;
+
+/// Result of one attempt
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Delivered { status: u16 },
+    /// Worth trying again later: network errors, timeouts, 408, 429 and 5xx
+    Retry { status: Option<u16>, error: String },
+    /// The receiver rejected the request, retrying won't help
+    Rejected { status: u16, error: String },
+}
+
+pub fn client() -> AppResult<reqwest::Client> {
+    reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        // A redirected POST turns into a GET, treat it as a rejection instead
+        .redirect(reqwest::redirect::Policy::none())
+        .user_agent(concat!("multitask-reminder/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .map_err(|e| AppError::Io(format!("Failed to create the HTTP client: {}", e)))
+}
+
+/// POSTs the payload once
+pub async fn send(client: &reqwest::Client, queued: &QueuedDelivery) -> Outcome {
+    let mut request = client
+        .post(&queued.url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header(EVENT_HEADER, queued.delivery.kind.as_str())
+        .header(DELIVERY_HEADER, queued.delivery.id.to_string());
+    if let Some(secret) = &queued.secret {
+        request = request.header(SIGNATURE_HEADER, sign(secret, queued.payload.as_bytes()));
+    }
+
+    let response = match request.body(queued.payload.clone()).send().await {
+        Ok(response) => response,
+        Err(e) => {
+            return Outcome::Retry {
+                status: None,
+                error: describe(&e),
+            }
+        }
+    };
+
+    let status = response.status();
+    if status.is_success() {
+        return Outcome::Delivered { status: status.as_u16() };
+    }
+    let error = format!("HTTP {}", status);
+    if status.is_server_error() || status.as_u16() == 408 || status.as_u16() == 429 {
+        Outcome::Retry {
+            status: Some(status.as_u16()),
+            error,
+        }
+    } else {
+        Outcome::Rejected {
+            status: status.as_u16(),
+            error,
+        }
+    }
+}
+
+/// The error with its causes, reqwest's own message rarely says what went wrong
+fn describe(error: &reqwest::Error) -> String {
+    let mut message = error.to_string();
+    let mut source = std::error::Error::source(error);
+    while let Some(cause) = source {
+        message.push_str(": ");
+        message.push_str(&cause.to_string());
+        source = cause.source();
+    }
+    message
+}
+
+/// How long to wait after the given number of failed attempts, `None` once
+/// the delivery should be given up
+pub fn retry_delay(attempts: u32) -> Option<chrono::Duration> {
+    if attempts >= MAX_ATTEMPTS {
+        return None;
+    }
+    let factor = 1i32 << attempts.saturating_sub(1).min(16);
+    Some((FIRST_RETRY * factor).min(MAX_RETRY))
+}
+
+/// Sends everything that is due and records the outcomes, returning how many
+/// deliveries were attempted
+pub async fn deliver_due(client: &reqwest::Client, now: DateTime<Utc>) -> AppResult<usize> {
+    let queued = storage::get_due_deliveries(now, BATCH_SIZE)?;
+
+    for delivery in &queued {
+        let attempts = delivery.delivery.attempts + 1;
+        let result = match send(client, delivery).await {
+            Outcome::Delivered { status } => {
+                storage::record_delivery_attempt(delivery.delivery.id, DeliveryStatus::Delivered, Some(status), None, None)
+            }
+            Outcome::Rejected { status, error } => storage::record_delivery_attempt(
+                delivery.delivery.id,
+                DeliveryStatus::Failed,
+                Some(status),
+                Some(&error),
+                None,
+            ),
+            Outcome::Retry { status, error } => {
+                let (status_after, next) = match retry_delay(attempts) {
+                    Some(delay) => (DeliveryStatus::Pending, Some(Utc::now() + delay)),
+                    None => (DeliveryStatus::Failed, None),
+                };
+                storage::record_delivery_attempt(delivery.delivery.id, status_after, status, Some(&error), next)
+            }
+        };
+        if let Err(e) = result {
+            eprintln!("Failed to record webhook delivery {}: {}", delivery.delivery.id, e);
+        }
+    }
+
+    Ok(queued.len())
+}
+
+/// Works off the delivery queue until the app exits
+pub async fn run_webhook_deliveries() {
+    let client = match client() {
+        Ok(client) => client,
+        Err(e) => {
+            eprintln!("Webhooks disabled: {}", e);
+            return;
+        }
+    };
+
+    loop {
+        let delivered = deliver_due(&client, Utc::now()).await.unwrap_or_else(|e| {
+            eprintln!("Failed to deliver webhooks: {}", e);
+            0
+        });
+        // A full batch means there is probably more waiting
+        if delivered < BATCH_SIZE {
+            sleep(POLL_INTERVAL).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{EventKind, WebhookDelivery};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+    use tokio::sync::oneshot;
+
+    /// A received request, headers lowercased
+    struct Captured {
+        headers: Vec<(String, String)>,
+        body: String,
+    }
+
+    impl Captured {
+        fn header(&self, name: &str) -> Option<&str> {
+            self.headers
+                .iter()
+                .find(|(n, _)| n == &name.to_lowercase())
+                .map(|(_, v)| v.as_str())
+        }
+    }
+
+    /// Stand-in receiver that answers one request with `status`
+    async fn stand_in(status: u16) -> (String, oneshot::Receiver<Captured>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let (tx, rx) = oneshot::channel();
+
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut data = Vec::new();
+            let mut buffer = [0u8; 4096];
+            let (head_end, content_length) = loop {
+                let n = stream.read(&mut buffer).await.unwrap();
+                data.extend_from_slice(&buffer[..n]);
+                let text = String::from_utf8_lossy(&data);
+                if let Some(end) = text.find("\r\n\r\n") {
+                    let length = text[..end]
+                        .lines()
+                        .find_map(|l| l.to_lowercase().strip_prefix("content-length:").map(|v| v.trim().parse::<usize>().unwrap()))
+                        .unwrap_or(0);
+                    break (end + 4, length);
+                }
+            };
+            while data.len() < head_end + content_length {
+                let n = stream.read(&mut buffer).await.unwrap();
+                data.extend_from_slice(&buffer[..n]);
+            }
+
+            let text = String::from_utf8(data).unwrap();
+            let headers = text[..head_end]
+                .lines()
+                .skip(1)
+                .filter_map(|l| l.split_once(':'))
+                .map(|(n, v)| (n.trim().to_lowercase(), v.trim().to_string()))
+                .collect();
+            let body = text[head_end..].to_string();
+
+            let response = format!("HTTP/1.1 {} Stand-in\r\ncontent-length: 0\r\nconnection: close\r\n\r\n", status);
+            stream.write_all(response.as_bytes()).await.unwrap();
+            let _ = tx.send(Captured { headers, body });
+        });
+
+        (url, rx)
+    }
+
+    fn queued(url: String, secret: Option<&str>) -> QueuedDelivery {
+        QueuedDelivery {
+            delivery: WebhookDelivery {
+                id: 7,
+                webhook_id: 1,
+                event_id: 42,
+                reminder_id: 3,
+                kind: EventKind::Triggered,
+                status: DeliveryStatus::Pending,
+                attempts: 0,
+                next_attempt_at: None,
+                last_attempt_at: None,
+                response_status: None,
+                last_error: None,
+                created_at: "2026-01-05T09:00:00.000Z".to_string(),
+            },
+            url,
+            secret: secret.map(str::to_string),
+            payload: r#"{"event":"triggered","reminder":{"id":3,"name":"Stretch"}}"#.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn posts_signed_payload() {
+        let (url, received) = stand_in(204).await;
+        let delivery = queued(url, Some("s3cret"));
+
+        let outcome = send(&client().unwrap(), &delivery).await;
+
+        assert_eq!(outcome, Outcome::Delivered { status: 204 });
+        let request = received.await.unwrap();
+        assert_eq!(request.body, delivery.payload);
+        assert_eq!(request.header("content-type"), Some("application/json"));
+        assert_eq!(request.header(EVENT_HEADER), Some("triggered"));
+        assert_eq!(request.header(DELIVERY_HEADER), Some("7"));
+        assert_eq!(
+            request.header(SIGNATURE_HEADER),
+            Some(sign("s3cret", delivery.payload.as_bytes()).as_str())
+        );
+    }
+
+    #[tokio::test]
+    async fn leaves_payload_unsigned_without_secret() {
+        let (url, received) = stand_in(200).await;
+
+        send(&client().unwrap(), &queued(url, None)).await;
+
+        assert_eq!(received.await.unwrap().header(SIGNATURE_HEADER), None);
+    }
+
+    #[tokio::test]
+    async fn retries_server_errors_and_rate_limits() {
+        for status in [500, 503, 429, 408] {
+            let (url, _received) = stand_in(status).await;
+            let outcome = send(&client().unwrap(), &queued(url, None)).await;
+            assert!(
+                matches!(outcome, Outcome::Retry { status: Some(s), .. } if s == status),
+                "{} gave {:?}",
+                status,
+                outcome
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn gives_up_on_client_errors_and_redirects() {
+        for status in [400, 404, 410, 301] {
+            let (url, _received) = stand_in(status).await;
+            let outcome = send(&client().unwrap(), &queued(url, None)).await;
+            assert!(
+                matches!(outcome, Outcome::Rejected { status: s, .. } if s == status),
+                "{} gave {:?}",
+                status,
+                outcome
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn retries_when_the_receiver_is_down() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        drop(listener);
+
+        let outcome = send(&client().unwrap(), &queued(url, None)).await;
+
+        assert!(matches!(outcome, Outcome::Retry { status: None, .. }), "{:?}", outcome);
+    }
+
+    #[test]
+    fn backs_off_exponentially_then_gives_up() {
+        let delays: Vec<Option<i64>> = (1..=MAX_ATTEMPTS)
+            .map(|attempts| retry_delay(attempts).map(|d| d.num_seconds()))
+            .collect();
+
+        assert_eq!(
+            delays,
+            vec![Some(30), Some(60), Some(120), Some(240), Some(480), Some(960), Some(1920), None]
+        );
+    }
+}
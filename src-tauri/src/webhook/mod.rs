@@ -0,0 +1,5 @@
+mod delivery;
+mod signing;
+
+pub use delivery::*;
+pub use signing::*;
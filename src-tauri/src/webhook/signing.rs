@@ -0,0 +1,28 @@
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+/// Header carrying `sha256=<hex>`, the HMAC-SHA256 of the raw request body
+/// keyed with the webhook's secret
+pub const SIGNATURE_HEADER: &str = "X-Multitask-Signature";
+pub const EVENT_HEADER: &str = "X-Multitask-Event";
+pub const DELIVERY_HEADER: &str = "X-Multitask-Delivery";
+
+/// The value of the signature header for `body`
+pub fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signs_like_other_hmac_sha256_implementations() {
+        assert_eq!(
+            sign("key", b"The quick brown fox jumps over the lazy dog"),
+            "sha256=f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"
+        );
+    }
+}
@@ -1,5 +1,13 @@
 import { useState, useEffect } from "react";
-import { Reminder, CreateReminderData, UpdateReminderData } from "../lib/tauri";
+import {
+  Reminder,
+  CreateReminderData,
+  UpdateReminderData,
+  CatchUpPolicy,
+  DeliveryStyle,
+  EscalationStep,
+  errorMessage
+} from "../lib/tauri";
 import { SoundType, SOUND_OPTIONS, previewSound } from "../lib/sounds";
 
 interface ReminderFormProps {
@@ -18,6 +26,30 @@ const DAYS_OF_WEEK = [
   { value: 6, label: "Sun" },
 ];
 
+const DELIVERY_OPTIONS: { value: DeliveryStyle["kind"]; label: string }[] = [
+  { value: "popup", label: "Popup" },
+  { value: "notification", label: "Notification" },
+  { value: "notification_then_popup", label: "Notification, then popup" },
+  { value: "silent", label: "Silent" },
+];
+
+const ESCALATION_OPTIONS: { value: EscalationStep["action"]["kind"]; label: string }[] = [
+  { value: "replay_sound", label: "Play the sound again" },
+  { value: "refocus", label: "Bring the popup to the front" },
+  { value: "change_sound", label: "Switch to a louder sound" },
+  { value: "webhook", label: "Notify webhooks" },
+];
+
+const CATCH_UP_OPTIONS: { value: CatchUpPolicy["kind"]; label: string }[] = [
+  { value: "skip", label: "Skip them" },
+  { value: "fire_once", label: "Remind me once" },
+  { value: "fire_each", label: "Remind me for each" },
+];
+
+// Intl.supportedValuesOf is newer than the ES2020 lib and not in every webview
+const TIME_ZONES: string[] =
+  (Intl as unknown as { supportedValuesOf?: (key: string) => string[] }).supportedValuesOf?.("timeZone") ?? [];
+
 const QUICK_INTERVALS = [
   { value: 15, label: "15 min" },
   { value: 30, label: "30 min" },
@@ -39,7 +71,20 @@ export function ReminderForm({ reminder, onSubmit, onCancel }: ReminderFormProps
   const [endTime, setEndTime] = useState("18:00");
   const [useDaysFilter, setUseDaysFilter] = useState(false);
   const [activeDays, setActiveDays] = useState<number[]>([0, 1, 2, 3, 4]);
+  const [useTimezone, setUseTimezone] = useState(false);
+  const [timezone, setTimezone] = useState(
+    () => Intl.DateTimeFormat().resolvedOptions().timeZone || "UTC"
+  );
   const [sound, setSound] = useState<SoundType>("chime");
+  const [delivery, setDelivery] = useState<DeliveryStyle["kind"]>("popup");
+  const [popupAfter, setPopupAfter] = useState("10");
+  const [escalation, setEscalation] = useState<EscalationStep[]>([]);
+  const [catchUp, setCatchUp] = useState<CatchUpPolicy["kind"]>("skip");
+  const [catchUpMax, setCatchUpMax] = useState("5");
+  const [runCommand, setRunCommand] = useState(false);
+  const [commandProgram, setCommandProgram] = useState("");
+  const [commandArgs, setCommandArgs] = useState("");
+  const [commandTimeout, setCommandTimeout] = useState("30");
   const [submitting, setSubmitting] = useState(false);
   const [error, setError] = useState<string | null>(null);
 
@@ -50,6 +95,15 @@ export function ReminderForm({ reminder, onSubmit, onCancel }: ReminderFormProps
       setIntervalMinutes(reminder.interval_minutes);
       setEnabled(reminder.enabled);
       setSound((reminder.sound as SoundType) || "chime");
+      setDelivery(reminder.delivery_style.kind);
+      if (reminder.delivery_style.kind === "notification_then_popup") {
+        setPopupAfter(String(reminder.delivery_style.minutes));
+      }
+      setEscalation(reminder.escalation);
+      setCatchUp(reminder.catch_up.kind);
+      if (reminder.catch_up.kind === "fire_each") {
+        setCatchUpMax(String(reminder.catch_up.max));
+      }
       
       if (!QUICK_INTERVALS.find(q => q.value === reminder.interval_minutes)) {
         setUseCustomInterval(true);
@@ -66,6 +120,18 @@ export function ReminderForm({ reminder, onSubmit, onCancel }: ReminderFormProps
         setUseDaysFilter(true);
         setActiveDays(reminder.active_days);
       }
+      
+      if (reminder.timezone) {
+        setUseTimezone(true);
+        setTimezone(reminder.timezone);
+      }
+      
+      if (reminder.command) {
+        setRunCommand(reminder.command.enabled);
+        setCommandProgram(reminder.command.program);
+        setCommandArgs(reminder.command.args.join("\n"));
+        setCommandTimeout(String(reminder.command.timeout_seconds));
+      }
     }
   }, [reminder]);
 
@@ -87,6 +153,34 @@ export function ReminderForm({ reminder, onSubmit, onCancel }: ReminderFormProps
       return;
     }
     
+    // A command that was switched off, e.g. by an import, is kept as it is
+    const command = commandProgram.trim()
+      ? {
+          program: commandProgram.trim(),
+          args: commandArgs.split("\n").filter(arg => arg !== ""),
+          timeout_seconds: parseInt(commandTimeout, 10) || 30,
+          enabled: runCommand,
+        }
+      : null;
+    
+    if (runCommand && !command) {
+      setError("Choose the program to run");
+      return;
+    }
+    
+    if (useTimezone && !timezone.trim()) {
+      setError("Choose a time zone");
+      return;
+    }
+    
+    const deliveryStyle: DeliveryStyle = delivery === "notification_then_popup"
+      ? { kind: delivery, minutes: parseInt(popupAfter, 10) || 10 }
+      : { kind: delivery };
+    
+    const catchUpPolicy: CatchUpPolicy = catchUp === "fire_each"
+      ? { kind: catchUp, max: parseInt(catchUpMax, 10) || 5 }
+      : { kind: catchUp };
+    
     const data: CreateReminderData | UpdateReminderData = {
       ...(reminder ? { id: reminder.id } : {}),
       name: name.trim(),
@@ -97,13 +191,18 @@ export function ReminderForm({ reminder, onSubmit, onCancel }: ReminderFormProps
       active_end_time: useTimeWindow ? endTime : null,
       active_days: useDaysFilter && activeDays.length > 0 ? activeDays : null,
       sound,
+      command,
+      delivery_style: deliveryStyle,
+      escalation,
+      catch_up: catchUpPolicy,
+      timezone: useTimezone ? timezone.trim() : null,
     } as CreateReminderData | UpdateReminderData;
     
     try {
       setSubmitting(true);
       await onSubmit(data);
     } catch (e) {
-      setError(e instanceof Error ? e.message : String(e));
+      setError(errorMessage(e));
     } finally {
       setSubmitting(false);
     }
@@ -117,6 +216,19 @@ export function ReminderForm({ reminder, onSubmit, onCancel }: ReminderFormProps
     );
   };
 
+  const addEscalationStep = () => {
+    const last = escalation[escalation.length - 1];
+    const afterMinutes = last ? last.after_minutes + 5 : 5;
+    setEscalation([...escalation, { after_minutes: afterMinutes, action: { kind: "replay_sound" } }]);
+  };
+
+  const updateEscalationStep = (index: number, step: EscalationStep) => {
+    setEscalation(escalation.map((s, i) => (i === index ? step : s)));
+  };
+
+  const escalationAction = (kind: EscalationStep["action"]["kind"]): EscalationStep["action"] =>
+    kind === "change_sound" ? { kind, sound: "alert" } : { kind };
+
   const handleSoundChange = (newSound: SoundType) => {
     setSound(newSound);
     if (newSound !== "none") {
@@ -232,6 +344,41 @@ export function ReminderForm({ reminder, onSubmit, onCancel }: ReminderFormProps
         </div>
         <p className="mt-1 text-xs text-gray-500">Click to preview sound</p>
       </div>
+
+      <div>
+        <label className="block text-sm font-medium text-purple-200 mb-2">
+          Show as
+        </label>
+        <div className="grid grid-cols-2 gap-2">
+          {DELIVERY_OPTIONS.map((option) => (
+            <button
+              key={option.value}
+              type="button"
+              onClick={() => setDelivery(option.value)}
+              className={`px-3 py-2 rounded-lg border text-sm font-medium transition-all ${
+                delivery === option.value
+                  ? "bg-gradient-to-r from-purple-600 to-pink-600 border-transparent text-white shadow-lg shadow-purple-500/25"
+                  : "bg-gray-800/50 border-purple-500/30 text-gray-300 hover:border-purple-400"
+              }`}
+            >
+              {option.label}
+            </button>
+          ))}
+        </div>
+        {delivery === "notification_then_popup" && (
+          <div className="mt-2 flex items-center gap-2">
+            <span className="text-sm text-gray-400">Popup after</span>
+            <input
+              type="number"
+              value={popupAfter}
+              onChange={(e) => setPopupAfter(e.target.value)}
+              min="1"
+              className="w-20 px-2 py-1 border border-purple-500/30 rounded bg-gray-800/50 text-white text-sm"
+            />
+            <span className="text-sm text-gray-400">minutes without an answer</span>
+          </div>
+        )}
+      </div>
       
       <div className="space-y-4 pt-4 border-t border-purple-500/20">
         <h4 className="text-sm font-medium text-purple-200">
@@ -303,6 +450,188 @@ export function ReminderForm({ reminder, onSubmit, onCancel }: ReminderFormProps
           )}
         </div>
         
+        <div>
+          <label className="flex items-center gap-2 cursor-pointer">
+            <input
+              type="checkbox"
+              checked={useTimezone}
+              onChange={(e) => setUseTimezone(e.target.checked)}
+              className="w-4 h-4 rounded border-purple-500/30 bg-gray-800 text-purple-500 focus:ring-purple-500"
+            />
+            <span className="text-sm text-gray-300">
+              Use a specific time zone
+            </span>
+          </label>
+          
+          {useTimezone && (
+            <div className="mt-2 ml-6">
+              <input
+                type="text"
+                list="reminder-time-zones"
+                value={timezone}
+                onChange={(e) => setTimezone(e.target.value)}
+                placeholder="Europe/Berlin"
+                className="w-56 px-2 py-1 border border-purple-500/30 rounded bg-gray-800/50 text-white text-sm"
+              />
+              <datalist id="reminder-time-zones">
+                {TIME_ZONES.map((zone) => (
+                  <option key={zone} value={zone} />
+                ))}
+              </datalist>
+            </div>
+          )}
+        </div>
+        
+        <div>
+          <span className="text-sm text-gray-300">
+            If it stays unanswered
+          </span>
+          {escalation.map((step, index) => (
+            <div key={index} className="mt-2 ml-6 flex flex-wrap items-center gap-2">
+              <span className="text-sm text-gray-400">after</span>
+              <input
+                type="number"
+                value={step.after_minutes}
+                onChange={(e) =>
+                  updateEscalationStep(index, { ...step, after_minutes: parseInt(e.target.value, 10) || 1 })
+                }
+                min="1"
+                className="w-16 px-2 py-1 border border-purple-500/30 rounded bg-gray-800/50 text-white text-sm"
+              />
+              <span className="text-sm text-gray-400">min</span>
+              <select
+                value={step.action.kind}
+                onChange={(e) =>
+                  updateEscalationStep(index, {
+                    ...step,
+                    action: escalationAction(e.target.value as EscalationStep["action"]["kind"])
+                  })
+                }
+                className="px-2 py-1 border border-purple-500/30 rounded bg-gray-800/50 text-white text-sm"
+              >
+                {ESCALATION_OPTIONS.map((option) => (
+                  <option key={option.value} value={option.value}>
+                    {option.label}
+                  </option>
+                ))}
+              </select>
+              {step.action.kind === "change_sound" && (
+                <select
+                  value={step.action.sound}
+                  onChange={(e) =>
+                    updateEscalationStep(index, {
+                      ...step,
+                      action: { kind: "change_sound", sound: e.target.value as SoundType }
+                    })
+                  }
+                  className="px-2 py-1 border border-purple-500/30 rounded bg-gray-800/50 text-white text-sm"
+                >
+                  {SOUND_OPTIONS.filter((option) => option.value !== "none").map((option) => (
+                    <option key={option.value} value={option.value}>
+                      {option.label}
+                    </option>
+                  ))}
+                </select>
+              )}
+              <button
+                type="button"
+                onClick={() => setEscalation(escalation.filter((_, i) => i !== index))}
+                className="text-sm text-gray-500 hover:text-red-400"
+              >
+                Remove
+              </button>
+            </div>
+          ))}
+          <button
+            type="button"
+            onClick={addEscalationStep}
+            className="mt-2 ml-6 block text-sm text-purple-400 hover:text-purple-300"
+          >
+            Add escalation step
+          </button>
+        </div>
+        
+        <div>
+          <span className="text-sm text-gray-300">
+            Missed while the app was closed
+          </span>
+          <div className="mt-2 ml-6 flex flex-wrap items-center gap-2">
+            <select
+              value={catchUp}
+              onChange={(e) => setCatchUp(e.target.value as CatchUpPolicy["kind"])}
+              className="px-2 py-1 border border-purple-500/30 rounded bg-gray-800/50 text-white text-sm"
+            >
+              {CATCH_UP_OPTIONS.map((option) => (
+                <option key={option.value} value={option.value}>
+                  {option.label}
+                </option>
+              ))}
+            </select>
+            {catchUp === "fire_each" && (
+              <>
+                <span className="text-sm text-gray-400">up to</span>
+                <input
+                  type="number"
+                  value={catchUpMax}
+                  onChange={(e) => setCatchUpMax(e.target.value)}
+                  min="1"
+                  max="50"
+                  className="w-16 px-2 py-1 border border-purple-500/30 rounded bg-gray-800/50 text-white text-sm"
+                />
+                <span className="text-sm text-gray-400">times</span>
+              </>
+            )}
+          </div>
+        </div>
+        
+        <div>
+          <label className="flex items-center gap-2 cursor-pointer">
+            <input
+              type="checkbox"
+              checked={runCommand}
+              onChange={(e) => setRunCommand(e.target.checked)}
+              className="w-4 h-4 rounded border-purple-500/30 bg-gray-800 text-purple-500 focus:ring-purple-500"
+            />
+            <span className="text-sm text-gray-300">
+              Run a program when it fires
+            </span>
+          </label>
+          
+          {(runCommand || commandProgram) && (
+            <div className="mt-2 ml-6 space-y-2">
+              <input
+                type="text"
+                value={commandProgram}
+                onChange={(e) => setCommandProgram(e.target.value)}
+                placeholder="Program, e.g. /usr/bin/notify-send"
+                className="w-full px-2 py-1 border border-purple-500/30 rounded bg-gray-800/50 text-white text-sm placeholder-gray-500"
+              />
+              <textarea
+                value={commandArgs}
+                onChange={(e) => setCommandArgs(e.target.value)}
+                placeholder="Arguments, one per line"
+                rows={2}
+                className="w-full px-2 py-1 border border-purple-500/30 rounded bg-gray-800/50 text-white text-sm placeholder-gray-500"
+              />
+              <div className="flex items-center gap-2">
+                <input
+                  type="number"
+                  value={commandTimeout}
+                  onChange={(e) => setCommandTimeout(e.target.value)}
+                  min="1"
+                  max="600"
+                  className="w-20 px-2 py-1 border border-purple-500/30 rounded bg-gray-800/50 text-white text-sm"
+                />
+                <span className="text-sm text-gray-400">seconds until it is stopped</span>
+              </div>
+              <p className="text-xs text-gray-500">
+                Runs without a shell. The reminder is passed as REMINDER_ID, REMINDER_NAME and
+                REMINDER_MESSAGE environment variables.
+              </p>
+            </div>
+          )}
+        </div>
+        
         <label className="flex items-center gap-2 cursor-pointer">
           <input
             type="checkbox"
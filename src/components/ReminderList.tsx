@@ -1,5 +1,6 @@
-import { Reminder } from "../lib/tauri";
-import { formatDistanceToNow, parseISO, addMinutes } from "date-fns";
+import { useEffect, useState } from "react";
+import { Reminder, getUpcomingTriggers } from "../lib/tauri";
+import { format, isToday, parseISO } from "date-fns";
 import { SOUND_OPTIONS } from "../lib/sounds";
 
 interface ReminderListProps {
@@ -9,21 +10,19 @@ interface ReminderListProps {
   onToggle: (id: number, enabled: boolean) => void;
 }
 
-function getNextTriggerTime(reminder: Reminder): string {
+/** How many upcoming times are shown per reminder */
+const UPCOMING_COUNT = 3;
+
+function getNextTriggerTime(reminder: Reminder, upcoming: string[] | undefined): string {
   if (!reminder.enabled) return "Disabled";
+  if (!upcoming) return "…";
+  if (upcoming.length === 0) return "Not scheduled";
   
-  const lastTriggered = reminder.last_triggered 
-    ? parseISO(reminder.last_triggered)
-    : parseISO(reminder.created_at);
-  
-  const nextTrigger = addMinutes(lastTriggered, reminder.interval_minutes);
-  const now = new Date();
-  
-  if (nextTrigger <= now) {
-    return "Soon";
-  }
-  
-  return formatDistanceToNow(nextTrigger, { addSuffix: true });
+  const times = upcoming.map((at) => {
+    const time = parseISO(at);
+    return format(time, isToday(time) ? "HH:mm" : "EEE HH:mm");
+  });
+  return `Next: ${times.join(", ")}`;
 }
 
 function formatInterval(minutes: number): string {
@@ -44,6 +43,34 @@ function getSoundLabel(sound: string | undefined): string {
 }
 
 export function ReminderList({ reminders, onEdit, onDelete, onToggle }: ReminderListProps) {
+  const [upcoming, setUpcoming] = useState<Record<number, string[]>>({});
+
+  useEffect(() => {
+    let cancelled = false;
+    
+    const load = async () => {
+      const entries = await Promise.all(
+        reminders
+          .filter((r) => r.enabled)
+          .map(async (r) => {
+            const triggers = await getUpcomingTriggers(r.id, UPCOMING_COUNT).catch(() => []);
+            return [r.id, triggers.map((t) => t.at)] as const;
+          })
+      );
+      if (!cancelled) {
+        setUpcoming(Object.fromEntries(entries));
+      }
+    };
+    
+    load();
+    // Times move on as reminders fire
+    const timer = setInterval(load, 60_000);
+    return () => {
+      cancelled = true;
+      clearInterval(timer);
+    };
+  }, [reminders]);
+
   if (reminders.length === 0) {
     return (
       <div className="text-center py-12">
@@ -105,7 +132,7 @@ export function ReminderList({ reminders, onEdit, onDelete, onToggle }: Reminder
                   <svg className="w-3.5 h-3.5" fill="none" stroke="currentColor" viewBox="0 0 24 24">
                     <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={2} d="M13 10V3L4 14h7v7l9-11h-7z" />
                   </svg>
-                  {getNextTriggerTime(reminder)}
+                  {getNextTriggerTime(reminder, upcoming[reminder.id])}
                 </span>
                 {reminder.sound && reminder.sound !== "none" && (
                   <span className="flex items-center gap-1">
@@ -6,27 +6,31 @@ import { GameType, getRandomGame } from "./types";
 
 interface GameContainerProps {
   onWin: () => void;
+  /** Called after every round, e.g. to record it in the reminder history */
+  onResult?: (game: GameType, won: boolean) => void;
 }
 
-export function GameContainer({ onWin }: GameContainerProps) {
+export function GameContainer({ onWin, onResult }: GameContainerProps) {
   const [currentGame, setCurrentGame] = useState<GameType>(getRandomGame);
   const [gameKey, setGameKey] = useState(0);
   const [lossStreak, setLossStreak] = useState(0);
 
   const handleLose = useCallback(() => {
+    onResult?.(currentGame, false);
     // Increment loss streak and pick a new random game
     setTimeout(() => {
       setLossStreak(prev => prev + 1);
       setCurrentGame(getRandomGame());
       setGameKey(k => k + 1);
     }, 500);
-  }, []);
+  }, [currentGame, onResult]);
 
   const handleWin = useCallback(() => {
+    onResult?.(currentGame, true);
     // Reset loss streak on win
     setLossStreak(0);
     onWin();
-  }, [onWin]);
+  }, [currentGame, onWin, onResult]);
 
   const renderGame = () => {
     const props = { onWin: handleWin, onLose: handleLose, lossStreak };
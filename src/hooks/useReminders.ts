@@ -8,6 +8,7 @@ import {
   updateReminder as apiUpdateReminder,
   deleteReminder as apiDeleteReminder,
   toggleReminder as apiToggleReminder,
+  errorMessage,
 } from "../lib/tauri";
 
 export function useReminders() {
@@ -22,7 +23,7 @@ export function useReminders() {
       const data = await fetchReminders();
       setReminders(data);
     } catch (e) {
-      setError(e instanceof Error ? e.message : String(e));
+      setError(errorMessage(e));
     } finally {
       setLoading(false);
     }
@@ -38,7 +39,7 @@ export function useReminders() {
       setReminders(prev => [newReminder, ...prev]);
       return newReminder;
     } catch (e) {
-      throw e instanceof Error ? e : new Error(String(e));
+      throw e instanceof Error ? e : new Error(errorMessage(e));
     }
   }, []);
 
@@ -48,7 +49,7 @@ export function useReminders() {
       setReminders(prev => prev.map(r => r.id === updated.id ? updated : r));
       return updated;
     } catch (e) {
-      throw e instanceof Error ? e : new Error(String(e));
+      throw e instanceof Error ? e : new Error(errorMessage(e));
     }
   }, []);
 
@@ -57,7 +58,7 @@ export function useReminders() {
       await apiDeleteReminder(id);
       setReminders(prev => prev.filter(r => r.id !== id));
     } catch (e) {
-      throw e instanceof Error ? e : new Error(String(e));
+      throw e instanceof Error ? e : new Error(errorMessage(e));
     }
   }, []);
 
@@ -67,7 +68,7 @@ export function useReminders() {
       setReminders(prev => prev.map(r => r.id === updated.id ? updated : r));
       return updated;
     } catch (e) {
-      throw e instanceof Error ? e : new Error(String(e));
+      throw e instanceof Error ? e : new Error(errorMessage(e));
     }
   }, []);
 
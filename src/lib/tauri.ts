@@ -1,17 +1,69 @@
 import { invoke } from "@tauri-apps/api/core";
 import { SoundType } from "./sounds";
 
+export type Schedule =
+  | { kind: "interval" }
+  | { kind: "daily_times"; times: string[] }
+  | { kind: "cron"; expression: string }
+  | { kind: "rrule"; rule: string };
+
+/** Program run without a shell when the reminder fires */
+export interface ReminderCommand {
+  program: string;
+  args: string[];
+  timeout_seconds: number;
+  /** Nothing runs until this is switched on, imports switch it off */
+  enabled: boolean;
+}
+
+export type DeliveryStyle =
+  | { kind: "popup" }
+  | { kind: "notification" }
+  | { kind: "notification_then_popup"; minutes: number }
+  | { kind: "silent" };
+
+export type EscalationAction =
+  | { kind: "replay_sound" }
+  | { kind: "refocus" }
+  | { kind: "change_sound"; sound: SoundType }
+  | { kind: "webhook" };
+
+/** Applied while a fired reminder stays unanswered */
+export interface EscalationStep {
+  after_minutes: number;
+  action: EscalationAction;
+}
+
+/** What happens to occurrences missed while the app was closed */
+export type CatchUpPolicy =
+  | { kind: "skip" }
+  | { kind: "fire_once" }
+  | { kind: "fire_each"; max: number };
+
 export interface Reminder {
   id: number;
   name: string;
   message: string | null;
   interval_minutes: number;
+  schedule: Schedule;
+  /** Set for one-shot reminders */
+  due_at: string | null;
   enabled: boolean;
   active_start_time: string | null;
   active_end_time: string | null;
   active_days: number[] | null;
   sound: SoundType;
+  command: ReminderCommand | null;
+  delivery_style: DeliveryStyle;
+  escalation: EscalationStep[];
+  catch_up: CatchUpPolicy;
+  /** IANA zone for the times of day, null uses the default zone */
+  timezone: string | null;
   last_triggered: string | null;
+  snoozed_until: string | null;
+  snooze_count: number;
+  /** Set while the reminder waits to be dismissed or snoozed */
+  pending_since: string | null;
   created_at: string;
 }
 
@@ -19,17 +71,188 @@ export interface CreateReminderData {
   name: string;
   message: string | null;
   interval_minutes: number;
+  /** Defaults to an interval schedule */
+  schedule?: Schedule;
+  due_at?: string | null;
   enabled: boolean;
   active_start_time: string | null;
   active_end_time: string | null;
   active_days: number[] | null;
   sound: SoundType;
+  command?: ReminderCommand | null;
+  /** Defaults to the popup */
+  delivery_style?: DeliveryStyle;
+  escalation?: EscalationStep[];
+  /** Defaults to skipping them */
+  catch_up?: CatchUpPolicy;
+  /** Defaults to the zone from the settings */
+  timezone?: string | null;
 }
 
 export interface UpdateReminderData extends CreateReminderData {
   id: number;
 }
 
+export type EventKind = "triggered" | "dismissed" | "snoozed" | "game_won" | "game_lost" | "missed" | "command_ran" | "escalated";
+
+export interface ReminderEvent {
+  id: number;
+  reminder_id: number;
+  kind: EventKind;
+  occurred_at: string;
+  metadata: Record<string, unknown> | null;
+}
+
+export interface Webhook {
+  id: number;
+  /** null for global webhooks, which fire for every reminder */
+  reminder_id: number | null;
+  url: string;
+  /** Key for the X-Multitask-Signature HMAC, unsigned when null */
+  secret: string | null;
+  events: EventKind[];
+  enabled: boolean;
+  created_at: string;
+}
+
+export type CreateWebhookData = Omit<Webhook, "id" | "created_at">;
+
+export type UpdateWebhookData = Omit<Webhook, "created_at">;
+
+export type DeliveryStatus = "pending" | "delivered" | "failed";
+
+export interface WebhookDelivery {
+  id: number;
+  webhook_id: number;
+  event_id: number;
+  reminder_id: number;
+  kind: EventKind;
+  status: DeliveryStatus;
+  attempts: number;
+  next_attempt_at: string | null;
+  last_attempt_at: string | null;
+  response_status: number | null;
+  last_error: string | null;
+  created_at: string;
+}
+
+export interface StatsRange {
+  from: string | null;
+  to: string | null;
+}
+
+export interface HeatmapCell {
+  triggered: number;
+  dismissed: number;
+}
+
+export interface ReminderStats {
+  reminder_id: number;
+  range: StatsRange;
+  trigger_count: number;
+  dismiss_count: number;
+  missed_count: number;
+  snooze_count: number;
+  dismiss_rate: number | null;
+  average_seconds_to_dismiss: number | null;
+  snooze_frequency: number | null;
+  longest_on_time_streak: number;
+  games_won: number;
+  games_lost: number;
+  /** Indexed by hour in the default time zone, 0 - 23 */
+  by_hour: HeatmapCell[];
+  /** Indexed by weekday in the default time zone, Monday = 0 */
+  by_weekday: HeatmapCell[];
+  /** heatmap[weekday][hour] */
+  heatmap: HeatmapCell[][];
+}
+
+export type PausePolicy = "drop" | "fire_on_resume";
+
+export interface Settings {
+  /** null allows unlimited snoozing */
+  max_snooze_count: number | null;
+  pause_policy: PausePolicy;
+  /** Serve the HTTP API on 127.0.0.1:api_port */
+  api_enabled: boolean;
+  api_port: number;
+  /** IANA zone for reminders without their own, null follows the system */
+  default_timezone: string | null;
+}
+
+export interface PauseState {
+  paused: boolean;
+  /** null while paused indefinitely */
+  until: string | null;
+}
+
+export interface PlannedTrigger {
+  reminder_id: number;
+  name: string;
+  at: string;
+}
+
+export interface SchedulerStatus {
+  running: boolean;
+  pause: PauseState;
+  enabled_reminders: number;
+  /** Fired and waiting to be dismissed or snoozed */
+  pending_reminders: number;
+  /** Soonest first */
+  next_triggers: PlannedTrigger[];
+}
+
+export type ImportMode = "merge" | "replace";
+
+export interface ImportReport {
+  mode: ImportMode;
+  dry_run: boolean;
+  created: string[];
+  updated: string[];
+  unchanged: string[];
+  deleted: string[];
+  skipped: { name: string; reason: string }[];
+  settings_changed: boolean;
+  /** Reminders whose command was switched off on import */
+  commands_disabled: string[];
+}
+
+export interface IcsExportReport {
+  exported: number;
+  skipped: { name: string; reason: string }[];
+}
+
+export interface IcsImportReport {
+  dry_run: boolean;
+  created: string[];
+  skipped: { name: string; reason: string }[];
+  /** Parts of imported events that were ignored */
+  warnings: string[];
+  /** Number of events without an alarm */
+  ignored: number;
+}
+
+export type AppErrorKind = "db" | "not_found" | "validation" | "scheduler" | "window" | "io";
+
+/** What every command rejects with */
+export interface AppError {
+  kind: AppErrorKind;
+  message: string;
+  /** The offending input for validation errors */
+  field: string | null;
+}
+
+export function isAppError(e: unknown): e is AppError {
+  return typeof e === "object" && e !== null && "kind" in e && "message" in e;
+}
+
+export function errorMessage(e: unknown): string {
+  if (isAppError(e) || e instanceof Error) {
+    return e.message;
+  }
+  return String(e);
+}
+
 export async function getReminders(): Promise<Reminder[]> {
   return invoke("get_reminders");
 }
@@ -42,6 +265,16 @@ export async function createReminder(reminder: CreateReminderData): Promise<Remi
   return invoke("create_reminder", { reminder });
 }
 
+/** `when` is a date/time or a phrase like "in 20 minutes" or "tomorrow 9am" */
+export async function createOneShotReminder(
+  name: string,
+  message: string | null,
+  when: string,
+  sound: SoundType | null = null
+): Promise<Reminder> {
+  return invoke("create_one_shot_reminder", { name, message, when, sound });
+}
+
 export async function updateReminder(reminder: UpdateReminderData): Promise<Reminder> {
   return invoke("update_reminder", { reminder });
 }
@@ -58,10 +291,131 @@ export async function dismissReminder(id: number): Promise<void> {
   return invoke("dismiss_reminder", { id });
 }
 
-export async function snoozeReminder(id: number, minutes: number): Promise<void> {
+export async function snoozeReminder(id: number, minutes: number): Promise<Reminder> {
   return invoke("snooze_reminder", { id, minutes });
 }
 
+/** Reminders that fired and haven't been answered, longest waiting first */
+export async function getPendingReminders(): Promise<Reminder[]> {
+  return invoke("get_pending_reminders");
+}
+
+export async function recordGameResult(id: number, game: string, won: boolean): Promise<void> {
+  return invoke("record_game_result", { id, game, won });
+}
+
+/** Events in chronological order, optionally for one reminder and an RFC 3339 range */
+export async function getHistory(
+  reminderId: number | null = null,
+  from: string | null = null,
+  to: string | null = null
+): Promise<ReminderEvent[]> {
+  return invoke("get_history", { reminderId, from, to });
+}
+
+export async function getReminderStats(id: number, range: StatsRange | null = null): Promise<ReminderStats> {
+  return invoke("get_reminder_stats", { id, range });
+}
+
+export async function getStats(range: StatsRange | null = null): Promise<ReminderStats[]> {
+  return invoke("get_stats", { range });
+}
+
+/** Writes all reminders and the settings to a JSON file, resolving to the number of reminders */
+export async function exportReminders(path: string): Promise<number> {
+  return invoke("export_reminders", { path });
+}
+
+export async function importReminders(
+  path: string,
+  mode: ImportMode = "merge",
+  dryRun = false
+): Promise<ImportReport> {
+  return invoke("import_reminders", { path, mode, dryRun });
+}
+
+/** Exports the given reminders, or every enabled one, as an iCalendar file */
+export async function exportIcs(path: string, ids: number[] | null = null): Promise<IcsExportReport> {
+  return invoke("export_ics", { path, ids });
+}
+
+/** Creates a reminder for every event with an alarm in an iCalendar file */
+export async function importIcs(path: string, dryRun = false): Promise<IcsImportReport> {
+  return invoke("import_ics", { path, dryRun });
+}
+
+export async function getWebhooks(reminderId: number | null = null): Promise<Webhook[]> {
+  return invoke("get_webhooks", { reminderId });
+}
+
+export async function createWebhook(webhook: CreateWebhookData): Promise<Webhook> {
+  return invoke("create_webhook", { webhook });
+}
+
+export async function updateWebhook(webhook: UpdateWebhookData): Promise<Webhook> {
+  return invoke("update_webhook", { webhook });
+}
+
+export async function deleteWebhook(id: number): Promise<void> {
+  return invoke("delete_webhook", { id });
+}
+
+/** The delivery log, newest first */
+export async function getWebhookDeliveries(
+  webhookId: number | null = null,
+  limit: number | null = null
+): Promise<WebhookDelivery[]> {
+  return invoke("get_webhook_deliveries", { webhookId, limit });
+}
+
+export async function getSettings(): Promise<Settings> {
+  return invoke("get_settings");
+}
+
+export async function updateSettings(settings: Settings): Promise<Settings> {
+  return invoke("update_settings", { settings });
+}
+
+/** Bearer token HTTP API clients have to send */
+export async function getApiToken(): Promise<string> {
+  return invoke("get_api_token");
+}
+
+export async function regenerateApiToken(): Promise<string> {
+  return invoke("regenerate_api_token");
+}
+
+export async function getPauseState(): Promise<PauseState> {
+  return invoke("get_pause_state");
+}
+
+/** Pauses all reminders for the given number of minutes, or indefinitely */
+export async function pauseReminders(minutes: number | null): Promise<void> {
+  return invoke("pause_reminders", { minutes });
+}
+
+export async function resumeReminders(): Promise<void> {
+  return invoke("resume_reminders");
+}
+
+/** Shows a reminder right away, outside its regular schedule */
+export async function triggerNow(id: number): Promise<void> {
+  return invoke("trigger_now", { id });
+}
+
+export async function getStatus(): Promise<SchedulerStatus> {
+  return invoke("get_status");
+}
+
+/** The next occurrences of one reminder, or of all enabled ones, soonest first, optionally up to an RFC 3339 time */
+export async function getUpcomingTriggers(
+  id: number | null = null,
+  count: number | null = null,
+  until: string | null = null
+): Promise<PlannedTrigger[]> {
+  return invoke("get_upcoming_triggers", { id, count, until });
+}
+
 export async function closePopup(): Promise<void> {
   return invoke("close_popup");
 }
@@ -1,8 +1,9 @@
 import { useState, useEffect } from "react";
 import { useSearchParams } from "react-router-dom";
 import { GameContainer } from "../games/GameContainer";
-import { dismissReminder, snoozeReminder } from "../lib/tauri";
+import { dismissReminder, errorMessage, recordGameResult, snoozeReminder } from "../lib/tauri";
 import { getCurrentWindow } from "@tauri-apps/api/window";
+import { getCurrentWebviewWindow } from "@tauri-apps/api/webviewWindow";
 import { playSound, SoundType } from "../lib/sounds";
 
 export function Popup() {
@@ -10,6 +11,7 @@ export function Popup() {
   const [dismissed, setDismissed] = useState(false);
   const [snoozed, setSnoozed] = useState(false);
   const [showSnoozeOptions, setShowSnoozeOptions] = useState(false);
+  const [snoozeError, setSnoozeError] = useState<string | null>(null);
 
   const id = parseInt(searchParams.get("id") || "0", 10);
   const name = decodeURIComponent(searchParams.get("name") || "Reminder");
@@ -26,6 +28,17 @@ export function Popup() {
     }
   }, [sound]);
 
+  // Escalation of an unanswered reminder, possibly with a louder sound
+  useEffect(() => {
+    const unlisten = getCurrentWebviewWindow().listen<{ sound: SoundType }>(
+      "reminder-escalated",
+      (event) => playSound(event.payload.sound)
+    );
+    return () => {
+      unlisten.then((stop) => stop());
+    };
+  }, []);
+
   const handleWin = async () => {
     setDismissed(true);
     try {
@@ -40,17 +53,26 @@ export function Popup() {
     }
   };
 
+  const handleGameResult = (game: string, won: boolean) => {
+    recordGameResult(id, game, won).catch((e) => {
+      console.error("Failed to record game result:", e);
+    });
+  };
+
   const handleSnooze = async (minutes: number) => {
-    setSnoozed(true);
     try {
       await snoozeReminder(id, minutes);
+      setSnoozed(true);
       // Close window
       setTimeout(async () => {
         const window = getCurrentWindow();
         await window.close();
       }, 500);
     } catch (e) {
+      // e.g. the snooze limit was reached - the game has to be won instead
       console.error("Failed to snooze:", e);
+      setSnoozeError(errorMessage(e));
+      setShowSnoozeOptions(false);
     }
   };
 
@@ -121,12 +143,16 @@ export function Popup() {
 
       {/* Game */}
       <div className="relative flex-1">
-        <GameContainer onWin={handleWin} />
+        <GameContainer onWin={handleWin} onResult={handleGameResult} />
       </div>
 
       {/* Snooze section */}
       <div className="relative mt-6 text-center">
-        {showSnoozeOptions ? (
+        {snoozeError ? (
+          <p className="text-sm text-amber-400">
+            {snoozeError}
+          </p>
+        ) : showSnoozeOptions ? (
           <div className="space-y-3 animate-fade-in">
             <p className="text-sm text-gray-400 mb-3">
               Snooze for: